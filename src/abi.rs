@@ -0,0 +1,234 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::failure::FailureClass;
+
+/// Type of one field of the public values, ABI-encoded as in `abi.encode(field, ...)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiType {
+    Bool,
+    /// Unsigned integer of this many bits
+    Uint(u16),
+    Bytes32,
+    Address,
+    /// Array of the element type, with a fixed length (`T[N]`) or a length-prefixed one (`T[]`)
+    Array(Box<AbiType>, Option<usize>),
+}
+
+impl AbiType {
+    /// Whether the value is stored behind an offset rather than in place
+    fn is_dynamic(&self) -> bool {
+        match self {
+            AbiType::Array(_, None) => true,
+            AbiType::Array(element, Some(_)) => element.is_dynamic(),
+            _ => false,
+        }
+    }
+
+    /// Bytes the value takes in the head of the enclosing tuple
+    fn head_size(&self) -> usize {
+        match self {
+            AbiType::Array(element, Some(len)) if !self.is_dynamic() => element.head_size().saturating_mul(*len),
+            _ => 32,
+        }
+    }
+}
+
+impl fmt::Display for AbiType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbiType::Bool => f.write_str("bool"),
+            AbiType::Uint(bits) => write!(f, "uint{}", bits),
+            AbiType::Bytes32 => f.write_str("bytes32"),
+            AbiType::Address => f.write_str("address"),
+            AbiType::Array(element, Some(len)) => write!(f, "{}[{}]", element, len),
+            AbiType::Array(element, None) => write!(f, "{}[]", element),
+        }
+    }
+}
+
+impl FromStr for AbiType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(rest) = s.strip_suffix(']')
+            && let Some((element, len)) = rest.rsplit_once('[')
+        {
+            let len = match len {
+                "" => None,
+                len => match len.parse() {
+                    Ok(len) if len > 0 => Some(len),
+                    _ => anyhow::bail!("Invalid array length in ABI type '{}'", s),
+                },
+            };
+            return Ok(AbiType::Array(Box::new(element.parse()?), len));
+        }
+        let bits = s.strip_prefix("uint").or_else(|| s.strip_prefix('u'));
+        match (s, bits) {
+            ("bool", _) => Ok(AbiType::Bool),
+            ("bytes32", _) => Ok(AbiType::Bytes32),
+            ("address", _) => Ok(AbiType::Address),
+            ("uint", _) => Ok(AbiType::Uint(256)),
+            (_, Some(bits)) => match bits.parse() {
+                Ok(bits) if bits % 8 == 0 && (8..=256).contains(&bits) => Ok(AbiType::Uint(bits)),
+                _ => anyhow::bail!("Unknown ABI type '{}'", s),
+            },
+            _ => anyhow::bail!(
+                "Unknown ABI type '{}': expected bool, u8 to u256, bytes32 or address, or an array of them (T[] or T[N])",
+                s
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FieldSpec {
+    name: String,
+    #[serde(rename = "type")]
+    abi_type: String,
+}
+
+/// Named fields the public values are ABI-encoded from, one 32-byte word each, in order
+#[derive(Debug, Clone)]
+pub struct PubsAbi {
+    fields: Vec<(String, AbiType)>,
+}
+
+impl PubsAbi {
+    /// Load a schema file: a JSON array of `{"name": ..., "type": ...}`, as in the `inputs`
+    /// of a Solidity ABI
+    pub async fn load(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read(path).await?;
+        let specs: Vec<FieldSpec> = serde_json::from_slice(&content)
+            .map_err(|e| FailureClass::BadInput.error(format!("Invalid ABI schema {}: {}", path.display(), e)))?;
+        let fields = specs
+            .into_iter()
+            .map(|spec| Ok((spec.name, spec.abi_type.parse()?)))
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| FailureClass::BadInput.error(format!("Invalid ABI schema {}: {:#}", path.display(), e)))?;
+        Ok(Self { fields })
+    }
+
+    /// Public values decoded into their named fields. Integers of any width become decimal
+    /// strings (JSON numbers lose precision past 53 bits), bytes32 and addresses hex, arrays
+    /// JSON arrays.
+    pub fn decode(&self, pubs: &[u8]) -> Result<Map<String, Value>> {
+        let head = self.fields.iter().fold(0usize, |size, (_, abi_type)| size.saturating_add(abi_type.head_size()));
+        let dynamic = self.fields.iter().any(|(_, abi_type)| abi_type.is_dynamic());
+        // Without arrays behind offsets the encoding has exactly the head's size
+        if pubs.len() < head || (!dynamic && pubs.len() != head) {
+            anyhow::bail!(
+                "Public values are {} bytes, but the ABI schema's {} fields take {}{}",
+                pubs.len(),
+                self.fields.len(),
+                if dynamic { "at least " } else { "" },
+                head
+            );
+        }
+        let mut decoded = Map::new();
+        let mut position = 0;
+        for (name, abi_type) in &self.fields {
+            let value = decode_in_tuple(abi_type, pubs, 0, position)
+                .map_err(|e| anyhow::anyhow!("Field '{}': {:#}", name, e))?;
+            decoded.insert(name.clone(), value);
+            position += abi_type.head_size();
+        }
+        Ok(decoded)
+    }
+}
+
+/// Value whose head is at `position` in the tuple encoded from `base`: in place if static,
+/// else behind an offset relative to `base`
+fn decode_in_tuple(abi_type: &AbiType, data: &[u8], base: usize, position: usize) -> Result<Value> {
+    let head = base.checked_add(position).ok_or_else(|| anyhow::anyhow!("Offset overflow"))?;
+    if !abi_type.is_dynamic() {
+        return decode_value(abi_type, data, head);
+    }
+    let offset = read_usize(data, head)?;
+    let at = base.checked_add(offset).ok_or_else(|| anyhow::anyhow!("Offset {} is out of range", offset))?;
+    decode_value(abi_type, data, at)
+}
+
+fn decode_value(abi_type: &AbiType, data: &[u8], at: usize) -> Result<Value> {
+    let (element, len, base) = match abi_type {
+        AbiType::Array(element, Some(len)) => (element, *len, at),
+        AbiType::Array(element, None) => (element, read_usize(data, at)?, at + 32),
+        _ => return decode_word(abi_type, word(data, at)?),
+    };
+    // A length beyond what the data could hold is rejected before allocating for it
+    if len.saturating_mul(element.head_size()) > data.len().saturating_sub(base) {
+        anyhow::bail!("{} of {} elements does not fit the public values", abi_type, len);
+    }
+    (0..len)
+        .map(|index| {
+            decode_in_tuple(element, data, base, index * element.head_size())
+                .map_err(|e| anyhow::anyhow!("Element {}: {:#}", index, e))
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(Value::Array)
+}
+
+fn word(data: &[u8], at: usize) -> Result<&[u8]> {
+    at.checked_add(32)
+        .and_then(|end| data.get(at..end))
+        .ok_or_else(|| anyhow::anyhow!("Word at byte {} is past the end of the public values", at))
+}
+
+/// Offset or length word, which must fit a usize
+fn read_usize(data: &[u8], at: usize) -> Result<usize> {
+    let word = word(data, at)?;
+    if word[..24].iter().any(|byte| *byte != 0) {
+        anyhow::bail!("Offset or length 0x{} is out of range", hex::encode(word));
+    }
+    usize::try_from(u64::from_be_bytes(word[24..].try_into()?))
+        .map_err(|_| anyhow::anyhow!("Offset or length 0x{} is out of range", hex::encode(word)))
+}
+
+/// Big-endian unsigned integer in decimal
+fn decimal(bytes: &[u8]) -> String {
+    let mut digits = Vec::new();
+    let mut number = bytes.to_vec();
+    while number.iter().any(|byte| *byte != 0) {
+        let mut remainder = 0u32;
+        for byte in number.iter_mut() {
+            let value = (remainder << 8) | *byte as u32;
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+    }
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("decimal digits are ASCII")
+}
+
+fn decode_word(abi_type: &AbiType, word: &[u8]) -> Result<Value> {
+    // Bytes the type leaves unused must be zero, as for the Solidity decoder
+    let padding = match abi_type {
+        AbiType::Bool => 31,
+        AbiType::Uint(bits) => 32 - *bits as usize / 8,
+        AbiType::Bytes32 => 0,
+        AbiType::Address => 12,
+        AbiType::Array(..) => unreachable!("arrays are decoded element by element"),
+    };
+    if word[..padding].iter().any(|byte| *byte != 0) {
+        anyhow::bail!("{} word 0x{} has non-zero padding", abi_type, hex::encode(word));
+    }
+    Ok(match abi_type {
+        AbiType::Bool => match word[31] {
+            0 => Value::Bool(false),
+            1 => Value::Bool(true),
+            other => anyhow::bail!("{} is not a bool", other),
+        },
+        AbiType::Uint(_) => Value::from(decimal(word)),
+        AbiType::Bytes32 => Value::from(format!("0x{}", hex::encode(word))),
+        AbiType::Address => Value::from(format!("0x{}", hex::encode(&word[12..]))),
+        AbiType::Array(..) => unreachable!("arrays are decoded element by element"),
+    })
+}
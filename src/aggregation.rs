@@ -0,0 +1,60 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use subxt::utils::H256;
+
+use crate::output::write_atomic;
+
+/// Merkle path of a statement inside a published aggregation, as returned by
+/// the `aggregate_statementPath` RPC
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub root: H256,
+    pub proof: Vec<H256>,
+    #[serde(alias = "numberOfLeaves")]
+    pub number_of_leaves: u32,
+    #[serde(alias = "leafIndex")]
+    pub leaf_index: u32,
+    pub leaf: H256,
+}
+
+/// State of an aggregation domain, from the `Aggregate` pallet's storage
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainInfo {
+    pub domain_id: u32,
+    /// `Ready`, `Hold`, `Removable` or `Removed`; only `Ready` domains accept proofs
+    pub state: String,
+    /// ID the aggregation now filling up will be published under
+    pub next_aggregation_id: u64,
+    /// Statements queued in that aggregation so far
+    pub queue_size: u32,
+    pub max_aggregation_size: u32,
+    /// Complete aggregations waiting for an `aggregate` call, oldest first
+    pub ready_aggregations: Vec<u64>,
+}
+
+/// Everything a dApp needs to check that a statement was aggregated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregationReceipt {
+    pub domain_id: u32,
+    pub aggregation_id: u64,
+    pub statement: H256,
+    /// Aggregation root published in `NewAggregationReceipt`
+    pub receipt: H256,
+    /// Block that carried the `NewAggregationReceipt` event
+    pub receipt_block_hash: H256,
+    pub merkle_path: MerkleProof,
+}
+
+impl AggregationReceipt {
+    pub async fn save(&self, output_path: &Path) -> Result<()> {
+        let json_content = serde_json::to_string_pretty(self)?;
+        write_atomic(output_path, json_content).await?;
+        Ok(())
+    }
+
+    pub async fn load(input_path: &Path) -> Result<Self> {
+        let json_content = tokio::fs::read(input_path).await?;
+        Ok(serde_json::from_slice(&json_content)?)
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use aws_config::BehaviorVersion;
+use aws_sdk_secretsmanager::config::Region;
+use aws_sdk_secretsmanager::error::{DisplayErrorContext, SdkError};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+use tracing::info;
+use zeroize::Zeroizing;
+
+use crate::failure::FailureClass;
+use crate::secret::SecretString;
+
+/// A secret in AWS Secrets Manager, written `<secret-id>[#field]`. The secret ID is its name or
+/// ARN; with a field, the secret is a JSON object and the mnemonic is that field's value.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct AwsSecret {
+    pub secret_id: String,
+    pub field: Option<String>,
+}
+
+impl FromStr for AwsSecret {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (secret_id, field) = match s.split_once('#') {
+            Some((secret_id, field)) => (secret_id, Some(field.to_string()).filter(|field| !field.is_empty())),
+            None => (s, None),
+        };
+        if secret_id.is_empty() {
+            anyhow::bail!("Invalid AWS secret '{}': expected <secret-id>[#field]", s);
+        }
+        Ok(Self { secret_id: secret_id.to_string(), field })
+    }
+}
+
+impl TryFrom<String> for AwsSecret {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for AwsSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "{}#{}", self.secret_id, field),
+            None => write!(f, "{}", self.secret_id),
+        }
+    }
+}
+
+impl AwsSecret {
+    // Region of an ARN secret ID, which the client must be in to read it
+    fn region(&self) -> Option<&str> {
+        let mut parts = self.secret_id.strip_prefix("arn:")?.split(':');
+        parts.nth(2).filter(|region| !region.is_empty())
+    }
+
+    /// The mnemonic the secret holds, `None` when the secret or its field does not exist.
+    /// Credentials and the region come from the usual AWS places, as for `--s3`.
+    pub async fn read(&self) -> Result<Option<SecretString>> {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let Some(region) = self.region() {
+            loader = loader.region(Region::new(region.to_string()));
+        }
+        let client = aws_sdk_secretsmanager::Client::new(&loader.load().await);
+        let output = match client.get_secret_value().secret_id(&self.secret_id).send().await {
+            Ok(output) => output,
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_resource_not_found_exception()) => return Ok(None),
+            Err(e) => {
+                let class = match &e {
+                    SdkError::ServiceError(_) => FailureClass::BadInput,
+                    _ => FailureClass::Network,
+                };
+                return Err(class.error(format!(
+                    "Failed to read AWS secret {}: {}",
+                    self.secret_id,
+                    DisplayErrorContext(&e)
+                )));
+            }
+        };
+        let value = match (output.secret_string(), output.secret_binary()) {
+            (Some(value), _) => Zeroizing::new(value.to_string()),
+            (None, Some(value)) => Zeroizing::new(String::from_utf8(value.as_ref().to_vec())?),
+            (None, None) => return Ok(None),
+        };
+        info!("Read AWS secret {}", self.secret_id);
+        let Some(field) = &self.field else {
+            return Ok(Some(SecretString::new(value.trim())).filter(|mnemonic| !mnemonic.expose().is_empty()));
+        };
+        let object: Value = serde_json::from_str(&value)
+            .map_err(|_| FailureClass::BadInput.error(format!("AWS secret {} is not a JSON object", self.secret_id)))?;
+        Ok(object.get(field).and_then(Value::as_str).map(|mnemonic| SecretString::new(mnemonic.trim())))
+    }
+}
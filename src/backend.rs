@@ -0,0 +1,151 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use subxt::dynamic::Value;
+use subxt::ext::codec::{Compact, Encode};
+use subxt::ext::futures::future::BoxFuture;
+
+use crate::converter::{ConvertedProof, ProofType};
+use crate::failure::FailureClass;
+use crate::plonky2::Plonky2Params;
+
+/// Files and options a proof is converted from; each backend reads the ones its proof
+/// system needs
+#[derive(Debug, Clone, Default)]
+pub struct ProofInputs {
+    /// The proof: an SP1 artifact, the output of `bb prove`, a snarkjs `proof.json`, ...
+    pub proof: PathBuf,
+    /// Hex VK of an SP1 program, checked against the artifact's
+    pub vk: Option<String>,
+    /// Convert with `vk` even when the artifact carries a different one
+    pub force_vk: bool,
+    /// Convert on the local CPU without reading SP1 prover settings from the environment
+    pub offline: bool,
+    /// VK file of the circuit
+    pub vk_file: Option<PathBuf>,
+    /// snarkjs `public.json`
+    pub public_inputs: Option<PathBuf>,
+    /// Plonky2 `CommonCircuitData`, when kept apart from the verifier data
+    pub common_data: Option<PathBuf>,
+    pub plonky2: Plonky2Params,
+}
+
+impl ProofInputs {
+    pub fn new(proof: &Path) -> Self {
+        Self { proof: proof.to_path_buf(), ..Default::default() }
+    }
+
+    pub(crate) fn vk_file(&self, proof_type: ProofType) -> Result<&Path> {
+        required(&self.vk_file, "the circuit's VK file", proof_type)
+    }
+
+    pub(crate) fn public_inputs(&self, proof_type: ProofType) -> Result<&Path> {
+        required(&self.public_inputs, "the snarkjs public.json", proof_type)
+    }
+}
+
+fn required<'a>(path: &'a Option<PathBuf>, what: &str, proof_type: ProofType) -> Result<&'a Path> {
+    path.as_deref()
+        .ok_or_else(|| FailureClass::BadInput.error(format!("Converting a {} proof needs {}", proof_type, what)))
+}
+
+/// Arguments of a pallet's `submit_proof` for one proof, the domain aside
+pub struct CallArgs {
+    pub vk: Value,
+    pub proof: Value,
+    pub pubs: Value,
+}
+
+/// The `vk`, `proof` and `pubs` arguments of `submit_proof`, each SCALE-encoded as the
+/// pallet decodes it
+pub struct ScaleArgs {
+    pub vk: Vec<u8>,
+    pub proof: Vec<u8>,
+    pub pubs: Vec<u8>,
+}
+
+impl ScaleArgs {
+    /// The three arguments in call order, the VK as `VkOrHash::Vk`: a call's encoding
+    /// without its pallet and call index, and before its domain
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + self.vk.len() + self.proof.len() + self.pubs.len());
+        bytes.push(VK_OR_HASH_VK);
+        bytes.extend(&self.vk);
+        bytes.extend(&self.proof);
+        bytes.extend(&self.pubs);
+        bytes
+    }
+}
+
+// Index of `Vk` in `VkOrHash { Hash, Vk }`
+const VK_OR_HASH_VK: u8 = 1;
+
+/// A proof system proofs can be converted from and submitted to zkVerify with. Each one
+/// lives in its own module and is picked with [`ProofType::backend`].
+pub trait ProofBackend: Send + Sync {
+    fn proof_type(&self) -> ProofType;
+
+    /// Pallet proofs are submitted to
+    fn pallet(&self) -> &'static str;
+
+    /// Convert the proof of `inputs` into zkVerify's format
+    fn convert<'a>(&'a self, inputs: &'a ProofInputs) -> BoxFuture<'a, Result<ConvertedProof>>;
+
+    /// VK the proof of `inputs` converts with, hex-encoded as in [`ConvertedProof::vk`],
+    /// without converting the proof
+    fn derive_vk<'a>(&'a self, inputs: &'a ProofInputs) -> BoxFuture<'a, Result<String>>;
+
+    /// Check that a converted proof has the shape the pallet takes. The proof itself is
+    /// only verified on chain.
+    fn verify_locally(&self, converted_proof: &ConvertedProof) -> Result<()>;
+
+    /// `submit_proof` arguments for a converted proof that passed [`Self::verify_locally`]
+    fn call_args(&self, converted_proof: &ConvertedProof) -> Result<CallArgs>;
+
+    /// The same arguments SCALE-encoded, for tools building the extrinsic themselves
+    fn encode_args(&self, converted_proof: &ConvertedProof) -> Result<ScaleArgs>;
+}
+
+/// Bytes as a sequence of u8 values, for `Vec<u8>` and `[u8; N]` arguments
+pub(crate) fn bytes_value(bytes: &[u8]) -> Value {
+    Value::unnamed_composite(bytes.iter().map(|b| Value::u128(*b as u128)).collect::<Vec<_>>())
+}
+
+/// Public inputs as a sequence of `size`-byte field elements
+pub(crate) fn scalars_value(pubs: &[u8], size: usize, proof_type: ProofType) -> Result<Value> {
+    check_scalars(pubs, size, proof_type)?;
+    Ok(Value::unnamed_composite(pubs.chunks(size).map(bytes_value).collect::<Vec<_>>()))
+}
+
+/// Public inputs as a `Vec<[u8; size]>`
+pub(crate) fn encode_scalars(pubs: &[u8], size: usize, proof_type: ProofType) -> Result<Vec<u8>> {
+    check_scalars(pubs, size, proof_type)?;
+    let mut bytes = Compact((pubs.len() / size) as u32).encode();
+    bytes.extend(pubs);
+    Ok(bytes)
+}
+
+pub(crate) fn check_scalars(pubs: &[u8], size: usize, proof_type: ProofType) -> Result<()> {
+    if !pubs.len().is_multiple_of(size) {
+        return Err(FailureClass::BadInput.error(format!(
+            "{} public inputs are {} bytes, not a multiple of {}",
+            proof_type,
+            pubs.len(),
+            size
+        )));
+    }
+    Ok(())
+}
+
+/// Refuse a component whose size the pallet fixes
+pub(crate) fn check_size(proof_type: ProofType, name: &str, bytes: &[u8], expected: usize) -> Result<()> {
+    if bytes.len() != expected {
+        return Err(FailureClass::BadInput.error(format!(
+            "{} '{}' is {} bytes, expected {}",
+            proof_type,
+            name,
+            bytes.len(),
+            expected
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use subxt::ext::futures::{stream, StreamExt};
+use tracing::warn;
+
+use crate::client::parse_request_input;
+use crate::queue::{Job, JobQueue, JobResult, QueueOptions};
+use crate::router::Router;
+
+/// One request of a batch, with its per-request options
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchEntry {
+    pub request_id: String,
+    /// Domain to submit to instead of `--domain-id`
+    #[serde(default)]
+    pub domain_id: Option<u32>,
+    /// Converted proof path; `<output_dir>/<request_id>.json` (`.json.zst` compressed) by default
+    #[serde(default)]
+    pub output: Option<PathBuf>,
+    /// Expected SHA-256 of the request's artifact
+    #[serde(default)]
+    pub artifact_sha256: Option<String>,
+}
+
+impl BatchEntry {
+    fn new(request_id: &str) -> Self {
+        Self { request_id: request_id.to_string(), domain_id: None, output: None, artifact_sha256: None }
+    }
+}
+
+// JSON files list bare request IDs, entries with options, or a mix of both
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonEntry {
+    RequestId(String),
+    Entry(BatchEntry),
+}
+
+/// Load a requests file. `.json` files hold an array of request IDs or
+/// `{"request_id", "domain_id", "output"}` objects; `.csv` files have a header row with a
+/// `request_id` column and optional `domain_id` and `output` columns; any other file lists
+/// one request ID per line, with `#` starting a comment. Explorer URLs of requests are taken
+/// as their ID.
+pub async fn load_requests_file(path: &Path) -> Result<Vec<BatchEntry>> {
+    let content = tokio::fs::read_to_string(path).await?;
+    let mut entries: Vec<BatchEntry> = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::from_str::<Vec<JsonEntry>>(&content)?
+            .into_iter()
+            .map(|entry| match entry {
+                JsonEntry::RequestId(request_id) => BatchEntry::new(&request_id),
+                JsonEntry::Entry(entry) => entry,
+            })
+            .collect(),
+        Some("csv") => parse_csv(&content)?,
+        _ => content
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or("").trim())
+            .filter(|line| !line.is_empty())
+            .map(BatchEntry::new)
+            .collect(),
+    };
+
+    if entries.is_empty() {
+        anyhow::bail!("Requests file {} lists no requests", path.display());
+    }
+    // Explorer URLs are accepted too; all requests are still fetched from the router's explorer
+    for entry in &mut entries {
+        entry.request_id = parse_request_input(&entry.request_id)?.0;
+    }
+    let mut seen = HashSet::new();
+    for entry in &entries {
+        if !seen.insert(entry.request_id.as_str()) {
+            anyhow::bail!("Request {} is listed twice in {}", entry.request_id, path.display());
+        }
+    }
+    Ok(entries)
+}
+
+// Plain comma-separated values; quoting is not supported
+fn parse_csv(content: &str) -> Result<Vec<BatchEntry>> {
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<&str> = lines
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("CSV requests file has no header row"))?
+        .split(',')
+        .map(str::trim)
+        .collect();
+    let column = |name: &str| header.iter().position(|&column| column == name);
+    let request_id_column = column("request_id").ok_or_else(|| anyhow::anyhow!("CSV requests file has no request_id column"))?;
+    let domain_id_column = column("domain_id");
+    let output_column = column("output");
+    let artifact_sha256_column = column("artifact_sha256");
+
+    lines
+        .enumerate()
+        .map(|(index, line)| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let field = |column: Option<usize>| column.and_then(|column| fields.get(column)).filter(|field| !field.is_empty());
+            let request_id = field(Some(request_id_column))
+                .ok_or_else(|| anyhow::anyhow!("CSV row {} has no request_id", index + 2))?;
+            let domain_id = field(domain_id_column)
+                .map(|domain_id| domain_id.parse())
+                .transpose()
+                .map_err(|_| anyhow::anyhow!("CSV row {} has an invalid domain_id", index + 2))?;
+            Ok(BatchEntry {
+                request_id: request_id.to_string(),
+                domain_id,
+                output: field(output_column).map(PathBuf::from),
+                artifact_sha256: field(artifact_sha256_column).map(|sha256| sha256.to_string()),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchStatus {
+    Converted,
+    Submitted,
+    /// Already routed according to the history
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchOutcome {
+    pub request_id: String,
+    pub status: BatchStatus,
+    /// Transaction hash, output path or error
+    pub detail: String,
+}
+
+pub struct BatchOptions {
+    /// Default directory for converted proofs
+    pub output_dir: PathBuf,
+    /// Skip requests the router's history shows as already routed
+    pub skip_routed: bool,
+    /// Explorer pages fetched at the same time
+    pub fetch_concurrency: usize,
+    pub queue: QueueOptions,
+}
+
+/// Route every entry and report how each one ended, in the order of `entries`
+pub async fn run_batch(router: Arc<Router>, entries: Vec<BatchEntry>, options: BatchOptions) -> Result<Vec<BatchOutcome>> {
+    tokio::fs::create_dir_all(&options.output_dir).await?;
+    status!("📦 Routing {} requests", entries.len());
+
+    let mut outcomes: Vec<Option<BatchOutcome>> = vec![None; entries.len()];
+    let mut outputs = Vec::with_capacity(entries.len());
+    let mut queue = JobQueue::start(router.clone(), options.queue);
+    let mut to_fetch = Vec::new();
+    for (index, mut entry) in entries.into_iter().enumerate() {
+        let output = entry
+            .output
+            .take()
+            .unwrap_or_else(|| options.output_dir.join(router.output_file_name(&entry.request_id)));
+        outputs.push(output.clone());
+
+        if options.skip_routed && router.already_routed(&entry.request_id) {
+            outcomes[index] = Some(BatchOutcome {
+                request_id: entry.request_id,
+                status: BatchStatus::Skipped,
+                detail: "already routed".to_string(),
+            });
+            continue;
+        }
+        to_fetch.push((index, entry, output));
+    }
+
+    // Queue each request as soon as its page is in, in order, while later pages still load
+    let router_ref = &router;
+    let mut fetched = stream::iter(to_fetch)
+        .map(|(index, entry, output)| async move {
+            let metadata = router_ref.fetch_metadata(&entry.request_id).await;
+            (index, entry, output, metadata)
+        })
+        .buffered(options.fetch_concurrency.max(1));
+    while let Some((index, entry, output, metadata)) = fetched.next().await {
+        match metadata {
+            Ok(mut metadata) => {
+                if entry.artifact_sha256.is_some() {
+                    metadata.artifact_sha256 = entry.artifact_sha256;
+                }
+                queue.push(Job {
+                    number: index + 1,
+                    request_id: entry.request_id,
+                    metadata,
+                    output,
+                    domain_id: entry.domain_id,
+                })
+            }
+            Err(e) => {
+                status!("❌ [{}/{}] {}: {:#}", index + 1, outcomes.len(), entry.request_id, e);
+                outcomes[index] = Some(BatchOutcome {
+                    request_id: entry.request_id,
+                    status: BatchStatus::Failed,
+                    detail: format!("{:#}", e),
+                });
+            }
+        }
+    }
+    drop(fetched);
+    // Every page is in, so free the browser's memory for the conversions
+    router.client().close_browser().await;
+
+    while queue.pending() > 0 {
+        let JobResult { number, request_id, outcome } = queue.next_result().await;
+        let index = number - 1;
+        let outcome = match outcome {
+            Ok(Some(submission)) => BatchOutcome { request_id, status: BatchStatus::Submitted, detail: submission.tx_hash },
+            Ok(None) => BatchOutcome {
+                request_id,
+                status: BatchStatus::Converted,
+                detail: outputs[index].display().to_string(),
+            },
+            Err(failure) => {
+                let error = format!("{:?}: {:#}", failure.stage, failure.error);
+                if let Some(history) = router.history()
+                    && let Err(e) = history.record_failure(&request_id, &format!("{:#}", failure.error))
+                {
+                    warn!("Failed to update history: {:#}", e);
+                }
+                BatchOutcome { request_id, status: BatchStatus::Failed, detail: error }
+            }
+        };
+        status!("{} [{}/{}] {}: {}", icon(outcome.status), number, outcomes.len(), outcome.request_id, outcome.detail);
+        outcomes[index] = Some(outcome);
+    }
+
+    Ok(outcomes.into_iter().flatten().collect())
+}
+
+pub(crate) fn icon(status: BatchStatus) -> &'static str {
+    match status {
+        BatchStatus::Converted => "✅",
+        BatchStatus::Submitted => "🎉",
+        BatchStatus::Skipped => "⏭️ ",
+        BatchStatus::Failed => "❌",
+    }
+}
+
+/// Print one line per request and the totals
+pub fn print_summary(outcomes: &[BatchOutcome]) {
+    let count = |status: BatchStatus| outcomes.iter().filter(|outcome| outcome.status == status).count();
+    status!();
+    status!(
+        "📋 Batch summary: {} submitted, {} converted, {} skipped, {} failed",
+        count(BatchStatus::Submitted),
+        count(BatchStatus::Converted),
+        count(BatchStatus::Skipped),
+        count(BatchStatus::Failed)
+    );
+    for outcome in outcomes {
+        status!("{} {}  {:<9}  {}", icon(outcome.status), outcome.request_id, format!("{:?}", outcome.status).to_lowercase(), outcome.detail);
+    }
+}
@@ -0,0 +1,211 @@
+use anyhow::Result;
+use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::detection::{self, DetectionOptions};
+use chromiumoxide::Page;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use subxt::ext::futures::StreamExt;
+use tempfile::TempDir;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::failure::FailureClass;
+
+/// How explorer pages are rendered by the headless browser, driven over CDP
+#[derive(Debug, Clone)]
+pub struct BrowserOptions {
+    /// Chrome or Chromium binary; `CHROME` or the usual binary names on the PATH when unset
+    pub executable: Option<PathBuf>,
+    /// CSS selector waited for before the page is read, for data rendered after load
+    pub wait_for_selector: Option<String>,
+    /// Limit on launching the browser, loading the page and waiting for the selector
+    pub timeout: Duration,
+    /// Chrome `--proxy-server` value: one proxy, or per-scheme rules such as `https=...;http=...`
+    pub proxy: Option<String>,
+    /// Hosts reached without the proxy, comma separated as in `NO_PROXY`
+    pub no_proxy: Option<String>,
+}
+
+impl Default for BrowserOptions {
+    fn default() -> Self {
+        Self {
+            executable: None,
+            wait_for_selector: None,
+            timeout: Duration::from_secs(30),
+            proxy: None,
+            no_proxy: None,
+        }
+    }
+}
+
+impl BrowserOptions {
+    pub fn with_executable(mut self, executable: Option<PathBuf>) -> Self {
+        self.executable = executable;
+        self
+    }
+
+    pub fn with_wait_for_selector(mut self, selector: Option<String>) -> Self {
+        self.wait_for_selector = selector;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_proxy(mut self, proxy: Option<String>, no_proxy: Option<String>) -> Self {
+        self.proxy = proxy;
+        self.no_proxy = no_proxy;
+        self
+    }
+
+    /// The browser binary pages are rendered with
+    pub fn find_executable(&self) -> Result<PathBuf> {
+        if let Some(executable) = &self.executable {
+            return Ok(executable.clone());
+        }
+        detection::default_executable(DetectionOptions::default()).map_err(|e| {
+            FailureClass::BadInput.error(format!(
+                "No Chrome or Chromium found to render explorer pages ({}): install one or pass --browser-path",
+                e
+            ))
+        })
+    }
+}
+
+/// One headless browser shared by every render, with up to `size` pages loading at a time.
+/// Pages are kept open and reused for later renders. The browser is launched on first use and
+/// launched again if it has exited when the next page is rendered.
+pub struct BrowserPool {
+    options: BrowserOptions,
+    tabs: Semaphore,
+    session: Mutex<Option<Arc<Session>>>,
+}
+
+struct Session {
+    browser: Browser,
+    events: JoinHandle<()>,
+    idle: std::sync::Mutex<Vec<Page>>,
+    // Removed when the session is dropped
+    _profile: TempDir,
+}
+
+impl BrowserPool {
+    pub fn new(options: BrowserOptions, size: usize) -> Self {
+        Self { options, tabs: Semaphore::new(size.max(1)), session: Mutex::new(None) }
+    }
+
+    /// Load `url` and return the DOM once scripts have rendered it
+    pub async fn render(&self, url: &str) -> Result<String> {
+        let _tab = self.tabs.acquire().await?;
+        let session = self.session().await?;
+        let idle = session.idle.lock().expect("idle pages lock").pop();
+        let page = match idle {
+            Some(page) => page,
+            None => tokio::time::timeout(self.options.timeout, session.browser.new_page("about:blank"))
+                .await
+                .map_err(|_| anyhow::anyhow!("Opening a browser page timed out after {:?}", self.options.timeout))??,
+        };
+
+        match tokio::time::timeout(self.options.timeout, load(&page, url, &self.options)).await {
+            Ok(Ok(content)) => {
+                session.idle.lock().expect("idle pages lock").push(page);
+                Ok(content)
+            }
+            result => {
+                // The page may be stuck loading, so it is not reused
+                if let Err(e) = page.close().await {
+                    debug!("Failed to close the page of {}: {}", url, e);
+                }
+                match result {
+                    Ok(Err(e)) => Err(e),
+                    _ => Err(anyhow::anyhow!("Rendering {} timed out after {:?}", url, self.options.timeout)),
+                }
+            }
+        }
+    }
+
+    /// Close the browser; the next render launches it again
+    pub async fn close(&self) {
+        let Some(session) = self.session.lock().await.take() else {
+            return;
+        };
+        match Arc::try_unwrap(session) {
+            Ok(mut session) => {
+                if let Err(e) = session.browser.close().await {
+                    debug!("Failed to close the browser: {}", e);
+                }
+                let _ = session.browser.wait().await;
+                session.events.abort();
+            }
+            // Still rendering; the last render to finish drops the browser, killing it
+            Err(_) => debug!("Browser still in use, not closing it"),
+        }
+    }
+
+    // The running browser, launching it if there is none or it has exited
+    async fn session(&self) -> Result<Arc<Session>> {
+        let mut session = self.session.lock().await;
+        if let Some(running) = session.as_ref() {
+            if !running.events.is_finished() {
+                return Ok(running.clone());
+            }
+            warn!("Headless browser exited, restarting it");
+        }
+        let launched = Arc::new(launch(&self.options).await?);
+        *session = Some(launched.clone());
+        Ok(launched)
+    }
+}
+
+async fn launch(options: &BrowserOptions) -> Result<Session> {
+    let executable = options.find_executable()?;
+    // A profile of its own, so that it does not share a lock with other browsers
+    let profile = tempfile::tempdir()?;
+    let config = config(&executable, profile.path(), options)?;
+    let (browser, mut handler) = Browser::launch(config)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to launch {}: {}", executable.display(), e))?;
+    // Ends when the connection to the browser closes, which is how an exit is noticed
+    let events = tokio::spawn(async move {
+        while let Some(event) = handler.next().await {
+            if let Err(e) = event {
+                debug!("Browser event error: {}", e);
+            }
+        }
+    });
+    debug!("Launched {}", executable.display());
+    Ok(Session { browser, events, idle: std::sync::Mutex::new(Vec::new()), _profile: profile })
+}
+
+fn config(executable: &Path, profile: &Path, options: &BrowserOptions) -> Result<BrowserConfig> {
+    let mut builder = BrowserConfig::builder()
+        .chrome_executable(executable)
+        .user_data_dir(profile)
+        .no_sandbox()
+        .arg("--disable-gpu")
+        .launch_timeout(options.timeout)
+        .request_timeout(options.timeout);
+    if let Some(proxy) = &options.proxy {
+        builder = builder.arg(format!("--proxy-server={}", proxy));
+        if let Some(no_proxy) = &options.no_proxy {
+            builder = builder.arg(format!("--proxy-bypass-list={}", no_proxy.replace(',', ";")));
+        }
+    }
+    builder.build().map_err(|e| anyhow::anyhow!("Invalid browser configuration: {}", e))
+}
+
+async fn load(page: &Page, url: &str, options: &BrowserOptions) -> Result<String> {
+    page.goto(url).await?;
+    page.wait_for_navigation().await?;
+    if let Some(selector) = &options.wait_for_selector {
+        debug!("Waiting for '{}' on {}", selector, url);
+        while page.find_element(selector.as_str()).await.is_err() {
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+    }
+    Ok(page.content().await?)
+}
@@ -0,0 +1,196 @@
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncReadExt;
+use tracing::{debug, warn};
+
+use crate::client::ProofRequestMetadata;
+use crate::output::write_atomic;
+
+/// On-disk cache of explorer metadata and downloaded artifacts, so converting a request
+/// again does not download its artifact again.
+///
+/// Metadata lives in `metadata/<request_id>.json`. Artifacts are stored by content in
+/// `artifacts/<sha256>.bin`, with `requests/<request_id>` holding the hash; the hash is
+/// checked on every read. Interrupted downloads wait in `partial/<request_id>.part`. Entries are touched when used, so [`Self::clean`] removes the
+/// ones left unused longest.
+#[derive(Debug, Clone)]
+pub struct ArtifactCache {
+    dir: PathBuf,
+}
+
+/// What [`ArtifactCache::clean`] removed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CleanStats {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Parse an age such as `30d`, `12h`, `90m` or `3600s`; plain numbers are seconds
+pub fn parse_age(s: &str) -> Result<Duration> {
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => s.split_at(index),
+        None => (s, "s"),
+    };
+    let number: u64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid age '{}': expected a number with s, m, h or d, e.g. 30d", s))?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => anyhow::bail!("Invalid age '{}': expected a number with s, m, h or d, e.g. 30d", s),
+    };
+    Ok(Duration::from_secs(number * seconds))
+}
+
+impl ArtifactCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `$XDG_CACHE_HOME/rusty_router`, falling back to `~/.cache`
+    pub fn default_dir() -> Option<PathBuf> {
+        let cache_home = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+        Some(cache_home.join("rusty_router"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn metadata_path(&self, request_id: &str) -> PathBuf {
+        self.dir.join("metadata").join(format!("{}.json", request_id))
+    }
+
+    fn request_path(&self, request_id: &str) -> PathBuf {
+        self.dir.join("requests").join(request_id)
+    }
+
+    fn artifact_path(&self, sha256: &str) -> PathBuf {
+        self.dir.join("artifacts").join(format!("{}.bin", sha256))
+    }
+
+    /// Where an interrupted download of a request's artifact is kept until it is resumed
+    pub fn partial_path(&self, request_id: &str) -> PathBuf {
+        self.dir.join("partial").join(format!("{}.part", request_id))
+    }
+
+    pub async fn metadata(&self, request_id: &str) -> Option<ProofRequestMetadata> {
+        let path = self.metadata_path(request_id);
+        let content = tokio::fs::read(&path).await.ok()?;
+        match serde_json::from_slice(&content) {
+            Ok(metadata) => {
+                touch(&path);
+                debug!("Metadata of {} read from the cache", request_id);
+                Some(metadata)
+            }
+            Err(e) => {
+                warn!("Ignoring unreadable cached metadata {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub async fn store_metadata(&self, request_id: &str, metadata: &ProofRequestMetadata) -> Result<()> {
+        write(&self.metadata_path(request_id), &serde_json::to_vec_pretty(metadata)?).await
+    }
+
+    /// Path and SHA-256 of the cached artifact of a request, if there is one whose content
+    /// still matches its hash
+    pub async fn artifact(&self, request_id: &str) -> Option<(PathBuf, String)> {
+        let request_path = self.request_path(request_id);
+        let sha256 = tokio::fs::read_to_string(&request_path).await.ok()?.trim().to_string();
+        let path = self.artifact_path(&sha256);
+        let mut hasher = Sha256::new();
+        hash_file(&path, &mut hasher).await.ok()?;
+        if hex::encode(hasher.finalize()) != sha256 {
+            warn!("Ignoring corrupt cached artifact {}", path.display());
+            return None;
+        }
+        touch(&request_path);
+        touch(&path);
+        debug!("Artifact of {} found in the cache", request_id);
+        Some((path, sha256))
+    }
+
+    /// Move a downloaded artifact into the cache, returning its path there. On error the
+    /// downloaded file is left where it was.
+    pub async fn store_artifact(&self, request_id: &str, downloaded: &Path, sha256: &str) -> Result<PathBuf> {
+        let path = self.artifact_path(sha256);
+        tokio::fs::create_dir_all(self.dir.join("artifacts")).await?;
+        if tokio::fs::try_exists(&path).await? {
+            tokio::fs::remove_file(downloaded).await?;
+            touch(&path);
+        } else {
+            tokio::fs::rename(downloaded, &path).await?;
+        }
+        // The artifact is cached by now; without the index entry the next run only downloads it again
+        if let Err(e) = write(&self.request_path(request_id), sha256.as_bytes()).await {
+            warn!("Failed to index the cached artifact of {}: {:#}", request_id, e);
+        }
+        Ok(path)
+    }
+
+    /// Remove the entries not used for longer than `older_than`
+    pub async fn clean(&self, older_than: Duration) -> Result<CleanStats> {
+        let cutoff = SystemTime::now() - older_than;
+        let mut stats = CleanStats::default();
+        for subdir in ["metadata", "requests", "artifacts", "partial"] {
+            let mut entries = match tokio::fs::read_dir(self.dir.join(subdir)).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            while let Some(entry) = entries.next_entry().await? {
+                let metadata = entry.metadata().await?;
+                if !metadata.is_file() || metadata.modified()? > cutoff {
+                    continue;
+                }
+                tokio::fs::remove_file(entry.path()).await?;
+                debug!("Removed {}", entry.path().display());
+                stats.files += 1;
+                stats.bytes += metadata.len();
+            }
+        }
+        Ok(stats)
+    }
+}
+
+/// Feed the content of the file at `path` to `hasher`, returning its length
+pub(crate) async fn hash_file(path: &Path, hasher: &mut Sha256) -> Result<u64> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut buffer = vec![0; 64 * 1024];
+    let mut len = 0;
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(len);
+        }
+        hasher.update(&buffer[..read]);
+        len += read as u64;
+    }
+}
+
+// Write through a temporary file, so that concurrent readers never see a partial entry
+async fn write(path: &Path, data: &[u8]) -> Result<()> {
+    let dir = path.parent().expect("cache entries are in a subdirectory");
+    tokio::fs::create_dir_all(dir).await?;
+    write_atomic(path, data).await?;
+    Ok(())
+}
+
+// Mark an entry as used; a failure only makes it eligible for cleaning sooner
+fn touch(path: &Path) {
+    if let Err(e) = std::fs::File::options()
+        .append(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()))
+    {
+        debug!("Failed to touch {}: {}", path.display(), e);
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use serde::Serialize;
+use sha3::{Digest, Keccak256};
+use std::path::Path;
+use subxt::utils::H256;
+
+use crate::aggregation::AggregationReceipt;
+use crate::output::write_atomic;
+
+/// Read-only method of zkVerify's attestation contract on Ethereum
+pub const VERIFY_PROOF_AGGREGATION: &str =
+    "verifyProofAggregation(uint256,uint256,bytes32,bytes32[],uint256,uint256)";
+
+#[derive(Debug, Serialize)]
+pub struct VerifyProofAggregationArgs {
+    pub domain_id: u32,
+    pub aggregation_id: u64,
+    pub leaf: H256,
+    pub merkle_path: Vec<H256>,
+    pub leaf_count: u32,
+    pub index: u32,
+}
+
+/// ABI-encoded call to `verifyProofAggregation`, ready to paste into contract tests
+#[derive(Debug, Serialize)]
+pub struct Calldata {
+    pub function: String,
+    pub selector: String,
+    pub args: VerifyProofAggregationArgs,
+    pub calldata: String,
+}
+
+impl Calldata {
+    pub fn from_receipt(receipt: &AggregationReceipt) -> Self {
+        let path = &receipt.merkle_path;
+        Self {
+            function: VERIFY_PROOF_AGGREGATION.to_string(),
+            selector: format!("0x{}", hex::encode(selector(VERIFY_PROOF_AGGREGATION))),
+            args: VerifyProofAggregationArgs {
+                domain_id: receipt.domain_id,
+                aggregation_id: receipt.aggregation_id,
+                leaf: path.leaf,
+                merkle_path: path.proof.clone(),
+                leaf_count: path.number_of_leaves,
+                index: path.leaf_index,
+            },
+            calldata: format!("0x{}", hex::encode(encode_verify_proof_aggregation(receipt))),
+        }
+    }
+
+    pub async fn save(&self, output_path: &Path) -> Result<()> {
+        let json_content = serde_json::to_string_pretty(self)?;
+        write_atomic(output_path, json_content).await?;
+        Ok(())
+    }
+}
+
+/// First four bytes of the Keccak-256 hash of a function signature
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = Keccak256::digest(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+fn uint_word(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// ABI-encode `verifyProofAggregation(domainId, aggregationId, leaf, merklePath, leafCount, index)`
+pub fn encode_verify_proof_aggregation(receipt: &AggregationReceipt) -> Vec<u8> {
+    let path = &receipt.merkle_path;
+    let mut data = selector(VERIFY_PROOF_AGGREGATION).to_vec();
+
+    // Head: five static words plus the offset of the dynamic `bytes32[]`
+    data.extend_from_slice(&uint_word(receipt.domain_id as u64));
+    data.extend_from_slice(&uint_word(receipt.aggregation_id));
+    data.extend_from_slice(path.leaf.as_bytes());
+    data.extend_from_slice(&uint_word(6 * 32));
+    data.extend_from_slice(&uint_word(path.number_of_leaves as u64));
+    data.extend_from_slice(&uint_word(path.leaf_index as u64));
+
+    // Tail: length-prefixed Merkle path
+    data.extend_from_slice(&uint_word(path.proof.len() as u64));
+    for node in &path.proof {
+        data.extend_from_slice(node.as_bytes());
+    }
+    data
+}
@@ -0,0 +1,53 @@
+use serde::Serialize;
+
+use crate::converter::ProofType;
+use crate::sp1_version::supported_releases;
+
+/// What this build of rusty_router can do, for orchestrators adapting to the deployed version
+#[derive(Debug, Serialize)]
+pub struct Capabilities {
+    pub version: &'static str,
+    pub proof_systems: Vec<&'static str>,
+    pub chains: Vec<&'static str>,
+    pub pallets: Vec<&'static str>,
+    pub submission_backends: Vec<&'static str>,
+    pub output_formats: Vec<&'static str>,
+    /// SP1 release lines whose artifacts are converted, e.g. `v5.x`
+    pub sp1_versions: Vec<String>,
+    /// Cargo features compiled into this binary
+    pub features: Vec<&'static str>,
+}
+
+impl Capabilities {
+    pub fn current() -> Self {
+        let mut chains = vec!["zkverify"];
+        let mut features = Vec::new();
+        if cfg!(feature = "eth") {
+            chains.push("ethereum");
+            features.push("eth");
+        }
+        if cfg!(feature = "vault") {
+            features.push("vault");
+        }
+        if cfg!(feature = "otel") {
+            features.push("otel");
+        }
+        if cfg!(feature = "s3") {
+            features.push("s3");
+        }
+
+        let mut pallets: Vec<&'static str> = ProofType::ALL.iter().map(ProofType::pallet).collect();
+        pallets.extend(["Aggregate", "System"]);
+
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            proof_systems: ProofType::ALL.iter().map(ProofType::as_str).collect(),
+            chains,
+            pallets,
+            submission_backends: vec!["direct", "relayer"],
+            output_formats: vec!["json", "scale", "cbor", "msgpack"],
+            sp1_versions: supported_releases(),
+            features,
+        }
+    }
+}
@@ -1,160 +1,617 @@
 ﻿use anyhow::Result;
-use reqwest::Client;
-use serde::Deserialize;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use reqwest::header::{HeaderName, CONTENT_RANGE, RANGE};
+use reqwest::{RequestBuilder, StatusCode};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::OnceCell;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
 
+use crate::browser::{BrowserOptions, BrowserPool};
+use crate::cache::hash_file;
+use crate::extract::{
+    extract_from_json, extract_from_next_data, extract_from_script_tags, extract_from_dom, extract_request_list,
+    Extracted, ExtractionStrategy, RequestDetails, RequestSummary, DEFAULT_STRATEGIES,
+};
+use crate::failure::{FailureClass, ResultExt, RouterError};
+use crate::network::{HttpClient, HttpPolicy};
+use crate::progress;
+#[cfg(feature = "s3")]
+use crate::s3::{parse_s3_url, S3Downloader, S3Options};
 
-#[derive(Debug, Deserialize)]
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ProofRequestMetadata {
     pub artifact_url: String,
     pub vk: String,
+    /// Expected SHA-256 of the artifact, hex; the download is refused if it differs
+    #[serde(default)]
+    pub artifact_sha256: Option<String>,
+    /// Other fields the explorer showed, e.g. `SP1 Version` or `Prover`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+    /// Cycles, gas, requester, fulfillment time and proof mode, read from `fields`
+    #[serde(flatten)]
+    pub details: RequestDetails,
+}
+
+/// Which explorer listing to read proof requests from
+#[derive(Debug, Clone)]
+pub enum RequestFilter {
+    /// Requests for a program, by VK
+    Program(String),
+    /// Requests sent by an address
+    Requester(String),
+}
+
+impl RequestFilter {
+    fn path(&self) -> String {
+        match self {
+            RequestFilter::Program(vk) => format!("program/{}", vk),
+            RequestFilter::Requester(address) => format!("requester/{}", address),
+        }
+    }
+}
+
+impl std::fmt::Display for RequestFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestFilter::Program(vk) => write!(f, "program {}", vk),
+            RequestFilter::Requester(address) => write!(f, "requester {}", address),
+        }
+    }
+}
+
+/// Split a request argument into the request ID and, when it is an explorer URL such as
+/// `https://explorer.succinct.xyz/request/0x...`, the explorer's base URL. Anything that is
+/// not a URL is taken as the ID itself.
+pub fn parse_request_input(input: &str) -> Result<(String, Option<String>), RouterError> {
+    let input = input.trim();
+    if !input.starts_with("http://") && !input.starts_with("https://") {
+        return Ok((input.to_string(), None));
+    }
+    let invalid = || {
+        RouterError::InvalidInput(anyhow::anyhow!(
+            "Invalid request URL '{}': expected <explorer>/request/<request_id>",
+            input
+        ))
+    };
+    let url = reqwest::Url::parse(input).map_err(|_| invalid())?;
+    let segments: Vec<&str> = url.path_segments().ok_or_else(invalid)?.filter(|segment| !segment.is_empty()).collect();
+    let index = segments.iter().position(|&segment| segment == "request").ok_or_else(invalid)?;
+    let request_id = segments.get(index + 1).ok_or_else(invalid)?;
+    // Everything before `/request`, for explorers served under a path
+    let mut api_base = url.origin().ascii_serialization();
+    for segment in &segments[..index] {
+        api_base.push('/');
+        api_base.push_str(segment);
+    }
+    Ok((request_id.to_string(), Some(api_base)))
 }
 
+// Full-object SHA-256 S3 reports for the artifact (base64), when it was uploaded with one;
+// multipart checksums (`<hash>-<parts>`) are of the parts and cannot be checked here
+fn storage_checksum(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let checksum = headers.get("x-amz-checksum-sha256")?.to_str().ok()?;
+    (!checksum.contains('-')).then(|| checksum.to_string())
+}
+
+/// Buffer between the response body and the artifact file
+const WRITE_BUFFER_SIZE: usize = 64 * 1024;
+
+/// An artifact written to disk by [`ProofClient::download_artifact`]
+#[derive(Debug, Clone)]
+pub struct DownloadedArtifact {
+    pub len: u64,
+    /// Hex SHA-256 of the whole file
+    pub sha256: String,
+}
+
+// First of the environment variables that is set and not empty
+fn env_var(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+}
+
+pub(crate) fn too_large(len: u64, max_size: u64) -> anyhow::Error {
+    FailureClass::BadInput.error(format!(
+        "Refusing artifact of {} bytes or more, above the --max-artifact-size of {} bytes",
+        len, max_size
+    ))
+}
+
+/// Parse a byte count such as `500M`, `2G`, `64KiB` or `1048576`; units are powers of 1024
+pub fn parse_byte_size(s: &str) -> Result<u64, RouterError> {
+    let invalid = || {
+        RouterError::InvalidInput(anyhow::anyhow!(
+            "Invalid size '{}': expected a number of bytes with an optional K, M or G unit",
+            s
+        ))
+    };
+    let s = s.trim();
+    let (number, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        _ => return Err(invalid()),
+    };
+    number.checked_mul(multiplier).ok_or_else(invalid)
+}
+
+// Empty the file to download it again from the start
+async fn restart(writer: &mut BufWriter<tokio::fs::File>, hasher: &mut Sha256, len: &mut u64) -> Result<()> {
+    writer.flush().await?;
+    writer.get_ref().set_len(0).await?;
+    *hasher = Sha256::new();
+    *len = 0;
+    Ok(())
+}
+
+// Object size from a `Content-Range: bytes <first>-<last>/<size>` header
+fn content_range_total(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    headers.get(CONTENT_RANGE)?.to_str().ok()?.rsplit('/').next()?.parse().ok()
+}
+
+pub const DEFAULT_API_BASE: &str = "https://explorer.succinct.xyz";
+
 pub struct ProofClient {
-    client: Client,
+    client: HttpClient,
     api_base: String,
     verbose: bool,
+    max_artifact_size: Option<u64>,
+    max_download_rate: Option<u64>,
+    strategies: Vec<ExtractionStrategy>,
+    explorer_api_url: Option<String>,
+    browser_options: BrowserOptions,
+    browser_pool_size: usize,
+    // Created on the first render, once the proxy settings are final
+    browser: OnceCell<BrowserPool>,
+    api_key: Option<String>,
+    api_key_header: Option<HeaderName>,
+    #[cfg(feature = "s3")]
+    s3: Option<S3Downloader>,
 }
 
 impl ProofClient {
     pub fn new() -> Self {
         Self {
-            client: Client::new(),
-            api_base: "https://explorer.succinct.xyz".to_string(),
+            client: HttpClient::default(),
+            api_base: DEFAULT_API_BASE.to_string(),
             verbose: false,
+            max_artifact_size: None,
+            max_download_rate: None,
+            strategies: DEFAULT_STRATEGIES.to_vec(),
+            explorer_api_url: None,
+            browser_options: BrowserOptions::default(),
+            browser_pool_size: 4,
+            browser: OnceCell::new(),
+            api_key: None,
+            api_key_header: None,
+            #[cfg(feature = "s3")]
+            s3: None,
         }
     }
 
     pub fn new_with_options(api_base: &str, verbose: bool) -> Self {
         Self {
-            client: Client::new(),
+            client: HttpClient::default(),
             api_base: api_base.to_string(),
             verbose,
+            max_artifact_size: None,
+            max_download_rate: None,
+            strategies: DEFAULT_STRATEGIES.to_vec(),
+            explorer_api_url: None,
+            browser_options: BrowserOptions::default(),
+            browser_pool_size: 4,
+            browser: OnceCell::new(),
+            api_key: None,
+            api_key_header: None,
+            #[cfg(feature = "s3")]
+            s3: None,
+        }
+    }
+
+    /// Send page renders and artifact downloads under `policy` instead of the default (no retries)
+    pub fn with_http_policy(mut self, policy: HttpPolicy) -> Result<Self, RouterError> {
+        self.client = HttpClient::new(policy)?;
+        Ok(self)
+    }
+
+    /// Refuse artifacts larger than `max_artifact_size` bytes, before or while downloading them
+    pub fn with_max_artifact_size(mut self, max_artifact_size: Option<u64>) -> Self {
+        self.max_artifact_size = max_artifact_size;
+        self
+    }
+
+    /// Download artifacts at no more than `max_download_rate` bytes per second; 0 is unlimited
+    pub fn with_max_download_rate(mut self, max_download_rate: Option<u64>) -> Self {
+        self.max_download_rate = max_download_rate.filter(|&rate| rate > 0);
+        self
+    }
+
+    /// Extraction strategies to try, in order, instead of [`DEFAULT_STRATEGIES`]
+    pub fn with_extraction_strategies(mut self, strategies: Vec<ExtractionStrategy>) -> Self {
+        self.strategies = strategies;
+        self
+    }
+
+    /// Explorer API URL answering a request's metadata as JSON, with `{request_id}` standing
+    /// for the request; the json-api strategy is skipped without one
+    pub fn with_explorer_api_url(mut self, explorer_api_url: Option<String>) -> Self {
+        self.explorer_api_url = explorer_api_url;
+        self
+    }
+
+    /// Browser binary, selector to wait for and time limit for rendering explorer pages
+    pub fn with_browser_options(mut self, browser_options: BrowserOptions) -> Self {
+        self.browser_options = browser_options;
+        self
+    }
+
+    /// Key sent on explorer API requests, in `header` or else as a bearer token
+    pub fn with_api_key(mut self, api_key: Option<String>, header: Option<HeaderName>) -> Self {
+        self.api_key = api_key;
+        self.api_key_header = header;
+        self
+    }
+
+    /// Download S3 artifacts in parallel ranged parts, with the AWS SDK when credentials are
+    /// configured, instead of one streamed GET
+    #[cfg(feature = "s3")]
+    pub fn with_s3(mut self, options: Option<S3Options>) -> Self {
+        self.s3 = options.map(S3Downloader::new);
+        self
+    }
+
+    /// Explorer pages the shared browser loads at the same time (default 4)
+    pub fn with_browser_pool_size(mut self, browser_pool_size: usize) -> Self {
+        self.browser_pool_size = browser_pool_size;
+        self
+    }
+
+    /// Close the browser rendering explorer pages; a later fetch launches it again
+    pub async fn close_browser(&self) {
+        if let Some(browser) = self.browser.get() {
+            browser.close().await;
+        }
+    }
+
+    // The browser only reads proxy variables on some desktops, so pass them on explicitly, as
+    // Chrome's per-scheme rules (`https=...;http=...`) where HTTPS_PROXY and HTTP_PROXY apply
+    // as they do to the HTTP client, ALL_PROXY covering a scheme neither sets
+    fn browser_proxy(&self) -> Option<String> {
+        if let Some(proxy) = &self.client.policy().proxy {
+            return Some(proxy.clone());
+        }
+        let all = env_var(&["ALL_PROXY", "all_proxy"]);
+        let https = env_var(&["HTTPS_PROXY", "https_proxy"]).or_else(|| all.clone());
+        let http = env_var(&["HTTP_PROXY", "http_proxy"]).or(all);
+        match (https, http) {
+            (Some(https), Some(http)) if https == http => Some(https),
+            (https, http) => {
+                let rules: Vec<String> = [("https", https), ("http", http)]
+                    .into_iter()
+                    .filter_map(|(scheme, proxy)| proxy.map(|proxy| format!("{}={}", scheme, proxy)))
+                    .collect();
+                (!rules.is_empty()).then(|| rules.join(";"))
+            }
         }
     }
 
-    pub async fn fetch_request_metadata(&self, request_id: &str) -> Result<ProofRequestMetadata> {
-        // Use headless browser to render the page and extract data
+    // Render an explorer page with a headless browser and return the resulting DOM
+    async fn render_page(&self, url: &str) -> Result<String> {
+        debug!("Rendering {} with a headless browser", url);
+        let browser = self
+            .browser
+            .get_or_init(|| async {
+                let options = self
+                    .browser_options
+                    .clone()
+                    .with_proxy(self.browser_proxy(), env_var(&["NO_PROXY", "no_proxy"]));
+                BrowserPool::new(options, self.browser_pool_size)
+            })
+            .await;
+        let html_content = browser.render(url).await?;
+        debug!("Rendered HTML length: {}", html_content.len());
+        Ok(html_content)
+    }
+
+    /// Requests listed on the explorer page of a program or requester, newest first
+    pub async fn list_requests(&self, filter: &RequestFilter) -> Result<Vec<RequestSummary>, RouterError> {
+        let url = format!("{}/{}", self.api_base, filter.path());
+        let html_content = self.render_explorer_page(&url).await?;
+        let requests = extract_request_list(&html_content);
+        debug!("Found {} requests on {}", requests.len(), url);
+        Ok(requests)
+    }
+
+    /// Find a request's artifact URL and VK with each extraction strategy in turn, until one
+    /// has found the artifact URL. The page is only rendered when a strategy needs it.
+    pub async fn fetch_request_metadata(&self, request_id: &str) -> Result<ProofRequestMetadata, RouterError> {
         let url = format!("{}/request/{}", self.api_base, request_id);
-        println!("=== RENDERING PAGE WITH HEADLESS BROWSER ===");
-        println!("URL: {}", url);
-        
-        // Use std::process::Command to run chromium-browser
-        let output = std::process::Command::new("chromium-browser")
-            .args(&[
-                "--headless",
-                "--disable-gpu", 
-                "--no-sandbox",
-                "--dump-dom",
-                &url
-            ])
-            .output()?;
-            
-        if !output.status.success() {
-            anyhow::bail!("Failed to render page: {}", String::from_utf8_lossy(&output.stderr));
-        }
-        
-        let html_content = String::from_utf8_lossy(&output.stdout);
-        println!("Rendered HTML length: {}", html_content.len());
-        println!("DEBUG: Starting VK extraction...");
-        
+        let mut html_content = None;
+        let mut artifact_url = None;
+        let mut vk = None;
+        let mut fields = BTreeMap::new();
+        for &strategy in &self.strategies {
+            let extracted = match strategy {
+                ExtractionStrategy::JsonApi => match &self.explorer_api_url {
+                    Some(api_url) => self.fetch_api_metadata(&api_url.replace("{request_id}", request_id)).await,
+                    None => continue,
+                },
+                _ => {
+                    if html_content.is_none() {
+                        html_content = Some(self.render_explorer_page(&url).await?);
+                    }
+                    let html = html_content.as_deref().unwrap_or_default();
+                    match strategy {
+                        ExtractionStrategy::NextData => extract_from_next_data(html),
+                        ExtractionStrategy::ScriptTags => extract_from_script_tags(html),
+                        _ => extract_from_dom(html),
+                    }
+                }
+            };
+            if extracted.is_empty() {
+                debug!("{} extraction found nothing for {}", strategy, request_id);
+                continue;
+            }
+            if fields.is_empty() {
+                fields = extracted.fields;
+            }
+            if vk.is_none()
+                && let Some(found) = extracted.vk
+            {
+                info!("VK of {} found by {} extraction: {}", request_id, strategy, found);
+                vk = Some(found);
+            }
+            if let Some(found) = extracted.artifact_url {
+                info!("Artifact URL of {} found by {} extraction: {}", request_id, strategy, found);
+                artifact_url = Some(found);
+                break;
+            }
+            debug!("{} extraction found no artifact URL for {}", strategy, request_id);
+        }
+
+        let details = RequestDetails::from_fields(&fields);
+        match artifact_url {
+            Some(url) => Ok(ProofRequestMetadata {
+                artifact_url: url,
+                vk: vk.unwrap_or_default(),
+                artifact_sha256: None,
+                details,
+                fields,
+            }),
+            // Waiting cannot help a request the network gave up on
+            None if details.is_unfulfillable() => Err(RouterError::InvalidInput(FailureClass::BadInput.error(format!(
+                "Request {} is unfulfillable: the prover network gave up on it, so it has no proof",
+                request_id
+            )))),
+            None => Err(RouterError::Extraction(FailureClass::Network.error(format!(
+                "Failed to extract artifact URL with strategies {}",
+                self.strategies.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+            )))),
+        }
+    }
+
+    async fn render_explorer_page(&self, url: &str) -> Result<String> {
+        // A missing browser is not worth retrying
+        self.browser_options.find_executable()?;
+        let html_content = self
+            .client
+            .retry("Rendering the explorer page", || self.render_page(url))
+            .await
+            .classify(FailureClass::Network)?;
         // Print a small snippet if verbose mode is enabled
         if self.verbose {
             let preview = html_content.chars().take(500).collect::<String>();
-            println!("HTML preview (first 500 chars): {}", preview);
-        }
-        
-
-        
-        // Extract artifact URL using regex
-        let artifact_pattern = r#"(https://spn-artifacts-mainnet\.s3[^"<>\s]*)"#;
-        
-        let artifact_url = if let Ok(re) = regex::Regex::new(artifact_pattern) {
-            re.captures(&html_content)
-                .and_then(|caps| caps.get(1))
-                .map(|m| {
-                    let url = m.as_str().to_string();
-                    // Decode HTML entities
-                    url.replace("&amp;", "&")
-                       .replace("&lt;", "<")
-                       .replace("&gt;", ">")
-                       .replace("&quot;", "\"")
-                       .replace("&#39;", "'")
-                })
-        } else {
-            None
-        };
+            debug!("HTML preview (first 500 chars): {}", preview);
+        }
+        Ok(html_content)
+    }
 
-        // Extract VK from "Program Blobstream" section
-        let possible_keywords = ["Program Blobstream", "Blobstream", "Program"];
-        let mut found_keyword = None;
-        let mut found_position = 0;
-        
-        for keyword in &possible_keywords {
-            if let Some(pos) = html_content.find(keyword) {
-                found_keyword = Some(keyword);
-                found_position = pos;
-                break;
+    // Metadata from the explorer API; a failure only moves on to the next strategy
+    async fn fetch_api_metadata(&self, api_url: &str) -> Extracted {
+        let response = match self.client.send(|client| self.authorize(client.get(api_url))).await {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) if matches!(response.status(), StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => {
+                warn!(
+                    "Explorer API refused the request ({}), {}",
+                    response.status(),
+                    if self.api_key.is_some() { "check --api-key" } else { "it may need --api-key" }
+                );
+                return Extracted::default();
+            }
+            Ok(response) => {
+                debug!("Explorer API answered {} for {}", response.status(), api_url);
+                return Extracted::default();
+            }
+            Err(e) => {
+                debug!("Explorer API request to {} failed: {:#}", api_url, e);
+                return Extracted::default();
+            }
+        };
+        match response.json::<serde_json::Value>().await {
+            Ok(json) => extract_from_json(&json),
+            Err(e) => {
+                debug!("Unreadable explorer API response from {}: {}", api_url, e);
+                Extracted::default()
             }
         }
-        
-        let vk = if let Some(keyword) = found_keyword {
-            // Look for VK pattern around this section (look back and forward)
-            let search_start = found_position.saturating_sub(1000);
-            let search_end = (found_position + 2000).min(html_content.len());
-            let search_section = &html_content[search_start..search_end];
-            
-            // Look for VK pattern (32 bytes = 64 hex chars)
-            let vk_pattern = r#"0x[0-9a-fA-F]{64}"#;
-            if let Ok(re) = regex::Regex::new(vk_pattern) {
-                if let Some(captures) = re.captures(search_section) {
-                    let found_vk = captures[0].to_string();
-                    println!("✅ VK found: {}", found_vk);
-                    Some(found_vk)
-                } else {
-                    // Let's also search the entire HTML for any VK pattern
-                    if let Some(captures) = re.captures(&html_content) {
-                        let found_vk = captures[0].to_string();
-                        println!("✅ VK found: {}", found_vk);
-                        Some(found_vk)
-                    } else {
-                        println!("❌ No VK pattern found");
-                        None
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match (&self.api_key, &self.api_key_header) {
+            (Some(api_key), Some(header)) => request.header(header, api_key),
+            (Some(api_key), None) => request.bearer_auth(api_key),
+            (None, _) => request,
+        }
+    }
+
+    /// Stream an artifact into the file at `path`, returning its length and SHA-256. Bytes
+    /// already in the file are kept and the download resumes after them with a Range
+    /// request, as it also does when the connection drops (as often as the HTTP policy
+    /// retries), so an interrupted download can be continued by a later call.
+    pub async fn download_artifact(&self, artifact_url: &str, path: &Path) -> Result<DownloadedArtifact, RouterError> {
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        #[cfg(feature = "s3")]
+        if let Some(s3) = &self.s3
+            && let Some(object) = parse_s3_url(artifact_url)
+        {
+            return Ok(s3
+                .download(&self.client, artifact_url, &object, path, self.max_artifact_size, self.max_download_rate)
+                .await?);
+        }
+        let mut hasher = Sha256::new();
+        let mut len = match tokio::fs::try_exists(path).await? {
+            true => hash_file(path, &mut hasher).await?,
+            false => 0,
+        };
+        let file = tokio::fs::OpenOptions::new().create(true).append(true).open(path).await?;
+        let mut writer = BufWriter::with_capacity(WRITE_BUFFER_SIZE, file);
+        if len > 0 {
+            status!("⏯️  Resuming artifact download at {} bytes", len);
+        }
+
+        let mut bar = None;
+        let mut checksum = None;
+        let mut interruptions = 0;
+        let expected_len = loop {
+            let mut response = self
+                .client
+                .send(|client| match len {
+                    0 => client.get(artifact_url),
+                    offset => client.get(artifact_url).header(RANGE, format!("bytes={}-", offset)),
+                })
+                .await
+                .classify(FailureClass::Network)?;
+
+            match response.status() {
+                StatusCode::PARTIAL_CONTENT => {}
+                StatusCode::OK => {
+                    checksum = storage_checksum(response.headers());
+                    if len > 0 {
+                        debug!("Artifact storage ignored the range, downloading from the start");
+                        restart(&mut writer, &mut hasher, &mut len).await?;
                     }
                 }
-            } else {
-                println!("❌ Failed to compile VK regex");
-                None
+                // What is in the file is of a different or already complete object
+                StatusCode::RANGE_NOT_SATISFIABLE if len > 0 => {
+                    debug!("Range not satisfiable, downloading from the start");
+                    restart(&mut writer, &mut hasher, &mut len).await?;
+                    continue;
+                }
+                status => {
+                    return Err(FailureClass::Network.error(format!("Failed to download artifact: {}", status)).into());
+                }
             }
-        } else {
-            println!("❌ No keywords found in HTML");
-            None
-        };
-        
+            let expected_len = match response.status() {
+                StatusCode::PARTIAL_CONTENT => content_range_total(response.headers()),
+                _ => response.content_length(),
+            };
 
-        
-        match artifact_url {
-            Some(url) => {
-                println!("✅ Found artifact URL: {}", url);
-                Ok(ProofRequestMetadata { 
-                    artifact_url: url.clone(),
-                    vk: vk.unwrap_or_default(),
-                })
+            if let (Some(max_size), Some(expected_len)) = (self.max_artifact_size, expected_len)
+                && expected_len > max_size
+            {
+                drop(writer);
+                tokio::fs::remove_file(path).await.ok();
+                return Err(too_large(expected_len, max_size).into());
             }
-            None => {
-                println!("❌ Missing artifact URL");
-                anyhow::bail!("Failed to extract artifact URL from rendered page")
+
+            let bar = bar.get_or_insert_with(|| progress::download("Downloading artifact".to_string(), expected_len));
+            bar.set_position(len);
+            let started = Instant::now();
+            let mut received = 0;
+            let interruption = loop {
+                let next = match self.client.policy().read_timeout {
+                    Some(read_timeout) => match tokio::time::timeout(read_timeout, response.chunk()).await {
+                        Ok(next) => next.map_err(|e| e.to_string()),
+                        Err(_) => Err(format!("no data for {:?}", read_timeout)),
+                    },
+                    None => response.chunk().await.map_err(|e| e.to_string()),
+                };
+                match next {
+                    Ok(Some(chunk)) => {
+                        writer.write_all(&chunk).await?;
+                        hasher.update(&chunk);
+                        len += chunk.len() as u64;
+                        bar.inc(chunk.len() as u64);
+                        if let Some(max_size) = self.max_artifact_size
+                            && len > max_size
+                        {
+                            drop(writer);
+                            tokio::fs::remove_file(path).await.ok();
+                            return Err(too_large(len, max_size).into());
+                        }
+                        // Hold back until the bytes so far fit the rate
+                        received += chunk.len() as u64;
+                        if let Some(rate) = self.max_download_rate {
+                            let due = started + Duration::from_secs_f64(received as f64 / rate as f64);
+                            tokio::time::sleep_until(due).await;
+                        }
+                    }
+                    Ok(None) if expected_len.is_some_and(|expected_len| len < expected_len) => {
+                        break Some("connection closed early".to_string());
+                    }
+                    Ok(None) => break None,
+                    Err(reason) => break Some(reason),
+                }
+            };
+            writer.flush().await?;
+
+            match interruption {
+                None => break expected_len,
+                Some(reason) if interruptions < self.client.policy().max_retries => {
+                    interruptions += 1;
+                    let delay = self.client.policy().retry_delay(interruptions);
+                    warn!(
+                        "Artifact download interrupted at {} bytes ({}), retry {}/{} in {:?}",
+                        len,
+                        reason,
+                        interruptions,
+                        self.client.policy().max_retries,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Some(reason) => {
+                    return Err(FailureClass::Network
+                        .error(format!("Artifact download interrupted at {} bytes: {}", len, reason))
+                        .into());
+                }
             }
-        }
-    }
+        };
+        drop(writer);
 
-    pub async fn download_artifact(&self, artifact_url: &str) -> Result<Vec<u8>> {
-        let response = self.client.get(artifact_url).send().await?;
-        
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to download artifact: {}", response.status());
+        let sha256 = hasher.finalize();
+        let problem = match (expected_len, checksum) {
+            (Some(expected_len), _) if len != expected_len => {
+                Some(format!("Artifact download truncated: got {} of {} bytes", len, expected_len))
+            }
+            (_, Some(checksum)) if base64::engine::general_purpose::STANDARD.encode(sha256) != checksum => Some(format!(
+                "Artifact is corrupted: SHA-256 {} does not match the storage checksum {}",
+                hex::encode(sha256),
+                checksum
+            )),
+            _ => None,
+        };
+        if let Some(problem) = problem {
+            // A bad download must not be resumed
+            if let Err(e) = tokio::fs::remove_file(path).await {
+                debug!("Failed to remove {}: {}", path.display(), e);
+            }
+            return Err(FailureClass::Network.error(problem).into());
         }
-        
-        let artifact_data = response.bytes().await?;
-        Ok(artifact_data.to_vec())
+        Ok(DownloadedArtifact { len, sha256: hex::encode(sha256) })
     }
 }
@@ -0,0 +1,176 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::client::DEFAULT_API_BASE;
+pub use crate::credentials::KeySource;
+
+/// Known zkVerify networks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    /// The zkVerify testnet, also accepted as `testnet`
+    #[default]
+    #[serde(alias = "testnet")]
+    Volta,
+    Mainnet,
+    /// A dev node on this machine, e.g. `zkv-node --dev`
+    Local,
+}
+
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "volta" | "testnet" => Ok(Network::Volta),
+            "mainnet" => Ok(Network::Mainnet),
+            "local" => Ok(Network::Local),
+            _ => anyhow::bail!("Unknown network '{}': expected volta, testnet, mainnet or local", s),
+        }
+    }
+}
+
+/// Decimals of the native token on every network
+pub const TOKEN_DECIMALS: u32 = 18;
+
+impl Network {
+    /// The preset whose RPC endpoint is `ws_url`, if any
+    pub fn from_ws_url(ws_url: &str) -> Option<Self> {
+        let ws_url = ws_url.trim_end_matches('/');
+        [Network::Volta, Network::Mainnet, Network::Local]
+            .into_iter()
+            .find(|network| network.ws_url() == ws_url)
+    }
+
+    pub fn ws_url(&self) -> &'static str {
+        match self {
+            Network::Volta => "wss://zkverify-volta-rpc.zkverify.io",
+            Network::Mainnet => "wss://zkverify-rpc.zkverify.io",
+            Network::Local => "ws://127.0.0.1:9944",
+        }
+    }
+
+    /// Succinct explorer requests are read from; every network routes Prover Network proofs
+    pub fn api_base(&self) -> &'static str {
+        DEFAULT_API_BASE
+    }
+
+    /// Address prefix; every network runs the zkVerify runtime, so they share it
+    pub fn ss58_prefix(&self) -> u16 {
+        251
+    }
+
+    /// Block explorer base URL; local nodes have none
+    pub fn explorer_url(&self) -> Option<&'static str> {
+        match self {
+            Network::Volta => Some("https://zkverify-testnet.subscan.io"),
+            Network::Mainnet => Some("https://zkverify.subscan.io"),
+            Network::Local => None,
+        }
+    }
+
+    pub fn extrinsic_url(&self, tx_hash: &str) -> Option<String> {
+        self.explorer_url().map(|explorer| format!("{}/extrinsic/{}", explorer, tx_hash))
+    }
+
+    pub fn token_symbol(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "VFY",
+            Network::Volta | Network::Local => "tVFY",
+        }
+    }
+
+    /// `planck` in whole tokens, e.g. `1.5 tVFY`
+    pub fn format_balance(&self, planck: u128) -> String {
+        let unit = 10u128.pow(TOKEN_DECIMALS);
+        let fraction = format!("{:0width$}", planck % unit, width = TOKEN_DECIMALS as usize);
+        let fraction = fraction.trim_end_matches('0');
+        match fraction.is_empty() {
+            true => format!("{} {}", planck / unit, self.token_symbol()),
+            false => format!("{}.{} {}", planck / unit, fraction, self.token_symbol()),
+        }
+    }
+
+    /// Where to get test tokens; mainnet has no faucet
+    pub fn faucet_hint(&self) -> Option<&'static str> {
+        match self {
+            Network::Volta => Some("Request test tokens at https://zkverify-faucet.zkverify.io"),
+            Network::Local => Some("Transfer tokens from a dev account such as //Alice"),
+            Network::Mainnet => None,
+        }
+    }
+}
+
+/// Named setups from the config file, e.g.
+///
+/// ```toml
+/// default_profile = "testnet"
+///
+/// [profiles.testnet]
+/// ws_url = "wss://zkverify-volta-rpc.zkverify.io"
+/// key = { env = "ZKV_MNEMONIC" }
+///
+/// [profiles.mainnet]
+/// network = "mainnet"
+/// key = { file = "/etc/rusty_router/mainnet.mnemonic" }
+/// domain_id = 1
+/// output_dir = "routed/mainnet"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Profile used when `--profile` is not given
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Settings of one profile; flags given on the command line take precedence
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Preset the other settings default to
+    pub network: Option<Network>,
+    pub api_base: Option<String>,
+    pub ws_url: Option<String>,
+    /// Where the signing mnemonic is read from
+    pub key: Option<KeySource>,
+    pub domain_id: Option<u32>,
+    /// Directory converted proofs of batches and watches are written to
+    pub output_dir: Option<PathBuf>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/rusty_router/config.toml`, falling back to `~/.config`
+    pub fn default_path() -> Option<PathBuf> {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(config_home.join("rusty_router").join("config.toml"))
+    }
+
+    pub async fn load(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read config file {}: {}", path.display(), e))?;
+        toml::from_str(&content).map_err(|e| anyhow::anyhow!("Invalid config file {}: {}", path.display(), e))
+    }
+
+    /// The named profile, or the default one when `name` is `None`
+    pub fn profile(&self, name: Option<&str>) -> Result<Option<&Profile>> {
+        let Some(name) = name.or(self.default_profile.as_deref()) else {
+            return Ok(None);
+        };
+        match self.profiles.get(name) {
+            Some(profile) => Ok(Some(profile)),
+            None => anyhow::bail!(
+                "Unknown profile {}; the config defines: {}",
+                name,
+                self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::Result;
+use glob::Pattern;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use subxt::ext::futures::{stream, StreamExt};
+
+use crate::backend::ProofInputs;
+use crate::batch::{icon, BatchOutcome, BatchStatus};
+use crate::converter::ProofType;
+use crate::failure::FailureClass;
+use crate::output::write_atomic;
+use crate::router::Router;
+
+/// Name of the file listing every artifact's result, written to the output directory
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+pub struct ConvertDirOptions {
+    /// Directory searched recursively for SP1 artifacts
+    pub input_dir: PathBuf,
+    /// Directory converted proofs are written to, in the same layout as `input_dir`
+    pub output_dir: PathBuf,
+    /// Artifacts are the files whose path relative to `input_dir` matches
+    pub pattern: Pattern,
+    /// Artifacts converted at the same time
+    pub jobs: usize,
+    /// Conversion options shared by every artifact; the proof path is each artifact's
+    pub inputs: ProofInputs,
+}
+
+/// One artifact's entry in the manifest
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestEntry {
+    /// Artifact path, relative to the input directory
+    pub input: PathBuf,
+    /// Converted proof path, relative to the output directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<PathBuf>,
+    /// `converted` or `failed`
+    pub status: String,
+    /// Hex VK the proof was converted with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vk: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Class of the error, e.g. `conversion` or `bad-input`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_class: Option<&'static str>,
+    pub seconds: f64,
+}
+
+/// Convert every artifact under `options.input_dir` matching `options.pattern`, write the
+/// manifest, and report how each one ended, in path order
+pub async fn convert_dir(router: &Router, options: ConvertDirOptions) -> Result<Vec<BatchOutcome>> {
+    let artifacts = find_artifacts(&options.input_dir, &options.pattern).await?;
+    if artifacts.is_empty() {
+        return Err(FailureClass::BadInput.error(format!(
+            "No files in {} match '{}'",
+            options.input_dir.display(),
+            options.pattern
+        )));
+    }
+    let outputs = output_paths(router, &artifacts);
+    tokio::fs::create_dir_all(&options.output_dir).await?;
+    status!("📦 Converting {} artifacts from {}", artifacts.len(), options.input_dir.display());
+
+    let total = artifacts.len();
+    let options = &options;
+    let mut results = stream::iter(artifacts.into_iter().zip(outputs).enumerate())
+        .map(|(index, (relative, output))| async move {
+            let entry = convert_one(router, options, &relative, output).await;
+            (index, entry)
+        })
+        .buffered(options.jobs.max(1));
+
+    let mut entries = Vec::with_capacity(total);
+    let mut outcomes = Vec::with_capacity(total);
+    while let Some((index, entry)) = results.next().await {
+        let outcome = match (&entry.output, &entry.error) {
+            (Some(output), None) => BatchOutcome {
+                request_id: entry.input.display().to_string(),
+                status: BatchStatus::Converted,
+                detail: options.output_dir.join(output).display().to_string(),
+            },
+            (_, error) => BatchOutcome {
+                request_id: entry.input.display().to_string(),
+                status: BatchStatus::Failed,
+                detail: error.clone().unwrap_or_default(),
+            },
+        };
+        status!("{} [{}/{}] {}: {}", icon(outcome.status), index + 1, total, outcome.request_id, outcome.detail);
+        entries.push(entry);
+        outcomes.push(outcome);
+    }
+
+    let manifest = options.output_dir.join(MANIFEST_FILE_NAME);
+    write_atomic(&manifest, serde_json::to_vec_pretty(&entries)?).await?;
+    status!("📝 Manifest written to {}", manifest.display());
+    Ok(outcomes)
+}
+
+// Where each artifact's proof goes, relative to the output directory: `dir/a.bin` is written
+// as `dir/a.json` (`dir/a.cbor`, ... with --format, plus `.zst` compressed), or as
+// `dir/a.bin.json` when another artifact in `dir` has the same stem, e.g. `dir/a.proof`
+fn output_paths(router: &Router, artifacts: &[PathBuf]) -> Vec<PathBuf> {
+    let by_stem = |relative: &PathBuf| {
+        let stem = relative.file_stem().unwrap_or_default().to_string_lossy();
+        relative.with_file_name(router.output_file_name(&stem))
+    };
+    let mut counts = HashMap::new();
+    for relative in artifacts {
+        *counts.entry(by_stem(relative)).or_insert(0) += 1;
+    }
+    artifacts
+        .iter()
+        .map(|relative| {
+            let output = by_stem(relative);
+            match counts[&output] {
+                1 => output,
+                _ => {
+                    let name = relative.file_name().unwrap_or_default().to_string_lossy();
+                    relative.with_file_name(router.output_file_name(&name))
+                }
+            }
+        })
+        .collect()
+}
+
+async fn convert_one(router: &Router, options: &ConvertDirOptions, relative: &Path, output: PathBuf) -> ManifestEntry {
+    let started = Instant::now();
+    let inputs = ProofInputs { proof: options.input_dir.join(relative), ..options.inputs.clone() };
+
+    let result = async {
+        let output_path = options.output_dir.join(&output);
+        if let Some(parent) = output_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        router.convert_local(ProofType::Sp1, &inputs, None, &output_path).await
+    }
+    .await;
+    let (output, vk, error, error_class) = match result {
+        Ok(converted_proof) => (Some(output), Some(converted_proof.vk), None, None),
+        Err(e) => (None, None, Some(format!("{:#}", e)), Some(FailureClass::of(&e).name())),
+    };
+    ManifestEntry {
+        input: relative.to_path_buf(),
+        status: if error.is_none() { "converted" } else { "failed" }.to_string(),
+        output,
+        vk,
+        error,
+        error_class,
+        seconds: started.elapsed().as_secs_f64(),
+    }
+}
+
+// Paths relative to `dir` of the files under it matching `pattern`, sorted; `*` also matches
+// across directories. Hidden files and directories are skipped.
+async fn find_artifacts(dir: &Path, pattern: &Pattern) -> Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        let mut entries = tokio::fs::read_dir(dir.join(&relative))
+            .await
+            .map_err(|e| FailureClass::BadInput.error(format!("Failed to read {}: {}", dir.join(&relative).display(), e)))?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = relative.join(entry.file_name());
+            let file_type = entry.file_type().await?;
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if pattern.matches_path(&path) {
+                found.push(path);
+            }
+        }
+    }
+    found.sort();
+    Ok(found)
+}
@@ -2,21 +2,482 @@
 use serde::{Deserialize, Serialize};
 use sp1_sdk::{ProverClient, SP1ProofWithPublicValues, HashableKey};
 use sp1_zkv_sdk::*;
+use std::borrow::Cow;
+use std::fmt;
 use std::path::Path;
+use std::str::FromStr;
+use subxt::ext::codec::Encode;
+use subxt::ext::futures::future::BoxFuture;
+use tracing::warn;
+
+use crate::abi::PubsAbi;
+use crate::backend::{bytes_value, CallArgs, ProofBackend, ProofInputs, ScaleArgs};
+use crate::extract::RequestDetails;
+use crate::failure::{FailureClass, ResultExt, RouterError};
+use crate::fflonk::FflonkBackend;
+use crate::groth16::{Curve, Groth16Backend};
+use crate::output::write_atomic;
+use crate::plonky2::{Plonky2Backend, Plonky2Params};
+use crate::sp1_version::load_artifact;
+use crate::substrate::decode_vk;
+use crate::ultraplonk::UltraPlonkBackend;
+use crate::warnings::{Warning, WarningCode};
+
+/// Serialized proofs above this size get a `large-proof` warning
+pub const LARGE_PROOF_THRESHOLD: usize = 1024 * 1024;
+
+/// Layout version of the converted proofs this build writes and reads. Files without a
+/// `format_version` predate it and may use deprecated field spellings.
+pub const FORMAT_VERSION: u32 = 1;
+
+// VK of SP1 proof files written before `vk` was, all for the program first routed
+const LEGACY_VK: &str = "0x50f8a2481aff84670a96db9126c7f4533f9f7e912129edfe3d35e4e81aa32472";
+
+/// First bytes of every zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+// Refuse proofs written by a newer build, whose layout we may misread
+fn check_format_version(version: u32) -> Result<()> {
+    if version > FORMAT_VERSION {
+        return Err(FailureClass::BadInput.error(format!(
+            "Proof file has format_version {}, but this build reads up to {}; upgrade rusty_router",
+            version, FORMAT_VERSION
+        )));
+    }
+    Ok(())
+}
+
+/// Rewrite the deprecated spellings of a proof file without `format_version` into the current
+/// layout, returning a note for each one found
+pub fn normalize_unversioned(object: &mut serde_json::Map<String, serde_json::Value>) -> Vec<String> {
+    let mut deprecations = Vec::new();
+    if let Some(pubs) = object.remove("pub_inputs") {
+        deprecations.push("'pub_inputs' is deprecated, use 'pubs'".to_string());
+        object.entry("pubs").or_insert(pubs);
+    }
+    let is_sp1 = object.get("proof_type").is_none_or(|proof_type| proof_type == "sp1");
+    if is_sp1 && !object.contains_key("vk") {
+        deprecations.push(format!("No 'vk'; assuming the program first routed, {}", LEGACY_VK));
+        object.insert("vk".to_string(), LEGACY_VK.into());
+    }
+    deprecations
+}
+
+/// Compress a written proof with zstd, as `--compress` does
+pub fn compress(content: &[u8]) -> Result<Vec<u8>, RouterError> {
+    Ok(zstd::encode_all(content, zstd::DEFAULT_COMPRESSION_LEVEL)?)
+}
+
+/// `content` decompressed if it is zstd-compressed, as is otherwise
+pub fn decompress(content: &[u8]) -> Result<Cow<'_, [u8]>, RouterError> {
+    if !content.starts_with(&ZSTD_MAGIC) {
+        return Ok(Cow::Borrowed(content));
+    }
+    zstd::decode_all(content)
+        .map(Cow::Owned)
+        .map_err(|e| RouterError::InvalidInput(anyhow::anyhow!("Invalid zstd-compressed proof: {}", e)))
+}
+
+/// Proof system a converted proof is verified with, one zkVerify settlement pallet each
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofType {
+    #[default]
+    Sp1,
+    /// Noir proofs from Barretenberg (`bb prove`)
+    UltraPlonk,
+    /// Circom proofs from snarkjs
+    Groth16,
+    /// fflonk proofs from snarkjs, with a single public input as in zkSync Era
+    Fflonk,
+    Plonky2,
+}
+
+impl ProofType {
+    pub const ALL: [ProofType; 5] =
+        [ProofType::Sp1, ProofType::UltraPlonk, ProofType::Groth16, ProofType::Fflonk, ProofType::Plonky2];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProofType::Sp1 => "sp1",
+            ProofType::UltraPlonk => "ultraplonk",
+            ProofType::Groth16 => "groth16",
+            ProofType::Fflonk => "fflonk",
+            ProofType::Plonky2 => "plonky2",
+        }
+    }
+
+    /// Backend converting and submitting proofs of this type
+    pub fn backend(&self) -> &'static dyn ProofBackend {
+        match self {
+            ProofType::Sp1 => &Sp1Backend,
+            ProofType::UltraPlonk => &UltraPlonkBackend,
+            ProofType::Groth16 => &Groth16Backend,
+            ProofType::Fflonk => &FflonkBackend,
+            ProofType::Plonky2 => &Plonky2Backend,
+        }
+    }
+
+    /// Pallet proofs of this type are submitted to
+    pub fn pallet(&self) -> &'static str {
+        self.backend().pallet()
+    }
+
+    fn is_sp1(&self) -> bool {
+        *self == ProofType::Sp1
+    }
+}
+
+impl fmt::Display for ProofType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ProofType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "sp1" => Ok(ProofType::Sp1),
+            "ultraplonk" | "noir" => Ok(ProofType::UltraPlonk),
+            "groth16" | "circom" | "snarkjs" => Ok(ProofType::Groth16),
+            "fflonk" => Ok(ProofType::Fflonk),
+            "plonky2" => Ok(ProofType::Plonky2),
+            _ => anyhow::bail!("Unknown proof type '{}': expected sp1, ultraplonk, groth16, fflonk or plonky2", s),
+        }
+    }
+}
+
+/// How converted proofs are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Hex components in a JSON file, as submitted by the router
+    #[default]
+    Json,
+    /// The pallet's SCALE-encoded `submit_proof` arguments, see [`ScaleArgs::to_bytes`]
+    Scale,
+    /// The JSON fields in CBOR, with raw bytes instead of hex
+    Cbor,
+    /// The JSON fields in MessagePack, with raw bytes instead of hex
+    MessagePack,
+}
+
+impl OutputFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Scale => "scale",
+            OutputFormat::Cbor => "cbor",
+            OutputFormat::MessagePack => "msgpack",
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "scale" => Ok(OutputFormat::Scale),
+            "cbor" => Ok(OutputFormat::Cbor),
+            "msgpack" | "messagepack" => Ok(OutputFormat::MessagePack),
+            _ => anyhow::bail!("Unknown output format '{}': expected json, scale, cbor or msgpack", s),
+        }
+    }
+}
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ConvertedProof {
+    /// Layout version, [`FORMAT_VERSION`] for proofs written by this build and 0 for older ones
+    #[serde(default)]
+    pub format_version: u32,
+    /// Absent in SP1 proofs, which came first
+    #[serde(default, skip_serializing_if = "ProofType::is_sp1")]
+    pub proof_type: ProofType,
+    /// Curve of Groth16 proofs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curve: Option<Curve>,
+    /// Hash and compression of Plonky2 proofs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plonky2: Option<Plonky2Params>,
     pub proof: String,
     pub pubs: String,
     pub vk: String,
+    /// Succinct request this proof was converted from, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Warning>,
+    /// What the SP1 artifact declared, known right after its conversion and not saved
+    #[serde(skip)]
+    pub sp1_artifact: Option<Sp1ArtifactInfo>,
+}
+
+/// Proof variant and SP1 version of a converted SP1 artifact, for the conversion stats
+#[derive(Debug, Clone)]
+pub struct Sp1ArtifactInfo {
+    /// core, compressed, plonk or groth16
+    pub variant: &'static str,
+    pub version: String,
+}
+
+// A `ConvertedProof` as stored in the binary formats
+#[derive(Deserialize, Serialize)]
+struct BinaryProof {
+    #[serde(default)]
+    format_version: u32,
+    #[serde(default, skip_serializing_if = "ProofType::is_sp1")]
+    proof_type: ProofType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    curve: Option<Curve>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    plonky2: Option<Plonky2Params>,
+    #[serde(with = "serde_bytes")]
+    proof: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    pubs: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    vk: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<Warning>,
+}
+
+impl ConvertedProof {
+    /// Proof of `proof_type` from its raw components, hex-encoded
+    pub fn new(proof_type: ProofType, proof: &[u8], pubs: &[u8], vk: &[u8]) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            proof_type,
+            curve: None,
+            plonky2: None,
+            proof: to_hex_with_prefix(proof),
+            pubs: to_hex_with_prefix(pubs),
+            vk: to_hex_with_prefix(vk),
+            request_id: None,
+            warnings: Vec::new(),
+            sp1_artifact: None,
+        }
+    }
+
+    /// The raw proof, public inputs and VK
+    pub fn decode(&self) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), RouterError> {
+        let decode = |name: &str, value: &str| {
+            hex::decode(value.trim_start_matches("0x"))
+                .map_err(|e| FailureClass::BadInput.error(format!("'{}' is not valid hex: {}", name, e)))
+        };
+        Ok((decode("proof", &self.proof)?, decode("pubs", &self.pubs)?, decode("vk", &self.vk)?))
+    }
+
+    /// The proof written as `format`
+    pub fn to_bytes(&self, format: OutputFormat) -> Result<Vec<u8>, RouterError> {
+        match format {
+            OutputFormat::Json => Ok(serde_json::to_vec_pretty(self)?),
+            OutputFormat::Scale => {
+                let backend = self.proof_type.backend();
+                backend.verify_locally(self)?;
+                Ok(backend.encode_args(self)?.to_bytes())
+            }
+            OutputFormat::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(&self.to_binary()?, &mut bytes)?;
+                Ok(bytes)
+            }
+            // With field names, so that optional fields can be left out
+            OutputFormat::MessagePack => Ok(rmp_serde::to_vec_named(&self.to_binary()?)?),
+        }
+    }
+
+    /// Parse a proof written as JSON, CBOR or MessagePack, told apart by its first byte, and
+    /// possibly zstd-compressed
+    pub fn from_bytes(content: &[u8]) -> Result<Self, RouterError> {
+        let content = &*decompress(content)?;
+        let binary: BinaryProof = match content.trim_ascii_start().first() {
+            Some(b'{') => return Ok(Self::from_json(content)?.0),
+            // CBOR maps
+            Some(0xa0..=0xbf) => ciborium::from_reader(content)?,
+            // MessagePack maps
+            Some(0x80..=0x8f | 0xde | 0xdf) => rmp_serde::from_slice(content)?,
+            _ => return Err(FailureClass::BadInput.error("Not a JSON, CBOR or MessagePack proof").into()),
+        };
+        check_format_version(binary.format_version)?;
+        Ok(Self {
+            format_version: binary.format_version,
+            proof_type: binary.proof_type,
+            curve: binary.curve,
+            plonky2: binary.plonky2,
+            proof: to_hex_with_prefix(&binary.proof),
+            pubs: to_hex_with_prefix(&binary.pubs),
+            vk: to_hex_with_prefix(&binary.vk),
+            request_id: binary.request_id,
+            warnings: binary.warnings,
+            sp1_artifact: None,
+        })
+    }
+
+    /// Parse a JSON proof of any layout version up to [`FORMAT_VERSION`], normalizing the
+    /// deprecated spellings of unversioned files. Returns the proof and what was deprecated in it.
+    pub fn from_json(content: &[u8]) -> Result<(Self, Vec<String>), RouterError> {
+        let invalid = |e: serde_json::Error| FailureClass::BadInput.error(format!("Invalid proof file: {}", e));
+        let mut value: serde_json::Value = serde_json::from_slice(content).map_err(invalid)?;
+        let Some(object) = value.as_object_mut() else {
+            return Err(FailureClass::BadInput.error("Invalid proof file: not a JSON object").into());
+        };
+        let version = match object.get("format_version") {
+            None => 0,
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or_else(|| FailureClass::BadInput.error(format!("Invalid format_version {}", version)))?,
+        };
+        check_format_version(version)?;
+
+        let deprecations = if version == 0 { normalize_unversioned(object) } else { Vec::new() };
+        Ok((serde_json::from_value(value).map_err(invalid)?, deprecations))
+    }
+
+    fn to_binary(&self) -> Result<BinaryProof> {
+        let (proof, pubs, vk) = self.decode()?;
+        Ok(BinaryProof {
+            format_version: self.format_version,
+            proof_type: self.proof_type,
+            curve: self.curve,
+            plonky2: self.plonky2,
+            proof,
+            pubs,
+            vk,
+            request_id: self.request_id.clone(),
+            warnings: self.warnings.clone(),
+        })
+    }
+
+    /// Write the proof's components as raw bytes into `dir`: `proof.bin` (bincode-serialized
+    /// shrunk proof for SP1, the raw proof otherwise), `pubs.bin` (public values) and `vk.bin`
+    pub async fn write_components(&self, dir: &Path) -> Result<(), RouterError> {
+        tokio::fs::create_dir_all(dir).await?;
+        let (proof, pubs, vk) = self.decode()?;
+        for (name, bytes) in [("proof.bin", proof), ("pubs.bin", pubs), ("vk.bin", vk)] {
+            write_atomic(&dir.join(name), bytes).await?;
+        }
+        Ok(())
+    }
 }
 
 // Helper function to get hex strings with 0x prefix
-fn to_hex_with_prefix(bytes: &[u8]) -> String {
+pub(crate) fn to_hex_with_prefix(bytes: &[u8]) -> String {
     let hex_string: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
     format!("0x{}", hex_string)
 }
 
+/// Read a proof system's binary file, either raw bytes or their hex encoding
+pub(crate) async fn read_raw_or_hex(path: &Path) -> Result<Vec<u8>> {
+    let bytes = tokio::fs::read(path)
+        .await
+        .map_err(|e| FailureClass::BadInput.error(format!("Failed to read {}: {}", path.display(), e)))?;
+    let text = std::str::from_utf8(&bytes).map(str::trim).unwrap_or_default();
+    let text = text.strip_prefix("0x").unwrap_or(text);
+    if !text.is_empty() && text.len().is_multiple_of(2) && text.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Ok(hex::decode(text)?);
+    }
+    Ok(bytes)
+}
+
+// Helper function to derive the VK hash from the proof structure, when the variant carries one
+fn vk_from_artifact(proof: &SP1ProofWithPublicValues) -> Option<String> {
+    match &proof.proof {
+        sp1_sdk::SP1Proof::Compressed(sp1_reduce_proof) => {
+            let vk_bytes = sp1_reduce_proof.vk.hash_bytes();
+            Some(to_hex_with_prefix(&vk_bytes))
+        }
+        _ => None,
+    }
+}
+
+// VK a proof converts with: the artifact's, which the chain verifies the proof against, or else
+// the given one. A given VK that differs from the artifact's is refused unless `force` keeps it;
+// the flag tells whether they differ.
+fn select_vk(artifact_vk: Option<String>, given_vk: &str, force: bool) -> Result<(String, bool)> {
+    let same = |vk: &str, other: &str| vk.trim_start_matches("0x").eq_ignore_ascii_case(other.trim_start_matches("0x"));
+    match artifact_vk {
+        Some(artifact_vk) if given_vk.is_empty() || same(&artifact_vk, given_vk) => Ok((artifact_vk, false)),
+        Some(_) if force => Ok((given_vk.to_string(), true)),
+        Some(artifact_vk) => Err(FailureClass::BadInput.error(format!(
+            "VK {} differs from the artifact's VK {}, so zkVerify would reject the proof; \
+             pass --force to convert with {} anyway",
+            given_vk, artifact_vk, given_vk
+        ))),
+        None if !given_vk.is_empty() => Ok((given_vk.to_string(), false)),
+        None => Ok((ZERO_VK.to_string(), false)),
+    }
+}
+
+/// Placeholder VK used when neither the explorer nor the artifact provides one
+pub const ZERO_VK: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+
+/// A boxed SP1 VK, as taken by `submit_proof` and `register_vk`
+pub(crate) fn vk_value(vk: &[u8]) -> subxt::dynamic::Value {
+    subxt::dynamic::Value::unnamed_composite(vec![bytes_value(vk)])
+}
+
+/// SP1 proofs, shrunk with `sp1_zkv_sdk` for zkVerify's SP1 pallet
+pub struct Sp1Backend;
+
+impl ProofBackend for Sp1Backend {
+    fn proof_type(&self) -> ProofType {
+        ProofType::Sp1
+    }
+
+    fn pallet(&self) -> &'static str {
+        "SettlementSp1Pallet"
+    }
+
+    fn convert<'a>(&'a self, inputs: &'a ProofInputs) -> BoxFuture<'a, Result<ConvertedProof>> {
+        Box::pin(async move { Ok(ProofConverter::new().convert_sp1(inputs).await?) })
+    }
+
+    // The VK `convert` would use
+    fn derive_vk<'a>(&'a self, inputs: &'a ProofInputs) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let proof = load_artifact(&inputs.proof).classify(FailureClass::BadInput)?;
+            let (vk, _) = select_vk(vk_from_artifact(&proof), inputs.vk.as_deref().unwrap_or_default(), inputs.force_vk)?;
+            Ok(vk)
+        })
+    }
+
+    fn verify_locally(&self, converted_proof: &ConvertedProof) -> Result<()> {
+        let (proof, pubs, _) = converted_proof.decode()?;
+        decode_vk(&converted_proof.vk).classify(FailureClass::BadInput)?;
+        if proof.is_empty() || pubs.is_empty() {
+            return Err(FailureClass::BadInput.error("SP1 proof or public values are empty"));
+        }
+        Ok(())
+    }
+
+    fn call_args(&self, converted_proof: &ConvertedProof) -> Result<CallArgs> {
+        let (proof, pubs, _) = converted_proof.decode()?;
+        Ok(CallArgs {
+            vk: vk_value(&decode_vk(&converted_proof.vk)?),
+            proof: bytes_value(&proof),
+            pubs: bytes_value(&pubs),
+        })
+    }
+
+    // A 32-byte VK, then `Vec<u8>` proof and public values
+    fn encode_args(&self, converted_proof: &ConvertedProof) -> Result<ScaleArgs> {
+        let (proof, pubs, _) = converted_proof.decode()?;
+        Ok(ScaleArgs { vk: decode_vk(&converted_proof.vk)?, proof: proof.encode(), pubs: pubs.encode() })
+    }
+}
+
 pub struct ProofConverter;
 
 impl ProofConverter {
@@ -24,43 +485,87 @@ impl ProofConverter {
         Self
     }
 
-    pub async fn convert_proof(&self, artifact_path: &Path, vk_from_page: &str) -> Result<ConvertedProof> {
-        let proof = SP1ProofWithPublicValues::load(artifact_path)?;
-        let client = ProverClient::from_env();
-
-        let vk = if !vk_from_page.is_empty() {
-            vk_from_page.to_string()
-        } else {
-            // Fallback to extracting from proof structure
-            match &proof.proof {
-                sp1_sdk::SP1Proof::Compressed(sp1_reduce_proof) => {
-                    let vk_bytes = sp1_reduce_proof.vk.hash_bytes();
-                    to_hex_with_prefix(&vk_bytes)
-                }
-                _ => {
-                    "0x0000000000000000000000000000000000000000000000000000000000000000".to_string()
-                }
-            }
+    /// Convert an SP1 artifact, refusing a `vk_from_page` that differs from the artifact's VK
+    pub async fn convert_proof(&self, artifact_path: &Path, vk_from_page: &str) -> Result<ConvertedProof, RouterError> {
+        let inputs = ProofInputs { vk: Some(vk_from_page.to_string()), ..ProofInputs::new(artifact_path) };
+        self.convert_sp1(&inputs).await
+    }
+
+    /// Convert the SP1 artifact of `inputs` with the VK it carries, checking `inputs.vk` against
+    /// it. With `force_vk`, a differing `inputs.vk` is used instead, with a `vk-mismatch` warning.
+    pub async fn convert_sp1(&self, inputs: &ProofInputs) -> Result<ConvertedProof, RouterError> {
+        let proof = load_artifact(&inputs.proof)?;
+        let mut warnings = Vec::new();
+
+        let vk_from_page = inputs.vk.as_deref().unwrap_or_default();
+        let artifact_vk = vk_from_artifact(&proof);
+        let (vk, mismatch) = select_vk(artifact_vk.clone(), vk_from_page, inputs.force_vk)?;
+        if mismatch {
+            warnings.push(Warning::emit(
+                WarningCode::VkMismatch,
+                format!(
+                    "VK from explorer ({}) differs from artifact VK ({}); converting with the explorer's as forced",
+                    vk_from_page,
+                    artifact_vk.unwrap_or_default()
+                ),
+            ));
+        }
+
+        let sp1_artifact = Sp1ArtifactInfo {
+            variant: match &proof.proof {
+                sp1_sdk::SP1Proof::Core(_) => "core",
+                sp1_sdk::SP1Proof::Compressed(_) => "compressed",
+                sp1_sdk::SP1Proof::Plonk(_) => "plonk",
+                sp1_sdk::SP1Proof::Groth16(_) => "groth16",
+            },
+            version: proof.sp1_version.clone(),
         };
 
-        // Convert proof and vk into a zkVerify-compatible proof.
+        // Convert proof and vk into a zkVerify-compatible proof. This takes minutes of CPU,
+        // so it runs off the async workers to keep progress reporting and other jobs going.
+        let offline = inputs.offline;
         let SP1ZkvProofWithPublicValues {
             proof: shrunk_proof,
             public_values,
-        } = client
-            .convert_proof_to_zkv(proof, Default::default())
-            .unwrap();
+        } = tokio::task::spawn_blocking(move || {
+            if offline {
+                // Built explicitly, the CPU prover reads no SP1_PROVER or network settings
+                ProverClient::builder().cpu().build().convert_proof_to_zkv(proof, Default::default())
+            } else {
+                ProverClient::from_env().convert_proof_to_zkv(proof, Default::default())
+            }
+            .map_err(|e| RouterError::Conversion(anyhow::anyhow!("Failed to convert the proof: {}", e)))
+        })
+        .await
+        .map_err(|e| RouterError::Conversion(e.into()))??;
 
         // Serialize the proof
-        let serialized_proof =
-            bincode::serde::encode_to_vec(&shrunk_proof, bincode::config::legacy())
-                .expect("failed to serialize proof");
+        let serialized_proof = bincode::serde::encode_to_vec(&shrunk_proof, bincode::config::legacy())
+            .map_err(|e| RouterError::Conversion(anyhow::anyhow!("Failed to serialize the proof: {}", e)))?;
+
+        if serialized_proof.len() > LARGE_PROOF_THRESHOLD {
+            warnings.push(Warning::emit(
+                WarningCode::LargeProof,
+                format!(
+                    "Serialized proof is {} bytes (threshold {} bytes)",
+                    serialized_proof.len(),
+                    LARGE_PROOF_THRESHOLD
+                ),
+            ));
+        }
 
         // Convert to required struct
         let output = ConvertedProof {
+            format_version: FORMAT_VERSION,
+            proof_type: ProofType::Sp1,
+            curve: None,
+            plonky2: None,
             proof: to_hex_with_prefix(&serialized_proof),
             pubs: to_hex_with_prefix(&public_values),
-            vk: vk, // Use VK extracted from proof structure
+            vk,
+            request_id: None,
+            warnings,
+            sp1_artifact: Some(sp1_artifact),
         };
         Ok(output)
     }
@@ -69,14 +574,26 @@ impl ProofConverter {
         &self,
         converted_proof: &ConvertedProof,
         output_path: &Path,
-    ) -> Result<()> {
+    ) -> Result<(), RouterError> {
         let json_content = serde_json::to_string_pretty(converted_proof)?;
-        tokio::fs::write(output_path, json_content).await?;
+        write_atomic(output_path, json_content).await?;
         Ok(())
     }
 
-    pub async fn save_detailed_proof_info(&self, artifact_path: &Path, output_path: &str) -> Result<()> {
-        let proof = SP1ProofWithPublicValues::load(artifact_path)?;
+    /// Load a proof saved in any format but SCALE, compressed or not
+    pub async fn load_proof(&self, input_path: &Path) -> Result<ConvertedProof, RouterError> {
+        let content = tokio::fs::read(input_path).await?;
+        ConvertedProof::from_bytes(&content)
+    }
+
+    pub async fn save_detailed_proof_info(
+        &self,
+        artifact_path: &Path,
+        request: Option<&RequestDetails>,
+        pubs_abi: Option<&PubsAbi>,
+        output_path: &Path,
+    ) -> Result<(), RouterError> {
+        let proof = load_artifact(artifact_path)?;
         
         // Create a detailed structure with all the information
         #[derive(Debug, Serialize)]
@@ -86,18 +603,27 @@ impl ProofConverter {
             vk_extracted: String,
             vk_length: usize,
             public_values_debug: String,
+            /// Public values decoded with `--pubs-abi`
+            #[serde(skip_serializing_if = "Option::is_none")]
+            public_values: Option<serde_json::Map<String, serde_json::Value>>,
             proof_structure: String,
             tee_proof: Option<String>,
+            /// What the explorer showed about the request
+            #[serde(skip_serializing_if = "Option::is_none")]
+            request: Option<RequestDetails>,
         }
 
         // Extract VK from the proof structure
-        let vk = match &proof.proof {
-            sp1_sdk::SP1Proof::Compressed(sp1_reduce_proof) => {
-                let vk_bytes = sp1_reduce_proof.vk.hash_bytes();
-                to_hex_with_prefix(&vk_bytes)
+        let vk = vk_from_artifact(&proof).unwrap_or_else(|| ZERO_VK.to_string());
+
+        // The proof is already converted, so a schema that does not fit only costs the decoded fields
+        let public_values = pubs_abi.and_then(|pubs_abi| match pubs_abi.decode(proof.public_values.as_slice()) {
+            Ok(decoded) => Some(decoded),
+            Err(e) => {
+                warn!("Public values do not match --pubs-abi: {:#}", e);
+                None
             }
-            _ => "0x0000000000000000000000000000000000000000000000000000000000000000".to_string()
-        };
+        });
 
         let detailed_info = DetailedProofInfo {
             sp1_version: proof.sp1_version.clone(),
@@ -105,12 +631,14 @@ impl ProofConverter {
             vk_extracted: vk.clone(),
             vk_length: vk.len() - 2, // Remove "0x" prefix
             public_values_debug: format!("{:?}", proof.public_values),
+            public_values,
             proof_structure: format!("{:?}", proof),
             tee_proof: proof.tee_proof.as_ref().map(|_| "Present".to_string()),
+            request: request.cloned(),
         };
 
         let json = serde_json::to_string_pretty(&detailed_info)?;
-        tokio::fs::write(output_path, json).await?;
+        write_atomic(output_path, json).await?;
         Ok(())
     }
 }
@@ -0,0 +1,208 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::fmt;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::info;
+use zeroize::Zeroizing;
+
+use crate::config::Config;
+use crate::failure::FailureClass;
+#[cfg(feature = "aws-secrets")]
+use crate::aws_secrets::AwsSecret;
+#[cfg(feature = "hashicorp-vault")]
+use crate::hashicorp::{VaultClient, VaultSecret};
+use crate::network::HttpPolicy;
+use crate::secret::SecretString;
+
+/// Environment variable the signing mnemonic is read from by default
+pub const MNEMONIC_ENV: &str = "ZKV_MNEMONIC";
+
+/// Service and account the mnemonic is stored under in the OS keyring
+#[cfg(feature = "keyring")]
+pub const KEYRING_SERVICE: &str = "rusty_router";
+#[cfg(feature = "keyring")]
+pub const KEYRING_ACCOUNT: &str = "mnemonic";
+
+/// Where the signing mnemonic comes from. Mnemonics are never stored in the config itself.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeySource {
+    /// Environment variable holding the mnemonic
+    Env(String),
+    /// File holding the mnemonic
+    File(PathBuf),
+    /// The router's own mnemonic file, `$XDG_CONFIG_HOME/rusty_router/mnemonic`
+    Keystore,
+    /// The OS keyring (macOS Keychain, Windows Credential Manager, Secret Service)
+    #[cfg(feature = "keyring")]
+    Keyring,
+    /// Asked for on the terminal, without echo
+    Prompt,
+    /// A field of a HashiCorp Vault KV secret, fetched at startup
+    #[cfg(feature = "hashicorp-vault")]
+    #[serde(rename = "hcvault")]
+    HashicorpVault(VaultSecret),
+    /// An AWS Secrets Manager secret, fetched at startup
+    #[cfg(feature = "aws-secrets")]
+    Aws(AwsSecret),
+}
+
+impl FromStr for KeySource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once(':') {
+            None if s == "env" => Ok(KeySource::Env(MNEMONIC_ENV.to_string())),
+            Some(("env", name)) if !name.is_empty() => Ok(KeySource::Env(name.to_string())),
+            Some(("file", path)) if !path.is_empty() => Ok(KeySource::File(PathBuf::from(path))),
+            None if s == "keystore" => Ok(KeySource::Keystore),
+            #[cfg(feature = "keyring")]
+            None if s == "keyring" => Ok(KeySource::Keyring),
+            #[cfg(not(feature = "keyring"))]
+            None if s == "keyring" => anyhow::bail!("The keyring key source needs a build with --features keyring"),
+            None if s == "prompt" => Ok(KeySource::Prompt),
+            #[cfg(feature = "hashicorp-vault")]
+            Some(("hcvault", secret)) => Ok(KeySource::HashicorpVault(secret.parse()?)),
+            #[cfg(not(feature = "hashicorp-vault"))]
+            Some(("hcvault", _)) => anyhow::bail!("The hcvault key source needs a build with --features hashicorp-vault"),
+            #[cfg(feature = "aws-secrets")]
+            Some(("aws", secret)) => Ok(KeySource::Aws(secret.parse()?)),
+            #[cfg(not(feature = "aws-secrets"))]
+            Some(("aws", _)) => anyhow::bail!("The aws key source needs a build with --features aws-secrets"),
+            _ => anyhow::bail!(
+                "Unknown key source '{}': expected env[:NAME], file:PATH, keystore, keyring, prompt, hcvault:MOUNT/PATH[#FIELD] or aws:SECRET_ID[#FIELD]",
+                s
+            ),
+        }
+    }
+}
+
+impl fmt::Display for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeySource::Env(name) => write!(f, "environment variable {}", name),
+            KeySource::File(path) => write!(f, "file {}", path.display()),
+            KeySource::Keystore => match keystore_path() {
+                Some(path) => write!(f, "keystore {}", path.display()),
+                None => write!(f, "keystore"),
+            },
+            #[cfg(feature = "keyring")]
+            KeySource::Keyring => write!(f, "OS keyring ({}/{})", KEYRING_SERVICE, KEYRING_ACCOUNT),
+            KeySource::Prompt => write!(f, "terminal prompt"),
+            #[cfg(feature = "hashicorp-vault")]
+            KeySource::HashicorpVault(secret) => write!(f, "Vault secret {}", secret),
+            #[cfg(feature = "aws-secrets")]
+            KeySource::Aws(secret) => write!(f, "AWS secret {}", secret),
+        }
+    }
+}
+
+impl KeySource {
+    /// The mnemonic of this source, failing when it holds none. Remote sources are reached
+    /// under `http_policy`.
+    pub async fn mnemonic(&self, http_policy: &HttpPolicy) -> Result<SecretString> {
+        self.read(http_policy)
+            .await?
+            .ok_or_else(|| FailureClass::BadInput.error(format!("No signing mnemonic in the {}", self)))
+    }
+
+    // The mnemonic of this source, `None` when it is not set up at all
+    #[cfg_attr(not(feature = "hashicorp-vault"), allow(unused_variables))]
+    async fn read(&self, http_policy: &HttpPolicy) -> Result<Option<SecretString>> {
+        match self {
+            KeySource::Env(name) => Ok(std::env::var(name)
+                .ok()
+                .filter(|mnemonic| !mnemonic.trim().is_empty())
+                .map(SecretString::new)),
+            KeySource::File(path) => read_file(path),
+            KeySource::Keystore => match keystore_path() {
+                Some(path) if path.exists() => read_file(&path),
+                _ => Ok(None),
+            },
+            #[cfg(feature = "keyring")]
+            KeySource::Keyring => {
+                let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_ACCOUNT)?;
+                match entry.get_password() {
+                    Ok(mnemonic) => Ok(Some(SecretString::new(Zeroizing::new(mnemonic).trim()))),
+                    Err(keyring::Error::NoEntry) => Ok(None),
+                    Err(e) => Err(anyhow::anyhow!("Failed to read the OS keyring: {}", e)),
+                }
+            }
+            KeySource::Prompt => {
+                if !std::io::stdin().is_terminal() {
+                    return Ok(None);
+                }
+                let mnemonic = Zeroizing::new(rpassword::prompt_password("Signing mnemonic: ")?);
+                Ok(Some(SecretString::new(mnemonic.trim())).filter(|mnemonic| !mnemonic.expose().is_empty()))
+            }
+            #[cfg(feature = "hashicorp-vault")]
+            KeySource::HashicorpVault(secret) => VaultClient::from_env(http_policy.clone())?.read(secret).await,
+            #[cfg(feature = "aws-secrets")]
+            KeySource::Aws(secret) => secret.read().await,
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/rusty_router/mnemonic`, next to the config file
+pub fn keystore_path() -> Option<PathBuf> {
+    Some(Config::default_path()?.with_file_name("mnemonic"))
+}
+
+/// Sources tried in order when no source is given: the file of `--mnemonic-file`, then
+/// `ZKV_MNEMONIC`, the keystore, the OS keyring and finally a prompt on the terminal
+pub fn default_chain(mnemonic_file: Option<&Path>) -> Vec<KeySource> {
+    let mut chain: Vec<KeySource> = mnemonic_file.map(|path| KeySource::File(path.to_path_buf())).into_iter().collect();
+    chain.push(KeySource::Env(MNEMONIC_ENV.to_string()));
+    chain.push(KeySource::Keystore);
+    #[cfg(feature = "keyring")]
+    chain.push(KeySource::Keyring);
+    chain.push(KeySource::Prompt);
+    chain
+}
+
+/// The signing mnemonic from `source` when one is chosen, or else from the first source of the
+/// default chain that holds one
+pub async fn resolve_mnemonic(
+    source: Option<&KeySource>,
+    mnemonic_file: Option<&Path>,
+    http_policy: &HttpPolicy,
+) -> Result<SecretString> {
+    if let Some(source) = source {
+        return source.mnemonic(http_policy).await;
+    }
+    let chain = default_chain(mnemonic_file);
+    for source in &chain {
+        if let Some(mnemonic) = source.read(http_policy).await? {
+            info!("Signing mnemonic read from the {}", source);
+            return Ok(mnemonic);
+        }
+    }
+    let tried: Vec<String> = chain.iter().map(KeySource::to_string).collect();
+    Err(FailureClass::BadInput.error(format!(
+        "No signing mnemonic found; tried the {}. Set {} in your .env file or pass --key-source",
+        tried.join(", the "),
+        MNEMONIC_ENV
+    )))
+}
+
+// A mnemonic file's trimmed content, warning when other users can read it
+fn read_file(path: &Path) -> Result<Option<SecretString>> {
+    let content = Zeroizing::new(
+        std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read mnemonic file {}: {}", path.display(), e))?,
+    );
+    #[cfg(unix)]
+    {
+        use crate::warnings::{Warning, WarningCode};
+        use std::os::unix::fs::PermissionsExt;
+        if std::fs::metadata(path)?.permissions().mode() & 0o077 != 0 {
+            Warning::emit(
+                WarningCode::ExposedKeyFile,
+                format!("Mnemonic file {} can be read by other users; restrict it with chmod 600", path.display()),
+            );
+        }
+    }
+    Ok(Some(SecretString::new(content.trim())).filter(|mnemonic| !mnemonic.expose().is_empty()))
+}
@@ -0,0 +1,77 @@
+use anyhow::Result;
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::{debug, info};
+
+use crate::aggregation::AggregationReceipt;
+use crate::calldata::encode_verify_proof_aggregation;
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse {
+    result: Option<String>,
+    error: Option<JsonRpcError>,
+}
+
+/// Minimal Ethereum JSON-RPC client for checking attestations
+pub struct EthClient {
+    client: Client,
+    rpc_url: String,
+}
+
+impl EthClient {
+    pub fn new(rpc_url: &str) -> Self {
+        Self {
+            client: Client::new(),
+            rpc_url: rpc_url.to_string(),
+        }
+    }
+
+    /// Ask the zkVerify contract whether the receipt's leaf is part of the attested aggregation.
+    pub async fn verify_proof_aggregation(&self, contract: &str, receipt: &AggregationReceipt) -> Result<bool> {
+        info!(
+            "Checking aggregation {} of domain {} on contract {}",
+            receipt.aggregation_id, receipt.domain_id, contract
+        );
+        let output = self.call(contract, &encode_verify_proof_aggregation(receipt)).await?;
+
+        // A `bool` return value is one ABI word
+        if output.len() != 32 {
+            anyhow::bail!("Unexpected return data length {} from {}", output.len(), contract);
+        }
+        Ok(output[31] == 1)
+    }
+
+    async fn call(&self, to: &str, data: &[u8]) -> Result<Vec<u8>> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_call",
+            "params": [{ "to": to, "data": format!("0x{}", hex::encode(data)) }, "latest"],
+        });
+        debug!("eth_call to {}", to);
+
+        let response: JsonRpcResponse = self
+            .client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = response.error {
+            anyhow::bail!("eth_call failed ({}): {}", error.code, error.message);
+        }
+        let result = response
+            .result
+            .ok_or_else(|| anyhow::anyhow!("eth_call returned no result"))?;
+        Ok(hex::decode(result.trim_start_matches("0x"))?)
+    }
+}
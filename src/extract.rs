@@ -0,0 +1,343 @@
+use anyhow::Result;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use tracing::debug;
+
+/// One way of finding a request's artifact URL and VK on the explorer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractionStrategy {
+    /// The JSON the explorer API returns, when an API URL is configured
+    JsonApi,
+    /// The `__NEXT_DATA__` JSON a Next.js page embeds
+    NextData,
+    /// JSON and escaped JSON in the page's other script tags
+    ScriptTags,
+    /// The elements of the rendered page showing the artifact URL and program
+    Dom,
+}
+
+/// Every strategy, most structured first
+pub const DEFAULT_STRATEGIES: [ExtractionStrategy; 4] = [
+    ExtractionStrategy::JsonApi,
+    ExtractionStrategy::NextData,
+    ExtractionStrategy::ScriptTags,
+    ExtractionStrategy::Dom,
+];
+
+impl FromStr for ExtractionStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "json-api" => Ok(ExtractionStrategy::JsonApi),
+            "next-data" => Ok(ExtractionStrategy::NextData),
+            "script-tags" => Ok(ExtractionStrategy::ScriptTags),
+            "dom" => Ok(ExtractionStrategy::Dom),
+            _ => anyhow::bail!("Unknown extraction strategy '{}': expected json-api, next-data, script-tags or dom", s),
+        }
+    }
+}
+
+impl std::fmt::Display for ExtractionStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ExtractionStrategy::JsonApi => "json-api",
+            ExtractionStrategy::NextData => "next-data",
+            ExtractionStrategy::ScriptTags => "script-tags",
+            ExtractionStrategy::Dom => "dom",
+        })
+    }
+}
+
+/// What a strategy found; any may be missing
+#[derive(Debug, Clone, Default)]
+pub struct Extracted {
+    pub artifact_url: Option<String>,
+    pub vk: Option<String>,
+    /// Other labelled values shown with them, e.g. `SP1 Version` or `Prover`
+    pub fields: BTreeMap<String, String>,
+}
+
+impl Extracted {
+    pub fn is_empty(&self) -> bool {
+        self.artifact_url.is_none() && self.vk.is_none()
+    }
+}
+
+const ARTIFACT_URL_PREFIX: &str = "https://spn-artifacts-mainnet.s3";
+const ARTIFACT_URL_KEYS: [&str; 6] = ["artifact_url", "artifactUrl", "proof_uri", "proofUri", "proof_url", "proofUrl"];
+const VK_KEYS: [&str; 6] = ["vk", "vk_hash", "vkHash", "program_vk", "programVk", "vkey"];
+
+/// Artifact URL and VK anywhere in a JSON document, by key or by the shape of the value
+pub fn extract_from_json(json: &Value) -> Extracted {
+    let mut extracted = Extracted::default();
+    walk(json, None, &mut extracted);
+    extracted
+}
+
+fn walk(value: &Value, key: Option<&str>, extracted: &mut Extracted) {
+    match value {
+        Value::String(s) => {
+            if extracted.artifact_url.is_none()
+                && (s.starts_with(ARTIFACT_URL_PREFIX)
+                    || key.is_some_and(|key| ARTIFACT_URL_KEYS.contains(&key)) && s.starts_with("https://"))
+            {
+                extracted.artifact_url = Some(s.clone());
+            }
+            if extracted.vk.is_none() && key.is_some_and(|key| VK_KEYS.contains(&key)) && is_hex32(s) {
+                extracted.vk = Some(s.clone());
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| walk(value, key, extracted)),
+        Value::Object(fields) => {
+            let had_artifact_url = extracted.artifact_url.is_some();
+            fields.iter().for_each(|(key, value)| walk(value, Some(key), extracted));
+            // The innermost object holding the artifact URL describes the request
+            if !had_artifact_url && extracted.artifact_url.is_some() && extracted.fields.is_empty() {
+                extracted.fields = fields
+                    .iter()
+                    .filter_map(|(key, value)| match value {
+                        Value::String(s) => Some((key.clone(), s.clone())),
+                        Value::Number(_) | Value::Bool(_) => Some((key.clone(), value.to_string())),
+                        _ => None,
+                    })
+                    .collect();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_hex32(s: &str) -> bool {
+    s.len() == 66 && s.starts_with("0x") && s[2..].bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Look in the `<script id="__NEXT_DATA__">` JSON of a Next.js page
+pub fn extract_from_next_data(html: &str) -> Extracted {
+    let document = Html::parse_document(html);
+    let Some(script) = document.select(&selector("script#__NEXT_DATA__")).next() else {
+        debug!("Page has no __NEXT_DATA__");
+        return Extracted::default();
+    };
+    match serde_json::from_str(&script.text().collect::<String>()) {
+        Ok(json) => extract_from_json(&json),
+        Err(e) => {
+            debug!("Unreadable __NEXT_DATA__: {}", e);
+            Extracted::default()
+        }
+    }
+}
+
+/// Look in the other inline scripts: JSON ones, and the escaped JSON that streamed pages push
+/// in `self.__next_f.push(...)` calls
+pub fn extract_from_script_tags(html: &str) -> Extracted {
+    let document = Html::parse_document(html);
+    let artifact_re = regex::Regex::new(r#"(https://spn-artifacts-mainnet\.s3[^"\\<>\s]*)"#).expect("valid regex");
+    let vk_re = regex::Regex::new(r#""(?:vk|vk_hash|vkHash|program_vk|programVk|vkey)":"(0x[0-9a-fA-F]{64})""#).expect("valid regex");
+
+    let mut extracted = Extracted::default();
+    for script in document.select(&selector("script:not([src])")) {
+        let body = script.text().collect::<String>();
+        let body = body.trim();
+        if let Ok(json) = serde_json::from_str::<Value>(body) {
+            let found = extract_from_json(&json);
+            extracted.artifact_url = extracted.artifact_url.or(found.artifact_url);
+            extracted.vk = extracted.vk.or(found.vk);
+            if extracted.fields.is_empty() {
+                extracted.fields = found.fields;
+            }
+        } else {
+            let body = body.replace("\\\"", "\"").replace("\\u0026", "&");
+            if extracted.artifact_url.is_none() {
+                extracted.artifact_url = artifact_re.captures(&body).map(|captures| captures[1].to_string());
+            }
+            if extracted.vk.is_none() {
+                extracted.vk = vk_re.captures(&body).map(|captures| captures[1].to_string());
+            }
+        }
+        if extracted.artifact_url.is_some() && extracted.vk.is_some() {
+            break;
+        }
+    }
+    extracted
+}
+
+/// Read the rendered page: the artifact URL from the link or text showing it, the VK from the
+/// details row labelled "Program", e.g. `Galxe (0x6810...)`, and the other details rows
+/// and summary cards as fields
+pub fn extract_from_dom(html: &str) -> Extracted {
+    let document = Html::parse_document(html);
+
+    // Text and attribute values come out of the parser with their entities decoded
+    let artifact_url = document
+        .select(&selector("a[href]"))
+        .filter_map(|link| link.value().attr("href"))
+        .chain(document.root_element().text().map(str::trim))
+        .find(|url| url.starts_with(ARTIFACT_URL_PREFIX))
+        .map(str::to_string);
+
+    let cell = selector("td, th");
+    let mut fields: BTreeMap<String, String> = document
+        .select(&selector("tr"))
+        .filter_map(|row| {
+            let cells: Vec<String> = row.select(&cell).map(|cell| text_of(&cell)).collect();
+            match cells.as_slice() {
+                [label, value] if !label.is_empty() => Some((label.clone(), value.clone())),
+                _ => None,
+            }
+        })
+        .collect();
+    // The summary cards above the table, a label paragraph followed by the value, e.g. `Mode`
+    for card in document.select(&selector("div.flex-col")) {
+        let children: Vec<ElementRef> = card.children().filter_map(ElementRef::wrap).collect();
+        if let [label, value] = children.as_slice()
+            && label.select(&selector("p")).count() == 1
+        {
+            let label = text_of(label);
+            if !label.is_empty() && !label.contains(|c: char| c.is_ascii_digit()) {
+                fields.entry(label).or_insert_with(|| text_of(value));
+            }
+        }
+    }
+    let vk = fields.get("Program").and_then(|program| find_vk(program)).map(str::to_string);
+
+    Extracted { artifact_url, vk, fields }
+}
+
+// Text of an element with runs of whitespace collapsed
+fn text_of(element: &ElementRef) -> String {
+    element.text().flat_map(str::split_whitespace).collect::<Vec<_>>().join(" ")
+}
+
+fn selector(selector: &str) -> Selector {
+    Selector::parse(selector).expect("valid selector")
+}
+
+// The first standalone 32-byte hex value in a text
+fn find_vk(text: &str) -> Option<&str> {
+    text.match_indices("0x").find_map(|(start, _)| {
+        let candidate = text.get(start..start + 66)?;
+        let standalone = !text[start + 66..].starts_with(|c: char| c.is_ascii_hexdigit());
+        (is_hex32(candidate) && standalone).then_some(candidate)
+    })
+}
+
+/// Details of a request picked out of its extracted fields, where the explorer showed them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RequestDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycles: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requester: Option<String>,
+    /// As shown, e.g. `Aug 29, 2025, 10:18:43 AM`, or as the JSON API gives it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fulfilled_at: Option<String>,
+    /// e.g. `Groth16` or `Compressed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_mode: Option<String>,
+    /// Fulfillment status, one of `Requested`, `Assigned`, `Fulfilled` or `Unfulfillable`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+// Field names as `normalize` leaves them, so that `Cycles Used`, `cycles_used` and `cyclesUsed` match
+const CYCLES_KEYS: [&str; 3] = ["cyclesused", "cycles", "cyclecount"];
+const GAS_KEYS: [&str; 2] = ["gasused", "gas"];
+const REQUESTER_KEYS: [&str; 2] = ["requester", "requesteraddress"];
+const FULFILLED_AT_KEYS: [&str; 5] = ["fulfilledat", "fulfilled", "fulfilledtime", "fulfillmenttime", "fulfilltime"];
+const PROOF_MODE_KEYS: [&str; 2] = ["proofmode", "mode"];
+const STATUS_KEYS: [&str; 3] = ["status", "fulfillmentstatus", "requeststatus"];
+
+impl RequestDetails {
+    /// Read the details from the labels of the page or the keys of the JSON
+    pub fn from_fields(fields: &BTreeMap<String, String>) -> Self {
+        let find = |keys: &[&str]| {
+            keys.iter().find_map(|key| {
+                fields
+                    .iter()
+                    .find(|(name, value)| normalize(name) == *key && !value.is_empty())
+                    .map(|(_, value)| value.clone())
+            })
+        };
+        Self {
+            cycles: find(&CYCLES_KEYS).and_then(|value| parse_count(&value)),
+            gas_used: find(&GAS_KEYS).and_then(|value| parse_count(&value)),
+            requester: find(&REQUESTER_KEYS),
+            fulfilled_at: find(&FULFILLED_AT_KEYS),
+            proof_mode: find(&PROOF_MODE_KEYS),
+            // As shown (`Unfulfillable`) or as an API enum (`FULFILLMENT_STATUS_UNFULFILLABLE`)
+            status: find(&STATUS_KEYS).and_then(|value| {
+                let value = normalize(&value);
+                REQUEST_STATUSES
+                    .iter()
+                    .find(|status| value.ends_with(&status.to_ascii_lowercase()))
+                    .map(|status| status.to_string())
+            }),
+        }
+    }
+
+    /// Whether the prover network gave up on the request, so it will never have a proof
+    pub fn is_unfulfillable(&self) -> bool {
+        self.status.as_deref() == Some("Unfulfillable")
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars().filter(char::is_ascii_alphanumeric).map(|c| c.to_ascii_lowercase()).collect()
+}
+
+// The number a value starts with, e.g. `44,854 Cycles`
+fn parse_count(value: &str) -> Option<u64> {
+    value.split_whitespace().next()?.replace([',', '_'], "").parse().ok()
+}
+
+/// A proof request as an explorer listing shows it
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestSummary {
+    pub request_id: String,
+    /// Fulfillment status shown, e.g. `Fulfilled`
+    pub status: Option<String>,
+    /// Creation time as shown, e.g. `Aug 29, 2025, 10:18:43 AM` or `5 minutes ago`
+    pub created: Option<String>,
+}
+
+const REQUEST_STATUSES: [&str; 4] = ["Requested", "Assigned", "Fulfilled", "Unfulfillable"];
+
+/// The requests a listing page links to, each once in page order, with the status and creation
+/// time found in the table row of the link
+pub fn extract_request_list(html: &str) -> Vec<RequestSummary> {
+    let document = Html::parse_document(html);
+    let time_re = regex::Regex::new(r"\d{1,2}:\d{2}|\b20\d{2}\b| ago$").expect("valid regex");
+    let cell = selector("td, th");
+
+    let mut requests: Vec<RequestSummary> = Vec::new();
+    for link in document.select(&selector("a[href*='/request/']")) {
+        let href = link.value().attr("href").unwrap_or_default();
+        let request_id = href[href.find("/request/").unwrap_or_default() + "/request/".len()..]
+            .split(['/', '?', '#'])
+            .next()
+            .unwrap_or_default();
+        if !is_hex32(request_id) || requests.iter().any(|request| request.request_id == request_id) {
+            continue;
+        }
+        let cells: Vec<String> = link
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .find(|element| element.value().name() == "tr")
+            .map(|row| row.select(&cell).map(|cell| cell.text().collect::<String>().trim().to_string()).collect())
+            .unwrap_or_default();
+        requests.push(RequestSummary {
+            request_id: request_id.to_string(),
+            status: cells.iter().find_map(|text| {
+                REQUEST_STATUSES.iter().find(|status| text.eq_ignore_ascii_case(status)).map(|status| status.to_string())
+            }),
+            created: cells.iter().find(|text| !is_hex32(text) && time_re.is_match(text)).cloned(),
+        });
+    }
+    requests
+}
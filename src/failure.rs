@@ -0,0 +1,222 @@
+use std::fmt;
+
+/// Class of a failed run, reported as the process exit code so scripts can branch on it.
+/// The codes are stable; new classes only ever get new codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureClass {
+    /// Anything not classified below
+    Other,
+    /// Invalid arguments, config, requests file or proof file; also used by clap for usage errors
+    BadInput,
+    /// The explorer, artifact storage, relayer or node could not be reached or answered with an error
+    Network,
+    /// The SP1 artifact could not be converted
+    Conversion,
+    /// The chain or relayer refused, dropped or failed the transaction
+    ChainRejected,
+    /// The signing account cannot pay for the transaction
+    InsufficientFunds,
+    /// Some requests of a batch failed
+    PartialFailure,
+    /// A multisig submission was approved but needs more signatories' approvals to execute
+    AwaitingApproval,
+}
+
+// Messages the node uses when the account cannot pay; they arrive as RPC errors or pool statuses
+const INSUFFICIENT_FUNDS_MESSAGES: [&str; 3] = ["Inability to pay", "InsufficientBalance", "FundsUnavailable"];
+
+impl FailureClass {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            FailureClass::Other => 1,
+            FailureClass::BadInput => 2,
+            FailureClass::Network => 3,
+            FailureClass::Conversion => 4,
+            FailureClass::ChainRejected => 5,
+            FailureClass::InsufficientFunds => 6,
+            FailureClass::PartialFailure => 7,
+            FailureClass::AwaitingApproval => 8,
+        }
+    }
+
+    /// Kebab-case name, as the manifest of `convert-dir` records it
+    pub fn name(&self) -> &'static str {
+        match self {
+            FailureClass::Other => "other",
+            FailureClass::BadInput => "bad-input",
+            FailureClass::Network => "network",
+            FailureClass::Conversion => "conversion",
+            FailureClass::ChainRejected => "chain-rejected",
+            FailureClass::InsufficientFunds => "insufficient-funds",
+            FailureClass::PartialFailure => "partial-failure",
+            FailureClass::AwaitingApproval => "awaiting-approval",
+        }
+    }
+
+    /// An error with `message`, tagged with this class
+    pub fn error(self, message: impl fmt::Display + fmt::Debug + Send + Sync + 'static) -> anyhow::Error {
+        Classified { class: self, error: anyhow::Error::msg(message) }.into()
+    }
+
+    /// Class an error was tagged with by [`ResultExt::classify`], or else one guessed from its causes
+    pub fn of(error: &anyhow::Error) -> Self {
+        if error.chain().any(|cause| {
+            let message = cause.to_string();
+            INSUFFICIENT_FUNDS_MESSAGES.iter().any(|pattern| message.contains(pattern))
+        }) {
+            return FailureClass::InsufficientFunds;
+        }
+        if let Some(classified) = error.downcast_ref::<Classified>() {
+            return classified.class;
+        }
+        for cause in error.chain() {
+            if let Some(error) = cause.downcast_ref::<RouterError>() {
+                return error.class();
+            }
+            if cause.is::<reqwest::Error>() {
+                return FailureClass::Network;
+            }
+            if let Some(error) = cause.downcast_ref::<subxt::Error>() {
+                return match error {
+                    subxt::Error::Rpc(_) if !error.to_string().contains("Invalid Transaction") => FailureClass::Network,
+                    subxt::Error::Rpc(_) | subxt::Error::Runtime(_) | subxt::Error::Transaction(_) => FailureClass::ChainRejected,
+                    _ => FailureClass::Other,
+                };
+            }
+        }
+        FailureClass::Other
+    }
+}
+
+/// Error of the library API of [`crate::client`], [`crate::converter`] and [`crate::substrate`],
+/// by what failed. Each variant holds the underlying error, which it displays as.
+#[derive(Debug, thiserror::Error)]
+pub enum RouterError {
+    /// The explorer or artifact storage could not be reached, or the download failed
+    #[error(transparent)]
+    Fetch(anyhow::Error),
+    /// The explorer page was fetched, but no artifact URL could be extracted from it
+    #[error(transparent)]
+    Extraction(anyhow::Error),
+    /// The SP1 artifact could not be converted
+    #[error(transparent)]
+    Conversion(anyhow::Error),
+    /// The zkVerify node could not be reached or its connection failed
+    #[error(transparent)]
+    ChainConnection(anyhow::Error),
+    /// The chain refused, dropped or failed the transaction, or the account cannot pay for it
+    #[error(transparent)]
+    Submission(anyhow::Error),
+    /// Invalid arguments, proof file or VK
+    #[error(transparent)]
+    InvalidInput(anyhow::Error),
+    /// Anything not classified above
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl RouterError {
+    /// The underlying error
+    pub fn inner(&self) -> &anyhow::Error {
+        match self {
+            RouterError::Fetch(error)
+            | RouterError::Extraction(error)
+            | RouterError::Conversion(error)
+            | RouterError::ChainConnection(error)
+            | RouterError::Submission(error)
+            | RouterError::InvalidInput(error)
+            | RouterError::Other(error) => error,
+        }
+    }
+
+    /// Class of the underlying error, which sets the exit code; finer than the variant, e.g.
+    /// telling an account that cannot pay from a rejected transaction
+    pub fn class(&self) -> FailureClass {
+        FailureClass::of(self.inner())
+    }
+}
+
+impl From<anyhow::Error> for RouterError {
+    // The variant of an error's class; network failures are of the chain when subxt saw them
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<RouterError>() {
+            Ok(error) => return error,
+            Err(error) => error,
+        };
+        match FailureClass::of(&error) {
+            FailureClass::BadInput => RouterError::InvalidInput(error),
+            FailureClass::Network if error.chain().any(|cause| cause.is::<subxt::Error>()) => {
+                RouterError::ChainConnection(error)
+            }
+            FailureClass::Network => RouterError::Fetch(error),
+            FailureClass::Conversion => RouterError::Conversion(error),
+            FailureClass::ChainRejected | FailureClass::InsufficientFunds | FailureClass::AwaitingApproval => {
+                RouterError::Submission(error)
+            }
+            FailureClass::Other | FailureClass::PartialFailure => RouterError::Other(error),
+        }
+    }
+}
+
+// Errors the library API propagates with `?`, classified as anyhow errors are
+macro_rules! router_error_from {
+    ($($error:ty),* $(,)?) => {
+        $(impl From<$error> for RouterError {
+            fn from(error: $error) -> Self {
+                anyhow::Error::from(error).into()
+            }
+        })*
+    };
+}
+
+router_error_from!(
+    std::io::Error,
+    reqwest::Error,
+    serde_json::Error,
+    hex::FromHexError,
+    subxt::Error,
+    subxt::ext::codec::Error,
+    tokio::task::JoinError,
+    ciborium::ser::Error<std::io::Error>,
+    ciborium::de::Error<std::io::Error>,
+    rmp_serde::encode::Error,
+    rmp_serde::decode::Error,
+);
+
+/// An error tagged with its class; displays as the error itself
+#[derive(Debug)]
+struct Classified {
+    class: FailureClass,
+    error: anyhow::Error,
+}
+
+impl fmt::Display for Classified {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl std::error::Error for Classified {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+pub trait ResultExt<T> {
+    /// Tag the error with `class` for the exit code; a class tagged closer to the cause wins
+    fn classify(self, class: FailureClass) -> anyhow::Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> ResultExt<T> for Result<T, E> {
+    fn classify(self, class: FailureClass) -> anyhow::Result<T> {
+        self.map_err(|error| {
+            let error = error.into();
+            let tagged = |error: &anyhow::Error| error.downcast_ref::<Classified>().is_some();
+            if tagged(&error) || error.downcast_ref::<RouterError>().is_some_and(|error| tagged(error.inner())) {
+                error
+            } else {
+                Classified { class, error }.into()
+            }
+        })
+    }
+}
@@ -0,0 +1,213 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::path::Path;
+use subxt::ext::futures::future::BoxFuture;
+
+use crate::backend::{bytes_value, check_size, CallArgs, ProofBackend, ProofInputs, ScaleArgs};
+use crate::converter::{ConvertedProof, ProofType, read_raw_or_hex, to_hex_with_prefix};
+use crate::snarkjs::{self, Endian, bad_input, field, read_json};
+
+/// Size of a BN254 field element, written big-endian as for the Solidity verifier
+pub const WORD_SIZE: usize = 32;
+/// Size of an fflonk proof: 4 G1 points and 16 evaluations
+pub const PROOF_SIZE: usize = 24 * WORD_SIZE;
+/// Size of a converted VK: `power`, the 7 scalars of [`VK_SCALARS`], `X_2` (G2) and `C0` (G1)
+pub const VK_SIZE: usize = 1 + VK_SCALARS.len() * WORD_SIZE + 4 * WORD_SIZE + 2 * WORD_SIZE;
+
+/// Scalars of the VK, in the order they are stored
+pub const VK_SCALARS: [&str; 7] = ["k1", "k2", "w", "w3", "w4", "w8", "wr"];
+
+// Proof components in the order of the Solidity verifier's calldata
+const POLYNOMIALS: [&str; 4] = ["C1", "C2", "W1", "W2"];
+const EVALUATIONS: [&str; 16] =
+    ["ql", "qr", "qm", "qo", "qc", "s1", "s2", "s3", "a", "b", "c", "z", "zw", "t1w", "t2w", "inv"];
+
+// The proof as 768 bytes, from a snarkjs `proof.json` or the raw (or hex) calldata bytes
+async fn read_proof(path: &Path) -> Result<Vec<u8>> {
+    let bytes = match read_json(path).await {
+        Ok(proof) if proof.is_object() => {
+            snarkjs::check_protocol(&proof, path, "fflonk")?;
+            let polynomials = field(&proof, "polynomials", path)?;
+            let evaluations = field(&proof, "evaluations", path)?;
+            let mut bytes = Vec::with_capacity(PROOF_SIZE);
+            for name in POLYNOMIALS {
+                bytes.extend(snarkjs::g1(field(polynomials, name, path)?, name, WORD_SIZE, Endian::Big)?);
+            }
+            for name in EVALUATIONS {
+                bytes.extend(snarkjs::field_element(field(evaluations, name, path)?, name, WORD_SIZE, Endian::Big)?);
+            }
+            bytes
+        }
+        _ => read_raw_or_hex(path).await?,
+    };
+    if bytes.len() != PROOF_SIZE {
+        return Err(bad_input(format!("{} holds a {}-byte proof, expected {}", path.display(), bytes.len(), PROOF_SIZE)));
+    }
+    Ok(bytes)
+}
+
+/// Convert an fflonk proof, either the `proof.json` of `snarkjs fflonk prove` or its raw
+/// 768-byte calldata, with its single public input (`public.json`) and the circuit's
+/// `verification_key.json`, for zkVerify's fflonk pallet. Field elements are big-endian.
+pub async fn convert(proof_path: &Path, public_path: &Path, vk_path: &Path) -> Result<ConvertedProof> {
+    let proof = read_proof(proof_path).await?;
+    let public = read_json(public_path).await?;
+    let vk = read_vk(vk_path).await?;
+
+    let inputs = snarkjs::public_inputs(&public, public_path)?;
+    if inputs.len() != 1 {
+        return Err(bad_input(format!(
+            "zkVerify verifies fflonk proofs with exactly one public input, but {} has {}",
+            public_path.display(),
+            inputs.len()
+        )));
+    }
+    let pubs = snarkjs::field_element(&inputs[0], "public input", WORD_SIZE, Endian::Big)?;
+
+    let vk_bytes = encode_vk(&vk, vk_path)?;
+    status!("✅ fflonk proof for a circuit of 2^{} constraints", vk_bytes[0]);
+
+    Ok(ConvertedProof::new(ProofType::Fflonk, &proof, &pubs, &vk_bytes))
+}
+
+// A `verification_key.json`, which must be for BN254
+async fn read_vk(vk_path: &Path) -> Result<Value> {
+    let vk = read_json(vk_path).await?;
+    snarkjs::check_protocol(&vk, vk_path, "fflonk")?;
+    if let Some(curve) = vk.get("curve").and_then(Value::as_str)
+        && curve != "bn128"
+    {
+        return Err(bad_input(format!("{} is a {} VK, but fflonk is verified on BN254", vk_path.display(), curve)));
+    }
+    Ok(vk)
+}
+
+fn encode_vk(vk: &Value, vk_path: &Path) -> Result<Vec<u8>> {
+    let power = field(vk, "power", vk_path)?
+        .as_u64()
+        .and_then(|power| u8::try_from(power).ok())
+        .ok_or_else(|| bad_input(format!("'power' in {} is not a small integer", vk_path.display())))?;
+    let mut bytes = vec![power];
+    for name in VK_SCALARS {
+        bytes.extend(snarkjs::field_element(field(vk, name, vk_path)?, name, WORD_SIZE, Endian::Big)?);
+    }
+    bytes.extend(snarkjs::g2(field(vk, "X_2", vk_path)?, "X_2", WORD_SIZE, Endian::Big)?);
+    bytes.extend(snarkjs::g1(field(vk, "C0", vk_path)?, "C0", WORD_SIZE, Endian::Big)?);
+    Ok(bytes)
+}
+
+/// Fields of a converted fflonk VK
+pub struct VkFields<'a> {
+    pub power: u8,
+    /// Big-endian, in the order of [`VK_SCALARS`]
+    pub scalars: Vec<&'a [u8]>,
+    /// x.c0, x.c1, y.c0, y.c1
+    pub x2: Vec<&'a [u8]>,
+    /// x, y
+    pub c0: Vec<&'a [u8]>,
+}
+
+impl<'a> VkFields<'a> {
+    pub fn split(vk: &'a [u8]) -> Result<Self> {
+        if vk.len() != VK_SIZE {
+            return Err(bad_input(format!("fflonk VK is {} bytes, expected {}", vk.len(), VK_SIZE)));
+        }
+        let mut words = vk[1..].chunks(WORD_SIZE);
+        Ok(Self {
+            power: vk[0],
+            scalars: words.by_ref().take(VK_SCALARS.len()).collect(),
+            x2: words.by_ref().take(4).collect(),
+            c0: words.collect(),
+        })
+    }
+}
+
+// A big-endian 256-bit word as a `U256`: four u64 limbs, least significant first
+fn u256_value(word: &[u8]) -> subxt::dynamic::Value {
+    subxt::dynamic::Value::unnamed_composite(
+        word.rchunks(8)
+            .map(|limb| subxt::dynamic::Value::u128(u64::from_be_bytes(limb.try_into().expect("8-byte limb")) as u128))
+            .collect::<Vec<_>>(),
+    )
+}
+
+// The pallet's `Vk`, whose points are projective (z = 1)
+fn vk_value(fields: &VkFields) -> subxt::dynamic::Value {
+    let one = [&[0u8; 31][..], &[1]].concat();
+    let zero = [0u8; 32];
+    let fq2 = |c0: &[u8], c1: &[u8]| subxt::dynamic::Value::unnamed_composite(vec![u256_value(c0), u256_value(c1)]);
+
+    let mut composite = vec![("power".to_string(), subxt::dynamic::Value::u128(fields.power as u128))];
+    for (name, scalar) in VK_SCALARS.iter().zip(&fields.scalars) {
+        composite.push((name.to_string(), u256_value(scalar)));
+    }
+    composite.push((
+        "x2".to_string(),
+        subxt::dynamic::Value::unnamed_composite(vec![
+            fq2(fields.x2[0], fields.x2[1]),
+            fq2(fields.x2[2], fields.x2[3]),
+            fq2(&one, &zero),
+        ]),
+    ));
+    composite.push((
+        "c0".to_string(),
+        subxt::dynamic::Value::unnamed_composite(vec![u256_value(fields.c0[0]), u256_value(fields.c0[1]), u256_value(&one)]),
+    ));
+    subxt::dynamic::Value::named_composite(composite)
+}
+
+/// fflonk proofs from snarkjs, for zkVerify's fflonk pallet
+pub struct FflonkBackend;
+
+impl ProofBackend for FflonkBackend {
+    fn proof_type(&self) -> ProofType {
+        ProofType::Fflonk
+    }
+
+    fn pallet(&self) -> &'static str {
+        "SettlementFFlonkPallet"
+    }
+
+    fn convert<'a>(&'a self, inputs: &'a ProofInputs) -> BoxFuture<'a, Result<ConvertedProof>> {
+        Box::pin(async move {
+            convert(&inputs.proof, inputs.public_inputs(ProofType::Fflonk)?, inputs.vk_file(ProofType::Fflonk)?).await
+        })
+    }
+
+    fn derive_vk<'a>(&'a self, inputs: &'a ProofInputs) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let vk_path = inputs.vk_file(ProofType::Fflonk)?;
+            Ok(to_hex_with_prefix(&encode_vk(&read_vk(vk_path).await?, vk_path)?))
+        })
+    }
+
+    fn verify_locally(&self, converted_proof: &ConvertedProof) -> Result<()> {
+        let (proof, pubs, vk) = converted_proof.decode()?;
+        check_size(ProofType::Fflonk, "proof", &proof, PROOF_SIZE)?;
+        check_size(ProofType::Fflonk, "pubs", &pubs, WORD_SIZE)?;
+        check_size(ProofType::Fflonk, "vk", &vk, VK_SIZE)
+    }
+
+    // `[u8; 768]` and `[u8; 32]`
+    fn call_args(&self, converted_proof: &ConvertedProof) -> Result<CallArgs> {
+        let (proof, pubs, vk) = converted_proof.decode()?;
+        Ok(CallArgs { vk: vk_value(&VkFields::split(&vk)?), proof: bytes_value(&proof), pubs: bytes_value(&pubs) })
+    }
+
+    // `U256` words are little-endian, and the points projective as in `vk_value`
+    fn encode_args(&self, converted_proof: &ConvertedProof) -> Result<ScaleArgs> {
+        let (proof, pubs, vk) = converted_proof.decode()?;
+        let fields = VkFields::split(&vk)?;
+        let one = [&[0u8; 31][..], &[1]].concat();
+        let zero = [0u8; 32];
+        let mut words = fields.scalars.clone();
+        words.extend([fields.x2[0], fields.x2[1], fields.x2[2], fields.x2[3], &one, &zero]);
+        words.extend([fields.c0[0], fields.c0[1], &one]);
+
+        let mut vk = vec![fields.power];
+        for word in words {
+            vk.extend(word.iter().rev());
+        }
+        Ok(ScaleArgs { vk, proof, pubs })
+    }
+}
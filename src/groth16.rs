@@ -0,0 +1,303 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use subxt::ext::codec::Encode;
+use subxt::ext::futures::future::BoxFuture;
+
+use crate::backend::{bytes_value, check_scalars, scalars_value, CallArgs, ProofBackend, ProofInputs, ScaleArgs};
+use crate::converter::{ConvertedProof, ProofType, to_hex_with_prefix};
+use crate::snarkjs::{self, Endian, bad_input, field, read_json};
+
+/// Size of a public input (a scalar field element), little-endian, on both curves
+pub const SCALAR_SIZE: usize = 32;
+
+/// Pairing curve of a Groth16 circuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Curve {
+    Bn254,
+    Bls12_381,
+}
+
+impl Curve {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Curve::Bn254 => "bn254",
+            Curve::Bls12_381 => "bls12_381",
+        }
+    }
+
+    /// Name of the curve in the pallet's `Curve` enum
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Curve::Bn254 => "Bn254",
+            Curve::Bls12_381 => "Bls12_381",
+        }
+    }
+
+    /// Size of a base field element
+    pub fn field_size(&self) -> usize {
+        match self {
+            Curve::Bn254 => 32,
+            Curve::Bls12_381 => 48,
+        }
+    }
+
+    /// Size of an uncompressed G1 point: x then y
+    pub fn g1_size(&self) -> usize {
+        2 * self.field_size()
+    }
+
+    /// Size of an uncompressed G2 point: x.c0, x.c1, y.c0 then y.c1
+    pub fn g2_size(&self) -> usize {
+        4 * self.field_size()
+    }
+}
+
+impl fmt::Display for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Curve {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            // snarkjs calls BN254 bn128
+            "bn254" | "bn128" => Ok(Curve::Bn254),
+            "bls12_381" | "bls12381" | "bls12-381" => Ok(Curve::Bls12_381),
+            _ => anyhow::bail!("Unknown curve '{}': expected bn254 (bn128) or bls12_381", s),
+        }
+    }
+}
+
+// Curve named by a snarkjs file, if it names one
+fn named_curve(object: &Value) -> Result<Option<Curve>> {
+    object
+        .get("curve")
+        .and_then(Value::as_str)
+        .map(|curve| curve.parse().map_err(|e| bad_input(format!("{:#}", e))))
+        .transpose()
+}
+
+/// Convert the `proof.json`, `public.json` and `verification_key.json` of `snarkjs groth16 prove`
+/// and `snarkjs zkey export verificationkey` into zkVerify's Groth16 format: points uncompressed
+/// with little-endian coordinates, and public inputs as 32-byte little-endian scalars.
+/// The curve is taken from the VK.
+pub async fn convert(proof_path: &Path, public_path: &Path, vk_path: &Path) -> Result<ConvertedProof> {
+    let proof = read_json(proof_path).await?;
+    let public = read_json(public_path).await?;
+    let (vk, curve) = read_vk(vk_path).await?;
+
+    snarkjs::check_protocol(&proof, proof_path, "groth16")?;
+    if let Some(proof_curve) = named_curve(&proof)?
+        && proof_curve != curve
+    {
+        return Err(bad_input(format!("The proof is on {} but the VK on {}", proof_curve, curve)));
+    }
+
+    let size = curve.field_size();
+    let g1 = |value: &Value, name: &str| snarkjs::g1(value, name, size, Endian::Little);
+    let g2 = |value: &Value, name: &str| snarkjs::g2(value, name, size, Endian::Little);
+
+    let mut proof_bytes = g1(field(&proof, "pi_a", proof_path)?, "pi_a")?;
+    proof_bytes.extend(g2(field(&proof, "pi_b", proof_path)?, "pi_b")?);
+    proof_bytes.extend(g1(field(&proof, "pi_c", proof_path)?, "pi_c")?);
+
+    let inputs = snarkjs::public_inputs(&public, public_path)?;
+    let mut pubs = Vec::with_capacity(inputs.len() * SCALAR_SIZE);
+    for (index, input) in inputs.iter().enumerate() {
+        pubs.extend(snarkjs::field_element(input, &format!("public input {}", index), SCALAR_SIZE, Endian::Little)?);
+    }
+
+    let (vk_bytes, vk_inputs) = encode_vk(&vk, vk_path, curve)?;
+    if vk_inputs != inputs.len() {
+        return Err(bad_input(format!(
+            "The VK takes {} public inputs but {} has {}: was it proven for this circuit?",
+            vk_inputs,
+            public_path.display(),
+            inputs.len()
+        )));
+    }
+    status!("✅ Groth16 proof on {} with {} public inputs", curve, inputs.len());
+
+    Ok(ConvertedProof { curve: Some(curve), ..ConvertedProof::new(ProofType::Groth16, &proof_bytes, &pubs, &vk_bytes) })
+}
+
+// A `verification_key.json` and the curve it names
+async fn read_vk(vk_path: &Path) -> Result<(Value, Curve)> {
+    let vk = read_json(vk_path).await?;
+    snarkjs::check_protocol(&vk, vk_path, "groth16")?;
+    let curve = named_curve(&vk)?.ok_or_else(|| bad_input(format!("Missing 'curve' in {}", vk_path.display())))?;
+    Ok((vk, curve))
+}
+
+// The VK as alpha, beta, gamma, delta then IC, and the number of public inputs it takes
+fn encode_vk(vk: &Value, vk_path: &Path, curve: Curve) -> Result<(Vec<u8>, usize)> {
+    let size = curve.field_size();
+    let mut bytes = snarkjs::g1(field(vk, "vk_alpha_1", vk_path)?, "vk_alpha_1", size, Endian::Little)?;
+    for name in ["vk_beta_2", "vk_gamma_2", "vk_delta_2"] {
+        bytes.extend(snarkjs::g2(field(vk, name, vk_path)?, name, size, Endian::Little)?);
+    }
+    let ic = field(vk, "IC", vk_path)?.as_array().ok_or_else(|| bad_input(format!("'IC' in {} is not a list", vk_path.display())))?;
+    if ic.is_empty() {
+        return Err(bad_input(format!("'IC' in {} is empty", vk_path.display())));
+    }
+    for (index, point) in ic.iter().enumerate() {
+        bytes.extend(snarkjs::g1(point, &format!("IC[{}]", index), size, Endian::Little)?);
+    }
+    Ok((bytes, ic.len() - 1))
+}
+
+/// Points of a converted Groth16 VK: `alpha_g1`, `beta_g2`, `gamma_g2`, `delta_g2`, then one
+/// `gamma_abc_g1` point per public input plus one
+pub struct VkPoints<'a> {
+    pub alpha_g1: &'a [u8],
+    pub beta_g2: &'a [u8],
+    pub gamma_g2: &'a [u8],
+    pub delta_g2: &'a [u8],
+    pub gamma_abc_g1: Vec<&'a [u8]>,
+}
+
+impl<'a> VkPoints<'a> {
+    pub fn split(vk: &'a [u8], curve: Curve) -> Result<Self> {
+        let (g1, g2) = (curve.g1_size(), curve.g2_size());
+        let fixed = g1 + 3 * g2;
+        if vk.len() < fixed + g1 || !(vk.len() - fixed).is_multiple_of(g1) {
+            return Err(bad_input(format!("Groth16 VK is {} bytes, not a valid {} VK", vk.len(), curve)));
+        }
+        Ok(Self {
+            alpha_g1: &vk[..g1],
+            beta_g2: &vk[g1..g1 + g2],
+            gamma_g2: &vk[g1 + g2..g1 + 2 * g2],
+            delta_g2: &vk[g1 + 2 * g2..fixed],
+            gamma_abc_g1: vk[fixed..].chunks(g1).collect(),
+        })
+    }
+}
+
+/// Points `a`, `b` and `c` of a converted Groth16 proof
+pub fn split_proof(proof: &[u8], curve: Curve) -> Result<(&[u8], &[u8], &[u8])> {
+    let (g1, g2) = (curve.g1_size(), curve.g2_size());
+    if proof.len() != 2 * g1 + g2 {
+        return Err(bad_input(format!("Groth16 proof is {} bytes, expected {} on {}", proof.len(), 2 * g1 + g2, curve)));
+    }
+    Ok((&proof[..g1], &proof[g1..g1 + g2], &proof[g1 + g2..]))
+}
+
+fn curve_value(curve: Curve) -> subxt::dynamic::Value {
+    subxt::dynamic::Value::unnamed_variant(curve.variant(), vec![])
+}
+
+// The pallet's `Vk`: the curve and each point, uncompressed
+fn vk_value(points: &VkPoints, curve: Curve) -> subxt::dynamic::Value {
+    subxt::dynamic::Value::named_composite(vec![
+        ("curve", curve_value(curve)),
+        ("alpha_g1", bytes_value(points.alpha_g1)),
+        ("beta_g2", bytes_value(points.beta_g2)),
+        ("gamma_g2", bytes_value(points.gamma_g2)),
+        ("delta_g2", bytes_value(points.delta_g2)),
+        (
+            "gamma_abc_g1",
+            subxt::dynamic::Value::unnamed_composite(points.gamma_abc_g1.iter().map(|point| bytes_value(point)).collect::<Vec<_>>()),
+        ),
+    ])
+}
+
+// The pallet's `Proof`: the curve and the points `a`, `b` and `c`
+fn proof_value(proof: &[u8], curve: Curve) -> Result<subxt::dynamic::Value> {
+    let (a, b, c) = split_proof(proof, curve)?;
+    Ok(subxt::dynamic::Value::named_composite(vec![
+        ("curve", curve_value(curve)),
+        (
+            "proof",
+            subxt::dynamic::Value::named_composite(vec![("a", bytes_value(a)), ("b", bytes_value(b)), ("c", bytes_value(c))]),
+        ),
+    ]))
+}
+
+fn proof_curve(converted_proof: &ConvertedProof) -> Result<Curve> {
+    converted_proof.curve.ok_or_else(|| bad_input("Missing 'curve' in the Groth16 proof".to_string()))
+}
+
+/// Circom Groth16 proofs from snarkjs, for zkVerify's Groth16 pallet
+pub struct Groth16Backend;
+
+impl ProofBackend for Groth16Backend {
+    fn proof_type(&self) -> ProofType {
+        ProofType::Groth16
+    }
+
+    fn pallet(&self) -> &'static str {
+        "SettlementGroth16Pallet"
+    }
+
+    fn convert<'a>(&'a self, inputs: &'a ProofInputs) -> BoxFuture<'a, Result<ConvertedProof>> {
+        Box::pin(async move {
+            convert(&inputs.proof, inputs.public_inputs(ProofType::Groth16)?, inputs.vk_file(ProofType::Groth16)?).await
+        })
+    }
+
+    fn derive_vk<'a>(&'a self, inputs: &'a ProofInputs) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let vk_path = inputs.vk_file(ProofType::Groth16)?;
+            let (vk, curve) = read_vk(vk_path).await?;
+            Ok(to_hex_with_prefix(&encode_vk(&vk, vk_path, curve)?.0))
+        })
+    }
+
+    // Point sizes depend on the curve, and the VK has one more `gamma_abc_g1` point than
+    // there are public inputs
+    fn verify_locally(&self, converted_proof: &ConvertedProof) -> Result<()> {
+        let curve = proof_curve(converted_proof)?;
+        let (proof, pubs, vk) = converted_proof.decode()?;
+        split_proof(&proof, curve)?;
+        check_scalars(&pubs, SCALAR_SIZE, ProofType::Groth16)?;
+        let points = VkPoints::split(&vk, curve)?;
+        if points.gamma_abc_g1.len() != pubs.len() / SCALAR_SIZE + 1 {
+            return Err(bad_input(format!(
+                "The Groth16 VK takes {} public inputs but the proof has {}",
+                points.gamma_abc_g1.len() - 1,
+                pubs.len() / SCALAR_SIZE
+            )));
+        }
+        Ok(())
+    }
+
+    fn call_args(&self, converted_proof: &ConvertedProof) -> Result<CallArgs> {
+        let curve = proof_curve(converted_proof)?;
+        let (proof, pubs, vk) = converted_proof.decode()?;
+        Ok(CallArgs {
+            vk: vk_value(&VkPoints::split(&vk, curve)?, curve),
+            proof: proof_value(&proof, curve)?,
+            pubs: scalars_value(&pubs, SCALAR_SIZE, ProofType::Groth16)?,
+        })
+    }
+
+    // Points and scalars are each a `Vec<u8>`, and the curve the index of its variant
+    fn encode_args(&self, converted_proof: &ConvertedProof) -> Result<ScaleArgs> {
+        let curve = proof_curve(converted_proof)?;
+        let (proof, pubs, vk) = converted_proof.decode()?;
+        let points = VkPoints::split(&vk, curve)?;
+        let mut vk = (curve as u8).encode();
+        for point in [points.alpha_g1, points.beta_g2, points.gamma_g2, points.delta_g2] {
+            vk.extend(point.encode());
+        }
+        vk.extend(points.gamma_abc_g1.iter().map(|point| point.to_vec()).collect::<Vec<_>>().encode());
+
+        let (a, b, c) = split_proof(&proof, curve)?;
+        let mut proof = (curve as u8).encode();
+        for point in [a, b, c] {
+            proof.extend(point.encode());
+        }
+
+        check_scalars(&pubs, SCALAR_SIZE, ProofType::Groth16)?;
+        let pubs = pubs.chunks(SCALAR_SIZE).map(<[u8]>::to_vec).collect::<Vec<_>>().encode();
+        Ok(ScaleArgs { vk, proof, pubs })
+    }
+}
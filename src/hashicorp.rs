@@ -0,0 +1,225 @@
+use anyhow::Result;
+use reqwest::StatusCode;
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+use tracing::info;
+use zeroize::Zeroizing;
+
+use crate::failure::{FailureClass, ResultExt};
+use crate::network::{HttpClient, HttpPolicy};
+use crate::secret::SecretString;
+
+/// Field of the secret holding the mnemonic when the path names none
+pub const DEFAULT_FIELD: &str = "mnemonic";
+
+/// Mount of the AppRole auth method when `VAULT_APPROLE_MOUNT` is not set
+pub const DEFAULT_APPROLE_MOUNT: &str = "approle";
+
+/// A field of a KV version 2 secret in HashiCorp Vault, written `<mount>/<path>[#field]`,
+/// e.g. `secret/rusty_router/mainnet#mnemonic`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct VaultSecret {
+    /// Mount of the KV engine
+    pub mount: String,
+    /// Path of the secret within the mount
+    pub path: String,
+    pub field: String,
+}
+
+impl FromStr for VaultSecret {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (path, field) = s.split_once('#').unwrap_or((s, DEFAULT_FIELD));
+        match path.trim_matches('/').split_once('/') {
+            Some((mount, path)) if !mount.is_empty() && !path.is_empty() && !field.is_empty() => Ok(Self {
+                mount: mount.to_string(),
+                path: path.to_string(),
+                field: field.to_string(),
+            }),
+            _ => anyhow::bail!("Invalid Vault secret '{}': expected <mount>/<path>[#field]", s),
+        }
+    }
+}
+
+impl TryFrom<String> for VaultSecret {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for VaultSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}#{}", self.mount, self.path, self.field)
+    }
+}
+
+/// How the router logs in to Vault
+pub enum VaultAuth {
+    /// A token, from `VAULT_TOKEN`
+    Token(SecretString),
+    /// AppRole login, from `VAULT_ROLE_ID` and `VAULT_SECRET_ID`
+    AppRole { mount: String, role_id: String, secret_id: SecretString },
+}
+
+/// Reads secrets from a Vault server over its HTTP API
+pub struct VaultClient {
+    addr: String,
+    namespace: Option<String>,
+    auth: VaultAuth,
+    http: HttpClient,
+}
+
+impl VaultClient {
+    pub fn new(addr: &str, auth: VaultAuth, http_policy: HttpPolicy) -> Result<Self> {
+        Ok(Self {
+            addr: addr.trim_end_matches('/').to_string(),
+            namespace: None,
+            auth,
+            http: HttpClient::new(http_policy)?,
+        })
+    }
+
+    /// Vault Enterprise namespace requests are made in
+    pub fn with_namespace(mut self, namespace: Option<String>) -> Self {
+        self.namespace = namespace;
+        self
+    }
+
+    /// The server of `VAULT_ADDR`, in `VAULT_NAMESPACE` when set, logging in with `VAULT_TOKEN` or
+    /// else with the AppRole of `VAULT_ROLE_ID` and `VAULT_SECRET_ID`
+    pub fn from_env(http_policy: HttpPolicy) -> Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let addr = var("VAULT_ADDR").ok_or_else(|| FailureClass::BadInput.error("VAULT_ADDR is not set"))?;
+        let auth = match (var("VAULT_TOKEN"), var("VAULT_ROLE_ID"), var("VAULT_SECRET_ID")) {
+            (Some(token), _, _) => VaultAuth::Token(SecretString::new(token)),
+            (None, Some(role_id), Some(secret_id)) => VaultAuth::AppRole {
+                mount: var("VAULT_APPROLE_MOUNT").unwrap_or_else(|| DEFAULT_APPROLE_MOUNT.to_string()),
+                role_id,
+                secret_id: SecretString::new(secret_id),
+            },
+            _ => {
+                return Err(FailureClass::BadInput.error("Set VAULT_TOKEN, or VAULT_ROLE_ID and VAULT_SECRET_ID, to log in to Vault"));
+            }
+        };
+        Ok(Self::new(&addr, auth, http_policy)?.with_namespace(var("VAULT_NAMESPACE")))
+    }
+
+    /// The field of `secret`, `None` when the secret or the field does not exist
+    pub async fn read(&self, secret: &VaultSecret) -> Result<Option<SecretString>> {
+        let token = self.token().await?;
+        let url = format!("{}/v1/{}/data/{}", self.addr, secret.mount, secret.path);
+        let response = self
+            .http
+            .send(|client| self.request(client.get(&url)).header("X-Vault-Token", token.expose()))
+            .await
+            .classify(FailureClass::Network)?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body = checked(response, &format!("Reading Vault secret {}", secret)).await?;
+        let mnemonic = SecretField { path: &["data", "data"], field: &secret.field }
+            .deserialize(&mut serde_json::Deserializer::from_slice(&body))
+            .map_err(|e| anyhow::anyhow!("Unexpected response reading Vault secret {}: {}", secret, e))?;
+        Ok(mnemonic.map(|mnemonic| SecretString::new(mnemonic.trim())))
+    }
+
+    // The configured token, or one from an AppRole login
+    async fn token(&self) -> Result<SecretString> {
+        let (mount, role_id, secret_id) = match &self.auth {
+            VaultAuth::Token(token) => return Ok(token.clone()),
+            VaultAuth::AppRole { mount, role_id, secret_id } => (mount, role_id, secret_id),
+        };
+        let url = format!("{}/v1/auth/{}/login", self.addr, mount);
+        // Each login issues a new token, so a login whose response was lost is not repeated
+        let response = self
+            .http
+            .send_once(|client| {
+                self.request(client.post(&url))
+                    .json(&serde_json::json!({ "role_id": role_id, "secret_id": secret_id.expose() }))
+            })
+            .await
+            .classify(FailureClass::Network)?;
+        let body = checked(response, "Vault AppRole login").await?;
+        let token = SecretField { path: &["auth"], field: "client_token" }
+            .deserialize(&mut serde_json::Deserializer::from_slice(&body))?
+            .ok_or_else(|| anyhow::anyhow!("Vault AppRole login returned no client token"))?;
+        info!("Logged in to Vault with AppRole {}", role_id);
+        Ok(SecretString::new(token.as_str()))
+    }
+
+    fn request(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.namespace {
+            Some(namespace) => request.header("X-Vault-Namespace", namespace),
+            None => request,
+        }
+    }
+}
+
+// The body of a successful response; a refused login or read is bad input
+async fn checked(response: reqwest::Response, what: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let status = response.status();
+    let body = Zeroizing::new(response.bytes().await?.to_vec());
+    if !status.is_success() {
+        let errors = serde_json::from_slice::<Value>(&body)
+            .ok()
+            .and_then(|body| body.get("errors").map(Value::to_string))
+            .unwrap_or_default();
+        let class = match status {
+            StatusCode::FORBIDDEN | StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED => FailureClass::BadInput,
+            _ => FailureClass::Network,
+        };
+        return Err(class.error(format!("{} failed with {} {}", what, status, errors)));
+    }
+    Ok(body)
+}
+
+// Walks the JSON objects along `path` and keeps only the string `field` of the last one, so
+// that the response leaves no other copy of a secret in memory
+struct SecretField<'a> {
+    path: &'a [&'a str],
+    field: &'a str,
+}
+
+impl<'de> DeserializeSeed<'de> for SecretField<'_> {
+    type Value = Option<Zeroizing<String>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SecretField<'_> {
+    type Value = Option<Zeroizing<String>>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a JSON object")
+    }
+
+    // e.g. the `data` of a deleted secret version
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut found = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match self.path.split_first() {
+                Some((next, path)) if key == *next => {
+                    found = map.next_value_seed(SecretField { path, field: self.field })?;
+                }
+                None if key == self.field => found = map.next_value::<Option<String>>()?.map(Zeroizing::new),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(found)
+    }
+}
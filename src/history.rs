@@ -0,0 +1,409 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension, Row, ToSql};
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::extract::RequestDetails;
+use crate::router::Submission;
+use crate::substrate::TxEvent;
+
+/// Where a request got to in the pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryStatus {
+    Converted,
+    /// Broadcast, not yet seen in a block
+    Submitted,
+    /// In a best block, not yet finalized
+    InBlock,
+    Finalized,
+    Failed,
+}
+
+impl HistoryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoryStatus::Converted => "converted",
+            HistoryStatus::Submitted => "submitted",
+            HistoryStatus::InBlock => "in_block",
+            HistoryStatus::Finalized => "finalized",
+            HistoryStatus::Failed => "failed",
+        }
+    }
+
+    /// Submitted, but the outcome is not known yet
+    pub fn is_in_flight(&self) -> bool {
+        matches!(self, HistoryStatus::Submitted | HistoryStatus::InBlock)
+    }
+
+    /// Submitted, so routing the request again could submit it twice
+    pub fn is_routed(&self) -> bool {
+        *self == HistoryStatus::Finalized || self.is_in_flight()
+    }
+
+    // Statuses a request may move to this one from
+    fn follows(&self) -> &'static [HistoryStatus] {
+        use HistoryStatus::*;
+        match self {
+            // A fresh conversion or submission starts over
+            Converted => &[Converted, Submitted, InBlock, Finalized, Failed],
+            Submitted => &[Converted, Submitted, Finalized, Failed],
+            InBlock => &[Submitted, InBlock],
+            // Also straight from converted or failed when the duplicate check finds the proof
+            // already verified on chain, so nothing is broadcast
+            Finalized => &[Converted, Submitted, InBlock, Failed],
+            Failed => &[Converted, Submitted, InBlock, Failed],
+        }
+    }
+}
+
+impl fmt::Display for HistoryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for HistoryStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "converted" => Ok(HistoryStatus::Converted),
+            "submitted" => Ok(HistoryStatus::Submitted),
+            "in_block" | "in-block" => Ok(HistoryStatus::InBlock),
+            "finalized" => Ok(HistoryStatus::Finalized),
+            "failed" => Ok(HistoryStatus::Failed),
+            _ => anyhow::bail!("Unknown history status '{}'", s),
+        }
+    }
+}
+
+/// Latest state of one processed request
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+    pub request_id: String,
+    pub artifact_sha256: Option<String>,
+    pub output_path: Option<String>,
+    pub tx_hash: Option<String>,
+    pub block_hash: Option<String>,
+    /// Finalized block number when the extrinsic was broadcast, where reconciliation starts searching
+    pub from_block: Option<u32>,
+    /// Earlier attempts `tx_hash` replaced with a higher tip, any of which may still be the one included
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub replaced_tx_hashes: Vec<String>,
+    /// Job ID when submitted through the relayer
+    pub relayer_job_id: Option<String>,
+    /// How long the SP1 conversion took
+    pub convert_seconds: Option<f64>,
+    /// From the explorer, when it showed them
+    pub cycles: Option<u64>,
+    pub gas_used: Option<u64>,
+    pub requester: Option<String>,
+    pub fulfilled_at: Option<String>,
+    pub proof_mode: Option<String>,
+    /// IPFS CIDs of the artifact and the converted proof, when archived
+    pub artifact_cid: Option<String>,
+    pub proof_cid: Option<String>,
+    pub status: HistoryStatus,
+    pub error: Option<String>,
+    /// Unix timestamps, in seconds
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl HistoryEntry {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let status: String = row.get("status")?;
+        Ok(Self {
+            request_id: row.get("request_id")?,
+            artifact_sha256: row.get("artifact_sha256")?,
+            output_path: row.get("output_path")?,
+            tx_hash: row.get("tx_hash")?,
+            block_hash: row.get("block_hash")?,
+            from_block: row.get("from_block")?,
+            replaced_tx_hashes: row
+                .get::<_, Option<String>>("replaced_tx_hashes")?
+                .map(|hashes| hashes.split(',').map(str::to_string).collect())
+                .unwrap_or_default(),
+            relayer_job_id: row.get("relayer_job_id")?,
+            convert_seconds: row.get("convert_seconds")?,
+            cycles: row.get("cycles")?,
+            gas_used: row.get("gas_used")?,
+            requester: row.get("requester")?,
+            fulfilled_at: row.get("fulfilled_at")?,
+            proof_mode: row.get("proof_mode")?,
+            artifact_cid: row.get("artifact_cid")?,
+            proof_cid: row.get("proof_cid")?,
+            status: status.parse().map_err(|e: anyhow::Error| {
+                rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, e.into())
+            })?,
+            error: row.get("error")?,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+// Applied in order; `PRAGMA user_version` records how many have run
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS jobs (
+        request_id      TEXT PRIMARY KEY,
+        artifact_sha256 TEXT,
+        output_path     TEXT,
+        tx_hash         TEXT,
+        block_hash      TEXT,
+        status          TEXT NOT NULL,
+        error           TEXT,
+        created_at      INTEGER NOT NULL,
+        updated_at      INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS jobs_status ON jobs (status);",
+    // Submission states; `submitted` used to mean finalized
+    "ALTER TABLE jobs ADD COLUMN from_block INTEGER;
+    ALTER TABLE jobs ADD COLUMN relayer_job_id TEXT;
+    UPDATE jobs SET status = 'finalized' WHERE status = 'submitted';",
+    "ALTER TABLE jobs ADD COLUMN convert_seconds REAL;",
+    "ALTER TABLE jobs ADD COLUMN cycles INTEGER;
+    ALTER TABLE jobs ADD COLUMN gas_used INTEGER;
+    ALTER TABLE jobs ADD COLUMN requester TEXT;
+    ALTER TABLE jobs ADD COLUMN fulfilled_at TEXT;
+    ALTER TABLE jobs ADD COLUMN proof_mode TEXT;",
+    "ALTER TABLE jobs ADD COLUMN artifact_cid TEXT;
+    ALTER TABLE jobs ADD COLUMN proof_cid TEXT;",
+    "ALTER TABLE jobs ADD COLUMN replaced_tx_hashes TEXT;",
+];
+
+/// Conversions averaged for [`History::average_conversion_time`]
+const CONVERSION_SAMPLE: u32 = 20;
+
+fn now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or_default()
+}
+
+/// Embedded SQLite record of every processed request
+pub struct History {
+    connection: Mutex<Connection>,
+}
+
+impl History {
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut connection = Connection::open(path)?;
+        let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let transaction = connection.transaction()?;
+            transaction.execute_batch(migration)?;
+            transaction.pragma_update(None, "user_version", index + 1)?;
+            transaction.commit()?;
+        }
+        Ok(Self { connection: Mutex::new(connection) })
+    }
+
+    fn connection(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.connection.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Move the request to `status` and apply `update` in one transaction, refusing
+    // transitions the state machine does not allow (e.g. finalized back to submitted)
+    fn transition(&self, request_id: &str, status: HistoryStatus, update: &str, values: &[&dyn ToSql]) -> Result<()> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+        let current: Option<String> = transaction
+            .query_row("SELECT status FROM jobs WHERE request_id = ?1", params![request_id], |row| row.get(0))
+            .optional()?;
+        let now = now();
+        match current {
+            Some(current) => {
+                let current: HistoryStatus = current.parse()?;
+                if !status.follows().contains(&current) {
+                    anyhow::bail!("History for {} cannot move from {} to {}", request_id, current, status);
+                }
+                transaction.execute(
+                    "UPDATE jobs SET status = ?2, updated_at = ?3 WHERE request_id = ?1",
+                    params![request_id, status.as_str(), now],
+                )?;
+            }
+            None => {
+                transaction.execute(
+                    "INSERT INTO jobs (request_id, status, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+                    params![request_id, status.as_str(), now],
+                )?;
+            }
+        }
+        let mut update_values: Vec<&dyn ToSql> = vec![&request_id];
+        update_values.extend_from_slice(values);
+        transaction.execute(&format!("UPDATE jobs SET {} WHERE request_id = ?1", update), update_values.as_slice())?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    pub fn record_conversion(
+        &self,
+        request_id: &str,
+        artifact_sha256: &str,
+        output_path: &Path,
+        conversion_time: Duration,
+        details: Option<&RequestDetails>,
+    ) -> Result<()> {
+        let details = details.cloned().unwrap_or_default();
+        self.transition(
+            request_id,
+            HistoryStatus::Converted,
+            "artifact_sha256 = ?2, output_path = ?3, convert_seconds = ?4, cycles = ?5, gas_used = ?6,
+             requester = ?7, fulfilled_at = ?8, proof_mode = ?9, tx_hash = NULL, block_hash = NULL,
+             from_block = NULL, relayer_job_id = NULL, error = NULL",
+            params![
+                artifact_sha256,
+                output_path.display().to_string(),
+                conversion_time.as_secs_f64(),
+                details.cycles.map(|cycles| cycles as i64),
+                details.gas_used.map(|gas| gas as i64),
+                details.requester,
+                details.fulfilled_at,
+                details.proof_mode,
+            ],
+        )
+    }
+
+    /// The converted request's artifact and proof were pinned to IPFS; the status is unchanged
+    pub fn record_archive(&self, request_id: &str, artifact_cid: &str, proof_cid: &str) -> Result<()> {
+        self.connection().execute(
+            "UPDATE jobs SET artifact_cid = ?2, proof_cid = ?3, updated_at = ?4 WHERE request_id = ?1",
+            params![request_id, artifact_cid, proof_cid, now()],
+        )?;
+        Ok(())
+    }
+
+    /// Mean SP1 conversion time of the most recent conversions, if any were recorded
+    pub fn average_conversion_time(&self) -> Result<Option<Duration>> {
+        let seconds: Option<f64> = self.connection().query_row(
+            "SELECT AVG(convert_seconds) FROM (
+                SELECT convert_seconds FROM jobs WHERE convert_seconds IS NOT NULL ORDER BY updated_at DESC LIMIT ?1
+            )",
+            params![CONVERSION_SAMPLE],
+            |row| row.get(0),
+        )?;
+        Ok(seconds.map(Duration::from_secs_f64))
+    }
+
+    /// The extrinsic was broadcast; see [`crate::substrate::TxEvent::Broadcast`]
+    pub fn record_broadcast(&self, request_id: &str, tx_hash: &str, from_block: u32) -> Result<()> {
+        // A fee bump re-signs the transaction from the same block on; the attempt it replaces
+        // is kept, since it may still be included
+        self.transition(
+            request_id,
+            HistoryStatus::Submitted,
+            "replaced_tx_hashes = CASE
+                WHEN from_block = ?3 AND tx_hash IS NOT NULL AND tx_hash != ?2
+                THEN COALESCE(replaced_tx_hashes || ',', '') || tx_hash
+            END,
+            tx_hash = ?2, from_block = ?3, error = NULL",
+            params![tx_hash, from_block],
+        )
+    }
+
+    /// The proof was handed to the relayer as job `job_id`
+    pub fn record_relayer_job(&self, request_id: &str, job_id: &str) -> Result<()> {
+        self.transition(
+            request_id,
+            HistoryStatus::Submitted,
+            "relayer_job_id = ?2, error = NULL",
+            params![job_id],
+        )
+    }
+
+    /// Record a step of a direct submission
+    pub fn record_tx_event(&self, request_id: &str, event: &TxEvent) -> Result<()> {
+        match event {
+            TxEvent::Broadcast { tx_hash, from_block } => self.record_broadcast(request_id, tx_hash, *from_block),
+            TxEvent::InBlock { block_hash } => self.record_in_block(request_id, &format!("{:?}", block_hash)),
+        }
+    }
+
+    pub fn record_in_block(&self, request_id: &str, block_hash: &str) -> Result<()> {
+        self.transition(request_id, HistoryStatus::InBlock, "block_hash = ?2", params![block_hash])
+    }
+
+    pub fn record_finalized(&self, request_id: &str, submission: &Submission) -> Result<()> {
+        self.transition(
+            request_id,
+            HistoryStatus::Finalized,
+            "tx_hash = ?2, block_hash = COALESCE(?3, block_hash), error = NULL",
+            params![submission.tx_hash, submission.block_hash],
+        )
+    }
+
+    pub fn record_failure(&self, request_id: &str, error: &str) -> Result<()> {
+        self.transition(request_id, HistoryStatus::Failed, "error = ?2", params![error])
+    }
+
+    pub fn get(&self, request_id: &str) -> Result<Option<HistoryEntry>> {
+        Ok(self
+            .connection()
+            .query_row("SELECT * FROM jobs WHERE request_id = ?1", params![request_id], HistoryEntry::from_row)
+            .optional()?)
+    }
+
+    pub fn is_routed(&self, request_id: &str) -> Result<bool> {
+        Ok(self.get(request_id)?.is_some_and(|entry| entry.status.is_routed()))
+    }
+
+    /// Entries left submitted or in a block, e.g. by a crash before the outcome was recorded
+    pub fn in_flight(&self) -> Result<Vec<HistoryEntry>> {
+        let connection = self.connection();
+        let mut statement = connection.prepare("SELECT * FROM jobs WHERE status IN ('submitted', 'in_block')")?;
+        let entries = statement
+            .query_map([], HistoryEntry::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    /// Most recently updated entries first, optionally only those with `status`
+    pub fn list(&self, status: Option<HistoryStatus>, limit: Option<u32>) -> Result<Vec<HistoryEntry>> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(
+            "SELECT * FROM jobs WHERE ?1 IS NULL OR status = ?1 ORDER BY updated_at DESC LIMIT ?2",
+        )?;
+        let entries = statement
+            .query_map(
+                params![status.map(|status| status.as_str()), limit.map_or(-1, i64::from)],
+                HistoryEntry::from_row,
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission() -> Submission {
+        Submission {
+            tx_hash: "0x01".to_string(),
+            block_hash: Some("0x02".to_string()),
+            block_number: Some(3),
+            aggregation_id: None,
+            events: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn an_already_verified_conversion_moves_to_finalized() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = History::open(&dir.path().join("history.db")).unwrap();
+        history
+            .record_conversion("request", "sha256", Path::new("proof.json"), Duration::from_secs(1), None)
+            .unwrap();
+        assert!(!history.is_routed("request").unwrap());
+
+        history.record_finalized("request", &submission()).unwrap();
+        let entry = history.get("request").unwrap().unwrap();
+        assert_eq!(entry.status, HistoryStatus::Finalized);
+        assert_eq!(entry.tx_hash.as_deref(), Some("0x01"));
+        assert!(history.is_routed("request").unwrap());
+    }
+}
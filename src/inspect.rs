@@ -0,0 +1,230 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::borrow::Cow;
+use subxt::utils::H256;
+
+use crate::converter::{
+    decompress, normalize_unversioned, ConvertedProof, ProofType, FORMAT_VERSION, LARGE_PROOF_THRESHOLD, ZERO_VK,
+};
+use crate::substrate::vk_hash;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The proof would be rejected or cannot be submitted as is
+    Error,
+    /// The proof is accepted but something looks off
+    Warning,
+}
+
+/// Problem found in a converted proof file
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Offline report on a converted proof file, see [`inspect`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Inspection {
+    pub proof_bytes: Option<usize>,
+    pub pubs_bytes: Option<usize>,
+    pub vk_bytes: Option<usize>,
+    /// Hash zkVerify registers the VK under, when the VK is well-formed
+    pub vk_hash: Option<H256>,
+    pub request_id: Option<String>,
+    pub findings: Vec<Finding>,
+}
+
+impl Inspection {
+    /// Whether no finding would stop the proof from being submitted
+    pub fn is_valid(&self) -> bool {
+        self.findings.iter().all(|finding| finding.severity != Severity::Error)
+    }
+
+    fn error(&mut self, message: impl Into<String>) {
+        self.findings.push(Finding { severity: Severity::Error, message: message.into() });
+    }
+
+    fn warning(&mut self, message: impl Into<String>) {
+        self.findings.push(Finding { severity: Severity::Warning, message: message.into() });
+    }
+}
+
+const KNOWN_FIELDS: [&str; 9] = ["format_version", "proof_type", "curve", "plonky2", "proof", "pubs", "vk", "request_id", "warnings"];
+
+// Names other tools use for our fields
+const FIELD_ALIASES: [(&str, &str); 8] = [
+    ("public_inputs", "pubs"),
+    ("pub_inputs", "pubs"),
+    ("public_values", "pubs"),
+    ("publicSignals", "pubs"),
+    ("public_signals", "pubs"),
+    ("vkey", "vk"),
+    ("verification_key", "vk"),
+    ("requestId", "request_id"),
+];
+
+/// Check the structure of a converted proof file without touching the network: field
+/// names, hex encoding, component lengths and the VK, whose hash is recomputed
+pub fn inspect(content: &[u8]) -> Inspection {
+    let mut inspection = Inspection::default();
+    // Compressed proofs are checked decompressed; a corrupt frame shows up as invalid JSON
+    let decompressed = decompress(content).unwrap_or(Cow::Borrowed(content));
+    let content = &*decompressed;
+    // CBOR and MessagePack proofs are checked as the JSON they hold
+    let json;
+    let content = match content.trim_ascii_start().first() {
+        Some(b'{') | None => content,
+        Some(_) => match ConvertedProof::from_bytes(content).and_then(|proof| Ok(serde_json::to_vec(&proof)?)) {
+            Ok(converted) => {
+                json = converted;
+                &json
+            }
+            Err(_) => content,
+        },
+    };
+    let mut object = match serde_json::from_slice::<Value>(content) {
+        Ok(Value::Object(object)) => object,
+        Ok(_) => {
+            inspection.error("Not a JSON object");
+            return inspection;
+        }
+        Err(e) => {
+            inspection.error(format!("Not valid JSON: {}", e));
+            return inspection;
+        }
+    };
+
+    // Unversioned files are read as submission reads them, deprecated spellings included
+    match object.get("format_version").map(Value::as_u64) {
+        None => {
+            for deprecation in normalize_unversioned(&mut object) {
+                inspection.warning(deprecation);
+            }
+        }
+        Some(Some(version)) if version <= FORMAT_VERSION as u64 => {}
+        Some(Some(version)) => {
+            inspection.error(format!("'format_version' {} is newer than this build reads ({})", version, FORMAT_VERSION));
+            return inspection;
+        }
+        Some(None) => inspection.error("'format_version' is not a number"),
+    }
+
+    for name in object.keys().filter(|name| !KNOWN_FIELDS.contains(&name.as_str())) {
+        let message = match FIELD_ALIASES.iter().find(|(alias, _)| alias == name) {
+            Some((_, field)) => format!("Unknown field '{}'; did you mean '{}'?", name, field),
+            None => format!("Unknown field '{}' is ignored", name),
+        };
+        inspection.warning(message);
+    }
+
+    let proof_type = match object.get("proof_type") {
+        Some(Value::String(proof_type)) => match proof_type.parse() {
+            Ok(proof_type) => proof_type,
+            Err(e) => {
+                inspection.error(format!("{:#}", e));
+                return inspection;
+            }
+        },
+        Some(_) => {
+            inspection.error("'proof_type' is not a string");
+            return inspection;
+        }
+        None => ProofType::Sp1,
+    };
+
+    inspection.proof_bytes = hex_field(&mut inspection, &object, "proof");
+    inspection.pubs_bytes = hex_field(&mut inspection, &object, "pubs");
+    // Circuits without public inputs are fine for UltraPlonk
+    let pubs_bytes = inspection.pubs_bytes.filter(|_| proof_type == ProofType::Sp1);
+    for (field, len) in [("proof", inspection.proof_bytes), ("pubs", pubs_bytes)] {
+        if len == Some(0) {
+            inspection.error(format!("'{}' is empty", field));
+        }
+    }
+    if let Some(len) = inspection.proof_bytes
+        && len > LARGE_PROOF_THRESHOLD
+    {
+        inspection.warning(format!("Proof is unusually large ({} bytes)", len));
+    }
+    if proof_type == ProofType::Sp1 {
+        inspect_vk(&mut inspection, &object);
+    } else {
+        inspection.vk_bytes = hex_field(&mut inspection, &object, "vk");
+        // Once every component decodes, the backend checks their sizes
+        if inspection.is_valid() {
+            match serde_json::from_value::<ConvertedProof>(Value::Object(object.clone())) {
+                Ok(converted_proof) => {
+                    if let Err(e) = proof_type.backend().verify_locally(&converted_proof) {
+                        inspection.error(format!("{:#}", e));
+                    }
+                }
+                Err(e) => inspection.error(format!("Not a valid {} proof: {}", proof_type, e)),
+            }
+        }
+    }
+
+    match object.get("request_id") {
+        Some(Value::String(request_id)) => inspection.request_id = Some(request_id.clone()),
+        Some(Value::Null) | None => {}
+        Some(_) => inspection.error("'request_id' is not a string"),
+    }
+    inspection
+}
+
+// Length of the hex string field `name`, flagging a missing field, a missing prefix or bad hex
+fn hex_field(inspection: &mut Inspection, object: &serde_json::Map<String, Value>, name: &str) -> Option<usize> {
+    let value = match object.get(name) {
+        Some(Value::String(value)) => value,
+        Some(_) => {
+            inspection.error(format!("'{}' is not a string", name));
+            return None;
+        }
+        None => {
+            inspection.error(format!("Missing field '{}'", name));
+            return None;
+        }
+    };
+    let hex_value = match value.strip_prefix("0x") {
+        Some(hex_value) => hex_value,
+        None => {
+            inspection.warning(format!("'{}' has no 0x prefix", name));
+            value
+        }
+    };
+    match hex::decode(hex_value) {
+        Ok(bytes) => Some(bytes.len()),
+        Err(e) => {
+            inspection.error(format!("'{}' is not valid hex: {}", name, e));
+            None
+        }
+    }
+}
+
+fn inspect_vk(inspection: &mut Inspection, object: &serde_json::Map<String, Value>) {
+    if hex_field(inspection, object, "vk").is_none() {
+        return;
+    }
+    let vk = object["vk"].as_str().expect("checked by hex_field");
+    let mut bytes = hex::decode(vk.trim_start_matches("0x")).expect("checked by hex_field");
+
+    // The hex text of the VK, hex-encoded again
+    if let Ok(inner) = std::str::from_utf8(&bytes)
+        && let Ok(inner_bytes) = hex::decode(inner.trim_start_matches("0x"))
+        && !inner_bytes.is_empty()
+    {
+        inspection.warning("'vk' is hex-encoded twice; it is decoded on submission, but prefer the plain hex VK");
+        bytes = inner_bytes;
+    }
+
+    inspection.vk_bytes = Some(bytes.len());
+    if bytes.len() != 32 {
+        inspection.error(format!("'vk' is {} bytes, expected 32", bytes.len()));
+        return;
+    }
+    if format!("0x{}", hex::encode(&bytes)) == ZERO_VK {
+        inspection.warning("'vk' is the all-zero placeholder used when no VK was found");
+    }
+    inspection.vk_hash = Some(vk_hash(&bytes));
+}
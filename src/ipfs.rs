@@ -0,0 +1,141 @@
+use anyhow::Result;
+use reqwest::multipart::{Form, Part};
+use reqwest::Body;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info};
+
+use crate::failure::FailureClass;
+use crate::network::{HttpClient, HttpPolicy};
+use crate::output::write_atomic;
+
+/// What was pinned for one conversion, written next to the proof as `<proof>.ipfs.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveReceipt {
+    pub request_id: Option<String>,
+    /// Hex SHA-256 of the artifact
+    pub artifact_sha256: String,
+    pub artifact_cid: String,
+    pub proof_path: PathBuf,
+    pub proof_cid: String,
+    /// Unix timestamp, in seconds
+    pub pinned_at: u64,
+}
+
+impl ArchiveReceipt {
+    /// Where the receipt of the proof at `proof_path` is written, e.g. `proof.ipfs.json`
+    pub fn path_for(proof_path: &Path) -> PathBuf {
+        proof_path.with_extension("ipfs.json")
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        write_atomic(path, serde_json::to_string_pretty(self)?)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AddResponse {
+    hash: String,
+}
+
+/// Client for the HTTP RPC API of an IPFS node (Kubo, or a pinning service speaking its API),
+/// pinning artifacts and converted proofs for an audit trail
+pub struct IpfsClient {
+    client: HttpClient,
+    api_url: String,
+    token: Option<String>,
+}
+
+impl IpfsClient {
+    /// Node API at `api_url`, e.g. `http://127.0.0.1:5001`
+    pub fn new(api_url: &str) -> Self {
+        Self { client: HttpClient::default(), api_url: api_url.trim_end_matches('/').to_string(), token: None }
+    }
+
+    /// Send API calls under `policy` instead of the default (no timeouts or retries)
+    pub fn with_http_policy(mut self, policy: HttpPolicy) -> Result<Self> {
+        self.client = HttpClient::new(policy)?;
+        Ok(self)
+    }
+
+    /// Bearer token for nodes and pinning services that require one
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Add and pin the file at `path`, returning its CID (v1). The file is streamed from disk,
+    /// opened again for each retry, rather than read into memory.
+    pub async fn pin_file(&self, path: &Path) -> Result<String> {
+        let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let url = format!("{}/api/v0/add?pin=true&cid-version=1", self.api_url);
+        let body = self
+            .client
+            .retry(&format!("Pinning {}", path.display()), || async {
+                let file = tokio::fs::File::open(path)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+                let length = file.metadata().await?.len();
+                // Taken by the single attempt `send_once` makes
+                let file = Mutex::new(Some(file));
+                let response = self
+                    .client
+                    .send_once(|client| {
+                        let body = file.lock().expect("file lock").take().map_or_else(|| Body::from(Vec::new()), Body::from);
+                        let form = Form::new().part("file", Part::stream_with_length(body, length).file_name(name.clone()));
+                        let request = client.post(&url).multipart(form);
+                        match &self.token {
+                            Some(token) => request.bearer_auth(token),
+                            None => request,
+                        }
+                    })
+                    .await
+                    .map_err(|e| FailureClass::Network.error(format!("Failed to reach the IPFS API at {}: {:#}", self.api_url, e)))?;
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let body = response.text().await.unwrap_or_default();
+                    return Err(FailureClass::Network.error(format!("IPFS node refused {} ({}): {}", path.display(), status, body.trim())));
+                }
+                Ok(response.text().await?)
+            })
+            .await?;
+        // One JSON object per added file; the last one is the file itself
+        let added: AddResponse = body
+            .lines()
+            .rfind(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .transpose()?
+            .ok_or_else(|| FailureClass::Network.error(format!("IPFS node returned no CID for {}", path.display())))?;
+        debug!("Pinned {} as {}", path.display(), added.hash);
+        Ok(added.hash)
+    }
+
+    /// Pin the artifact and the converted proof, and write their CIDs to the proof's receipt
+    pub async fn archive(
+        &self,
+        request_id: Option<&str>,
+        artifact_path: &Path,
+        artifact_sha256: &str,
+        proof_path: &Path,
+    ) -> Result<ArchiveReceipt> {
+        let artifact_cid = self.pin_file(artifact_path).await?;
+        info!("Artifact pinned to IPFS as {}", artifact_cid);
+        let proof_cid = self.pin_file(proof_path).await?;
+        info!("Proof pinned to IPFS as {}", proof_cid);
+        let receipt = ArchiveReceipt {
+            request_id: request_id.map(str::to_string),
+            artifact_sha256: artifact_sha256.to_string(),
+            artifact_cid,
+            proof_path: proof_path.to_path_buf(),
+            proof_cid,
+            pinned_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or_default(),
+        };
+        receipt.save(&ArchiveReceipt::path_for(proof_path)).await?;
+        Ok(receipt)
+    }
+}
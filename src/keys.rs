@@ -0,0 +1,64 @@
+use anyhow::Result;
+use bip39::Mnemonic;
+use serde::{Serialize, Serializer};
+use subxt_signer::{sr25519::Keypair, SecretUri};
+use zeroize::Zeroize;
+
+use crate::failure::FailureClass;
+use crate::secret::SecretString;
+use crate::substrate::ss58_address;
+
+/// Word counts a generated mnemonic may have
+pub const MNEMONIC_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// An sr25519 account, as the router signs with it
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyInfo {
+    /// Set for a generated key only; inspecting never echoes the secret back
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "expose")]
+    pub mnemonic: Option<SecretString>,
+    /// SS58 address for the network's prefix
+    pub address: String,
+    pub ss58_prefix: u16,
+    /// Hex public key, which is also the account ID
+    pub public_key: String,
+}
+
+impl KeyInfo {
+    /// A new account from a fresh mnemonic of `words` words
+    pub fn generate(words: usize, ss58_prefix: u16) -> Result<Self> {
+        if !MNEMONIC_WORD_COUNTS.contains(&words) {
+            return Err(FailureClass::BadInput.error(format!("Mnemonics have 12, 15, 18, 21 or 24 words, not {}", words)));
+        }
+        let mut mnemonic = Mnemonic::generate(words)?;
+        let keypair = Keypair::from_phrase(&mnemonic, None)?;
+        let phrase = SecretString::new(mnemonic.to_string());
+        mnemonic.zeroize();
+        Ok(Self { mnemonic: Some(phrase), ..Self::of(&keypair, ss58_prefix) })
+    }
+
+    /// The account of a mnemonic, a `0x` hex seed or a secret URI with derivation paths such as
+    /// `<mnemonic>//hard/soft`
+    pub fn inspect(secret: &str, ss58_prefix: u16) -> Result<Self> {
+        let uri: SecretUri = secret
+            .trim()
+            .parse()
+            .map_err(|e| FailureClass::BadInput.error(format!("Invalid secret: {}", e)))?;
+        let keypair = Keypair::from_uri(&uri).map_err(|e| FailureClass::BadInput.error(format!("Invalid secret: {}", e)))?;
+        Ok(Self::of(&keypair, ss58_prefix))
+    }
+
+    fn of(keypair: &Keypair, ss58_prefix: u16) -> Self {
+        Self {
+            mnemonic: None,
+            address: ss58_address(&keypair.public_key().to_account_id(), ss58_prefix),
+            ss58_prefix,
+            public_key: format!("0x{}", hex::encode(keypair.public_key().0)),
+        }
+    }
+}
+
+// Only a generated key's mnemonic is ever serialized, to print it once
+fn expose<S: Serializer>(secret: &Option<SecretString>, serializer: S) -> Result<S::Ok, S::Error> {
+    secret.as_ref().map(SecretString::expose).serialize(serializer)
+}
@@ -1,3 +1,59 @@
+// Declared first so `status!` is in scope in the modules below
+#[macro_use]
+pub mod output;
+
+pub mod abi;
+pub mod aggregation;
+#[cfg(feature = "aws-secrets")]
+pub mod aws_secrets;
+pub mod backend;
+pub mod batch;
+pub mod browser;
+pub mod cache;
+pub mod calldata;
+pub mod capabilities;
 pub mod client;
+pub mod config;
 pub mod converter;
+pub mod credentials;
+pub mod convert_dir;
+#[cfg(feature = "eth")]
+pub mod eth;
+pub mod extract;
+pub mod failure;
+pub mod fflonk;
+pub mod groth16;
+#[cfg(feature = "hashicorp-vault")]
+pub mod hashicorp;
+pub mod history;
+pub mod inspect;
+pub mod ipfs;
+pub mod keys;
+pub mod logging;
+pub mod network;
+pub mod plonky2;
+pub mod policy;
+pub mod progress;
+pub mod queue;
+pub mod receipt;
+pub mod relayer;
+pub mod remark;
+pub mod router;
+#[cfg(feature = "s3")]
+pub mod s3;
+pub mod scheduler;
+pub mod secret;
+pub mod signing;
+mod snarkjs;
+pub mod sp1_version;
+pub mod statement;
+pub mod stats;
 pub mod substrate;
+#[cfg(feature = "otel")]
+pub mod telemetry;
+pub mod ultraplonk;
+#[cfg(feature = "vault")]
+pub mod vault;
+pub mod warnings;
+pub mod watch;
+pub mod webhook;
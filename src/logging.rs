@@ -0,0 +1,218 @@
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::format::{JsonFields, Writer};
+use tracing_subscriber::fmt::time::{FormatTime, SystemTime};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields, MakeWriter};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer, Registry};
+
+use crate::secret::Redacting;
+#[cfg(feature = "otel")]
+use crate::telemetry::{self, TelemetryGuard};
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// How often `--log-file` starts a new file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogRotation {
+    Never,
+    Hourly,
+    Daily,
+}
+
+impl FromStr for LogRotation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "never" => Ok(LogRotation::Never),
+            "hourly" => Ok(LogRotation::Hourly),
+            "daily" => Ok(LogRotation::Daily),
+            _ => anyhow::bail!("Unknown log rotation '{}': expected never, hourly or daily", s),
+        }
+    }
+}
+
+/// How log events are written to the console and the log file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per event, for log collectors
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => anyhow::bail!("Unknown log format '{}': expected text or json", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogOptions {
+    /// Print debug logs to the console
+    pub verbose: bool,
+    pub format: LogFormat,
+    /// Also write logs, including status lines, to this file
+    pub log_file: Option<PathBuf>,
+    pub rotation: LogRotation,
+    /// Rotated files kept besides the current one; all are kept when `None`
+    pub max_files: Option<usize>,
+    /// Export pipeline spans to this OTLP/HTTP traces endpoint
+    #[cfg(feature = "otel")]
+    pub otlp_endpoint: Option<String>,
+}
+
+/// Flushes the log file and exported spans when dropped
+pub struct LogGuard {
+    _file: Option<WorkerGuard>,
+    #[cfg(feature = "otel")]
+    _telemetry: Option<TelemetryGuard>,
+}
+
+// RUST_LOG when it is set, `default` otherwise
+fn env_filter(default: &str) -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default))
+}
+
+// Log events into `writer` in the requested format, with secrets and proof payloads redacted
+fn fmt_layer<W>(format: LogFormat, writer: W, ansi: bool, filter: EnvFilter) -> BoxedLayer
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(Redacting(writer)).with_ansi(ansi);
+    match format {
+        LogFormat::Text => layer.with_target(false).compact().with_filter(filter).boxed(),
+        LogFormat::Json => layer
+            .fmt_fields(JsonFields::new())
+            .event_format(JsonFormat)
+            .with_filter(filter)
+            .boxed(),
+    }
+}
+
+/// One JSON object per line with `timestamp`, `level`, `target`, the event's message and fields,
+/// and the fields of the spans the event happened in, so a job's `request_id` is on every event of it
+struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    N: for<'writer> FormatFields<'writer> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        let mut timestamp = String::new();
+        SystemTime.format_time(&mut Writer::new(&mut timestamp))?;
+        let metadata = event.metadata();
+        let mut object = Map::new();
+        object.insert("timestamp".to_string(), Value::String(timestamp));
+        object.insert("level".to_string(), Value::String(metadata.level().to_string()));
+        object.insert("target".to_string(), Value::String(metadata.target().to_string()));
+
+        // Outer spans first, so inner spans and the event itself win on name clashes
+        for span in ctx.event_scope().into_iter().flat_map(|scope| scope.from_root()) {
+            if let Some(fields) = span.extensions().get::<FormattedFields<N>>()
+                && let Ok(Value::Object(fields)) = serde_json::from_str::<Value>(fields)
+            {
+                object.extend(fields);
+            }
+        }
+        event.record(&mut JsonVisitor(&mut object));
+        writeln!(writer, "{}", Value::Object(object))
+    }
+}
+
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), Value::String(value.to_string()));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_string(), Value::String(format!("{:?}", value)));
+    }
+}
+
+/// Install the console, log file and OTLP layers the options ask for
+pub fn init(options: &LogOptions) -> Result<LogGuard> {
+    let mut layers: Vec<BoxedLayer> = Vec::new();
+
+    if options.verbose {
+        // Status lines are already printed as they are
+        let filter = env_filter("warn,rusty_router=debug").add_directive("status=off".parse()?);
+        layers.push(fmt_layer(options.format, io::stdout, options.format == LogFormat::Text, filter));
+    }
+
+    let file_guard = match &options.log_file {
+        Some(path) => {
+            let directory = path.parent().filter(|parent| !parent.as_os_str().is_empty());
+            let file_name = path
+                .file_name()
+                .ok_or_else(|| anyhow::anyhow!("Log file {} has no file name", path.display()))?;
+            let mut builder = RollingFileAppender::builder()
+                .rotation(match options.rotation {
+                    LogRotation::Never => Rotation::NEVER,
+                    LogRotation::Hourly => Rotation::HOURLY,
+                    LogRotation::Daily => Rotation::DAILY,
+                })
+                .filename_prefix(file_name.to_string_lossy());
+            if let Some(max_files) = options.max_files {
+                // The current file counts towards the limit
+                builder = builder.max_log_files(max_files + 1);
+            }
+            let appender = builder.build(directory.unwrap_or_else(|| ".".as_ref()))?;
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let filter = env_filter(if options.verbose { "info,rusty_router=debug" } else { "info" });
+            layers.push(fmt_layer(options.format, writer, false, filter));
+            Some(guard)
+        }
+        None => None,
+    };
+
+    #[cfg(feature = "otel")]
+    let telemetry = match &options.otlp_endpoint {
+        Some(endpoint) => {
+            let (layer, guard) = telemetry::layer(endpoint)?;
+            layers.push(layer);
+            Some(guard)
+        }
+        None => None,
+    };
+
+    if !layers.is_empty() {
+        tracing_subscriber::registry().with(layers).try_init()?;
+    }
+    Ok(LogGuard {
+        _file: file_guard,
+        #[cfg(feature = "otel")]
+        _telemetry: telemetry,
+    })
+}
@@ -1,135 +1,1781 @@
-﻿use clap::Parser;
-use rusty_router::client::ProofClient;
-use rusty_router::converter::ProofConverter;
-use rusty_router::substrate::SubstrateClient;
-
-use std::path::PathBuf;
-use tempfile::NamedTempFile;
-use tracing::{debug, info};
+﻿use clap::parser::ValueSource;
+use clap::{ArgGroup, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use rusty_router::abi::PubsAbi;
+use rusty_router::aggregation::AggregationReceipt;
+use rusty_router::backend::ProofInputs;
+use rusty_router::batch::{load_requests_file, print_summary, run_batch, BatchOptions, BatchStatus};
+use rusty_router::browser::BrowserOptions;
+use rusty_router::cache::{parse_age, ArtifactCache};
+use rusty_router::calldata::Calldata;
+use rusty_router::capabilities::Capabilities;
+use rusty_router::client::{parse_byte_size, parse_request_input, ProofClient, RequestFilter};
+use rusty_router::config::{Config, KeySource, Network, Profile};
+use rusty_router::convert_dir::{convert_dir, ConvertDirOptions};
+use rusty_router::credentials::resolve_mnemonic;
+use rusty_router::converter::{compress, ConvertedProof, OutputFormat, ProofConverter, ProofType};
+use rusty_router::extract::{ExtractionStrategy, RequestSummary};
+use rusty_router::failure::{FailureClass, ResultExt};
+use rusty_router::history::{History, HistoryStatus};
+use rusty_router::inspect::{inspect, Severity};
+use rusty_router::ipfs::IpfsClient;
+use rusty_router::keys::KeyInfo;
+use rusty_router::logging::{self, LogFormat, LogOptions, LogRotation};
+use rusty_router::network::{HttpPolicy, RpcPolicy, TlsOptions};
+use rusty_router::output::{set_quiet, write_atomic};
+use rusty_router::plonky2::{Plonky2Hash, Plonky2Params};
+use rusty_router::policy::VkAllowlist;
+use rusty_router::queue::{QueueOptions, RetryPolicy};
+use rusty_router::receipt::{self, SubmissionReceipt};
+use rusty_router::relayer::{RelayerClient, DEFAULT_RELAYER_URL};
+use rusty_router::remark::{reassemble, ChunkManifest};
+use rusty_router::router::{Router, Submission, Submitter};
+use rusty_router::status;
+use rusty_router::scheduler::{Scheduler, TaskSpec};
+use rusty_router::secret::SecretString;
+use rusty_router::signing::{parse_signature, SignatureScheme, UnsignedTx};
+use rusty_router::statement::Statement;
+use rusty_router::stats::{print_totals, write_stats, StatsRecorder};
+use rusty_router::substrate::{parse_rate, vk_hash, Era, RemarkCall, VkMode, DEFAULT_DUPLICATE_CHECK_BLOCKS, EventFilter, FeeBump, Multisig, ss58_address, SubstrateClient, TxEvent, TxOptions, WATCHED_PALLETS};
+use subxt::utils::AccountId32;
+use rusty_router::warnings::{Warning, WarningCode};
+use rusty_router::watch::{watch, WatchOptions};
+use rusty_router::webhook::WebhookClient;
+
+use reqwest::header::HeaderName;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
 use dotenv::dotenv;
+use glob::Pattern;
+
+/// How proofs reach zkVerify
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Backend {
+    /// Sign and submit the extrinsic with our own account
+    Direct,
+    /// Hand the proof to the hosted zkVerify relayer
+    Relayer,
+}
+
+/// Export format of the `history` and `list` commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum HistoryFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// Remove cached metadata and artifacts not used for some time
+    Clean {
+        /// Age of the entries to remove, e.g. `30d`, `12h` or `90m`; `0` empties the cache
+        #[arg(long, value_parser = parse_age)]
+        older_than: Duration,
+    },
+}
+
+#[derive(Subcommand)]
+enum VkCommand {
+    /// Print the hash zkVerify registers the VK under, computed locally
+    Hash {
+        #[command(flatten)]
+        vk: VkArg,
+    },
+    /// Check whether the VK is registered in the SP1 settlement pallet
+    Check {
+        #[command(flatten)]
+        vk: VkArg,
+    },
+    /// Register the VK in the SP1 settlement pallet with the signing account
+    Register {
+        #[command(flatten)]
+        vk: VkArg,
+    },
+}
+
+#[derive(Subcommand)]
+enum DomainCommand {
+    /// Print a domain's state, next aggregation ID, queued statements and complete aggregations
+    Status {
+        domain_id: u32,
+
+        /// Print the state as JSON
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Publish a complete aggregation of the domain with the signing account
+    Aggregate {
+        domain_id: u32,
+
+        /// Aggregation to publish; the oldest complete one by default
+        #[arg(long)]
+        aggregation_id: Option<u64>,
+    },
+    /// Wait until a statement is in a published aggregation of the domain and save its receipt
+    Wait {
+        domain_id: u32,
+
+        #[command(flatten)]
+        statement: StatementArg,
+
+        /// Aggregation the statement was queued into; any aggregation of the domain otherwise
+        #[arg(long)]
+        aggregation_id: Option<u64>,
+
+        /// Where to write the aggregation receipt and Merkle path
+        #[arg(long, default_value = "aggregation.json")]
+        output: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeysCommand {
+    /// Generate a new sr25519 account and print its mnemonic, address and public key
+    Generate {
+        /// Words in the mnemonic: 12, 15, 18, 21 or 24
+        #[arg(long, default_value_t = 24)]
+        words: usize,
+
+        /// Print the account as JSON
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Print the address and public key of an existing account
+    Inspect {
+        /// Mnemonic, 0x hex seed or secret URI such as `<mnemonic>//derivation`; read from
+        /// stdin when omitted, which keeps it out of the shell history
+        secret: Option<String>,
+
+        /// Print the account as JSON
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum MultisigCommand {
+    /// Print the multisig's address and the hash of the call submitting a proof, for the other
+    /// signatories to approve
+    CallHash {
+        /// Converted proof the call submits
+        proof: PathBuf,
+    },
+    /// Approve a call of the multisig by its hash with the signing account
+    Approve {
+        /// Call hash, as printed by `multisig call-hash`
+        call_hash: String,
+    },
+    /// Print the approvals of a call waiting for them
+    Status {
+        /// Call hash, as printed by `multisig call-hash`
+        call_hash: String,
+
+        /// Print the approvals as JSON
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+}
+
+/// Statement hash given directly or computed from a converted proof
+#[derive(clap::Args)]
+#[group(required = true, multiple = false)]
+struct StatementArg {
+    /// Statement hash, as printed by `hash`
+    #[arg(long)]
+    statement: Option<String>,
+
+    /// Converted proof to compute the statement of
+    #[arg(long)]
+    proof: Option<PathBuf>,
+}
+
+/// VK given directly or read from a converted proof
+#[derive(clap::Args)]
+#[group(required = true, multiple = false)]
+struct VkArg {
+    /// Hex VK, e.g. the `vk` of a converted proof
+    #[arg(long)]
+    vk: Option<String>,
+
+    /// Converted proof to take the VK from
+    #[arg(long)]
+    proof: Option<PathBuf>,
+}
+
+impl VkArg {
+    async fn bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let vk = match (&self.vk, &self.proof) {
+            (Some(vk), _) => vk.clone(),
+            (None, Some(proof)) => ProofConverter::new().load_proof(proof).await.classify(FailureClass::BadInput)?.vk,
+            (None, None) => unreachable!("clap requires --vk or --proof"),
+        };
+        rusty_router::substrate::decode_vk(&vk).classify(FailureClass::BadInput)
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the proof systems, chains, pallets, formats and features of this build as JSON
+    Capabilities,
+    /// Print the address, balance and nonce of the signing account, to check the key before spending fees
+    Account,
+    /// Print settlement and aggregation pallet events from finalized blocks as they arrive
+    Events {
+        /// Only events of extrinsics signed by this SS58 address
+        #[arg(long)]
+        account: Option<String>,
+
+        /// Only events carrying this statement hash
+        #[arg(long)]
+        statement: Option<String>,
+
+        /// Print one JSON object per line instead of text
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Check a converted proof file offline: structure, component lengths and VK hash
+    Inspect {
+        /// Converted proof to check
+        #[arg(default_value = "proof.json")]
+        proof: PathBuf,
+
+        /// Print the report as JSON
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Print the statement hash zkVerify will record for a converted proof, computed locally;
+    /// it is also the proof's leaf in its aggregation
+    Hash {
+        /// Converted proof to hash
+        #[arg(default_value = "proof.json")]
+        proof: PathBuf,
+
+        /// Also print the hashes the statement is computed from, as JSON
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Convert every SP1 artifact in a directory, recursively, writing one converted proof per
+    /// artifact and a manifest.json of the results; --jobs, --format, --compress, --force and --offline apply
+    ConvertDir {
+        /// Directory holding the artifacts
+        dir: PathBuf,
+
+        /// Directory converted proofs and the manifest are written to, in the layout of `dir`
+        #[arg(long, default_value = "converted")]
+        out_dir: PathBuf,
+
+        /// Only convert files whose path relative to `dir` matches this glob
+        #[arg(long, default_value = "*.bin")]
+        glob: Pattern,
+    },
+    /// Look up a submitted extrinsic: its block, outcome, events and the fee paid
+    Status {
+        /// Extrinsic hash printed at submission
+        #[arg(required_unless_present = "receipt", conflicts_with = "receipt")]
+        tx_hash: Option<String>,
+
+        /// JSON submission receipt with a `tx_hash` and, if known, its `block_hash`
+        #[arg(long)]
+        receipt: Option<PathBuf>,
+
+        /// Block the extrinsic is in; without it the most recent finalized blocks are searched
+        #[arg(long)]
+        block_hash: Option<String>,
+
+        /// Finalized blocks searched back from the head when the block is not known
+        #[arg(long, default_value_t = 300)]
+        search_depth: u32,
+
+        /// Print the report as JSON
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Rebuild a proof sent with --send-remark from chain data, following the chunk manifest of
+    /// a proof that was sent in chunks
+    Reassemble {
+        /// Hash of the remark holding the proof, or of its chunk manifest
+        tx_hash: String,
+
+        /// Block the remark is in; without it the most recent finalized blocks are searched
+        #[arg(long)]
+        block_hash: Option<String>,
+
+        /// Finalized blocks searched back from the head when the block is not known
+        #[arg(long, default_value_t = 300)]
+        search_depth: u32,
+
+        /// Where to write the rebuilt proof file
+        #[arg(long, default_value = "reassembled.json")]
+        output: PathBuf,
+    },
+    /// Manage the artifact cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Generate signing accounts and inspect existing ones, for the network's address prefix
+    Keys {
+        #[command(subcommand)]
+        command: KeysCommand,
+    },
+    /// Hash, check or register a VK independently of proof submission
+    Vk {
+        #[command(subcommand)]
+        command: VkCommand,
+    },
+    /// Inspect aggregation domains, publish their aggregations and wait for a statement's receipt
+    Domain {
+        #[command(subcommand)]
+        command: DomainCommand,
+    },
+    /// Approve and inspect the calls of the --multisig-signatories multisig
+    Multisig {
+        #[command(subcommand)]
+        command: MultisigCommand,
+    },
+    /// Poll for newly fulfilled requests of a program or requester and route each one to zkVerify
+    Watch {
+        /// Watch requests for this program VK
+        #[arg(long, required_unless_present = "requester", conflicts_with = "requester")]
+        program_vk: Option<String>,
+
+        /// Watch requests sent by this requester address
+        #[arg(long)]
+        requester: Option<String>,
+
+        /// Time between explorer polls, e.g. `30s` or `2m`; a bare number is seconds
+        #[arg(long, value_parser = parse_age, default_value = "60s")]
+        poll_interval: Duration,
+
+        /// Directory converted proofs are written to, one `<request_id>.json` per job
+        #[arg(long, default_value = "routed")]
+        output_dir: PathBuf,
+
+        /// Also route requests already listed when the watch starts
+        #[arg(long, default_value_t = false)]
+        include_existing: bool,
+
+        /// POST a JSON notification here whenever a job finishes or fails
+        #[arg(long)]
+        webhook_url: Option<String>,
+
+        /// Sign webhook bodies with HMAC-SHA256 using this secret
+        #[arg(long, env = "WEBHOOK_SECRET", hide_env_values = true, requires = "webhook_url")]
+        webhook_secret: Option<String>,
+
+        /// Delivery retries after a failed webhook POST
+        #[arg(long, default_value_t = 3)]
+        webhook_retries: u32,
+
+        /// Periodic task as `<task>:<seconds>`, e.g. `balance-check:600` or `canary:3600` (repeatable)
+        #[arg(long = "task")]
+        tasks: Vec<TaskSpec>,
+
+        /// Known-good converted proof submitted by the canary task
+        #[arg(long)]
+        canary_proof: Option<PathBuf>,
+
+        /// Jobs converted and submitted at the same time; submissions still go one at a time
+        #[arg(long, default_value_t = 1)]
+        workers: usize,
+
+        /// Attempts at fetching and converting a job's proof before giving up
+        #[arg(long, default_value_t = 3)]
+        convert_attempts: u32,
+
+        /// Attempts at submitting a job's proof before giving up
+        #[arg(long, default_value_t = 1)]
+        submit_attempts: u32,
+
+        /// Seconds before the first retry of a failed stage, doubling after each attempt
+        #[arg(long, default_value_t = 10)]
+        retry_backoff: u64,
+
+        /// Write jobs that ran out of attempts here as `<request_id>.json`
+        #[arg(long)]
+        dead_letter_dir: Option<PathBuf>,
+    },
+    /// Build a proof submission for an externally held key and write its signing payload
+    PrepareTx {
+        /// SS58 address of the account that will sign
+        #[arg(long)]
+        signer: String,
+
+        /// Converted proof to submit
+        #[arg(long, default_value = "proof.json")]
+        proof: PathBuf,
+
+        /// Where to write the prepared transaction
+        #[arg(long, default_value = "unsigned_tx.json")]
+        tx_output: PathBuf,
+
+        /// Also show the transaction as a Polkadot Vault QR code
+        #[cfg(feature = "vault")]
+        #[arg(long, default_value_t = false)]
+        qr: bool,
+
+        /// Key type of the signing account, for the QR code
+        #[cfg(feature = "vault")]
+        #[arg(long, default_value = "sr25519")]
+        signature_scheme: SignatureScheme,
+    },
+    /// Query or export the job history recorded in --history-db
+    History {
+        /// Only show entries with this status: converted, submitted, in_block, finalized or failed
+        #[arg(long)]
+        status: Option<HistoryStatus>,
+
+        /// Show at most this many entries, most recently updated first
+        #[arg(long)]
+        limit: Option<u32>,
+
+        #[arg(long, value_enum, default_value_t = HistoryFormat::Table)]
+        format: HistoryFormat,
+
+        /// First settle submissions left in flight by checking the chain (or the relayer) with --backend
+        #[arg(long, default_value_t = false)]
+        reconcile: bool,
+    },
+    /// Print the recent proof requests of a program or requester with their status and creation
+    /// time, newest first; `--format csv` output can be given to --requests-file
+    List {
+        /// List requests for this program VK
+        #[arg(long, required_unless_present = "requester", conflicts_with = "requester")]
+        program_vk: Option<String>,
+
+        /// List requests sent by this requester address
+        #[arg(long)]
+        requester: Option<String>,
+
+        /// Only list requests with this status, e.g. `fulfilled`
+        #[arg(long)]
+        status: Option<String>,
+
+        /// List at most this many requests
+        #[arg(long)]
+        limit: Option<usize>,
+
+        #[arg(long, value_enum, default_value_t = HistoryFormat::Table)]
+        format: HistoryFormat,
+    },
+    /// Attach a signature made elsewhere to a prepared transaction and broadcast it
+    SubmitSigned {
+        /// Prepared transaction written by prepare-tx
+        #[arg(long, default_value = "unsigned_tx.json")]
+        tx: PathBuf,
+
+        /// Hex signature over the prepared signing payload
+        #[cfg_attr(feature = "vault", arg(long, required_unless_present = "signature_qr"))]
+        #[cfg_attr(not(feature = "vault"), arg(long, required = true))]
+        signature: Option<String>,
+
+        /// Photo or screenshot of the signature QR code shown by Polkadot Vault
+        #[cfg(feature = "vault")]
+        #[arg(long, conflicts_with = "signature")]
+        signature_qr: Option<PathBuf>,
+
+        /// Key type of a raw signature: sr25519, ed25519 or ecdsa
+        #[arg(long, default_value = "sr25519")]
+        signature_scheme: SignatureScheme,
+    },
+}
 
 #[derive(Parser)]
 #[command(name = "rusty_router")]
 #[command(about = "Convert Succinct proof requests to zkVerify format")]
+#[command(group(ArgGroup::new("artifact_source").args(["request_id", "artifact_path"])))]
 struct Args {
-    /// The Succinct proof request ID
+    /// Run a specific command instead of the default convert/submit pipeline
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// TOML file of named profiles (default: ~/.config/rusty_router/config.toml, if present)
+    #[arg(long, env = "RUSTY_ROUTER_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Config profile to use instead of the file's `default_profile`; explicit flags still win
+    #[arg(long, env = "RUSTY_ROUTER_PROFILE")]
+    profile: Option<String>,
+
+    /// zkVerify network preset: volta (alias testnet), mainnet or local. Sets the RPC endpoint,
+    /// explorer links and Succinct API base unless given explicitly (default: the preset of --ws-url)
+    #[arg(long, env = "RUSTY_ROUTER_NETWORK")]
+    network: Option<Network>,
+
+    /// Read the signing mnemonic only from this source: env[:NAME], file:PATH, keystore, keyring
+    /// or prompt. By default --mnemonic-file, ZKV_MNEMONIC, the keystore, the OS keyring and a
+    /// terminal prompt are tried in that order; a profile's `key` also picks the source
+    #[arg(long, env = "RUSTY_ROUTER_KEY_SOURCE")]
+    key_source: Option<KeySource>,
+
+    /// File holding the signing mnemonic, tried before ZKV_MNEMONIC
+    #[arg(long)]
+    mnemonic_file: Option<PathBuf>,
+
+    /// The Succinct proof request ID, or its explorer URL (https://explorer.succinct.xyz/request/<id>)
     #[arg(long)]
     request_id: Option<String>,
 
-    /// Path where to save the JSON file
+    /// SP1 proof file (SP1ProofWithPublicValues), or bb or snarkjs proof with --proof-type, to convert instead
+    /// of fetching one for --request-id
+    #[arg(long, conflicts_with_all = ["request_id", "requests_file"])]
+    artifact_path: Option<PathBuf>,
+
+    /// VK of --artifact-path's program (hex); read from the artifact if not given
+    #[arg(long, requires = "artifact_path")]
+    vk: Option<String>,
+
+    /// Convert --artifact-path (or convert-dir's artifacts) on the local CPU without network
+    /// access, ignoring SP1 prover settings such as SP1_PROVER
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["request_id", "requests_file", "submit_to_zkverify", "send_remark"]
+    )]
+    offline: bool,
+
+    /// Convert with the explorer's (or --vk's) VK even when the SP1 artifact carries a different
+    /// one, which zkVerify would reject the proof with; a vk-mismatch warning is recorded instead
+    #[arg(long, default_value_t = false)]
+    force: bool,
+
+    /// Proof system of --artifact-path: sp1, ultraplonk for a Noir proof written by `bb prove`,
+    /// groth16 or fflonk for a snarkjs `proof.json`, or plonky2 for a serialized `ProofWithPublicInputs`
+    #[arg(long, default_value = "sp1")]
+    proof_type: ProofType,
+
+    /// VK file of --artifact-path's circuit: from `bb write_vk` for ultraplonk, snarkjs
+    /// `verification_key.json` for groth16 and fflonk, serialized verifier data for plonky2
+    #[arg(long, requires = "artifact_path", conflicts_with = "vk")]
+    vk_file: Option<PathBuf>,
+
+    /// snarkjs `public.json` of --artifact-path, for --proof-type groth16 and fflonk
+    #[arg(long, requires = "artifact_path")]
+    public_inputs: Option<PathBuf>,
+
+    /// Serialized `CommonCircuitData`, for --proof-type plonky2 when --vk-file holds only the
+    /// `VerifierOnlyCircuitData`
+    #[arg(long, requires = "vk_file")]
+    common_data: Option<PathBuf>,
+
+    /// Hasher of the Plonky2 circuit: poseidon or keccak
+    #[arg(long, default_value = "poseidon")]
+    plonky2_hash: Plonky2Hash,
+
+    /// --artifact-path is a `CompressedProofWithPublicInputs`, for --proof-type plonky2
+    #[arg(long, default_value_t = false, requires = "artifact_path")]
+    plonky2_compressed: bool,
+
+    /// Expected SHA-256 of the artifact (hex); conversion is refused if the download or --artifact-path differs
+    #[arg(long, requires = "artifact_source")]
+    artifact_sha256: Option<String>,
+
+    /// Convert (and with --submit-to-zkverify, submit) every request listed in this file:
+    /// one ID per line, or CSV/JSON with per-request `domain_id` and `output`
+    #[arg(long, conflicts_with = "request_id")]
+    requests_file: Option<PathBuf>,
+
+    /// Explorer pages loaded at the same time for --requests-file and watch
+    #[arg(long, default_value_t = 4)]
+    fetch_concurrency: usize,
+
+    /// Write everything read from the explorer for --request-id (artifact URL, VK and the other
+    /// fields shown) to this JSON file
+    #[arg(long, requires = "request_id")]
+    save_metadata: Option<PathBuf>,
+
+    /// If --request-id is still being proven, wait until its artifact is available instead of failing
+    #[arg(long, default_value_t = false, requires = "request_id")]
+    wait_for_fulfillment: bool,
+
+    /// How often --wait-for-fulfillment checks the request, e.g. `30s` or `5m`; a bare number is seconds
+    #[arg(long, value_parser = parse_age, default_value = "30s", requires = "wait_for_fulfillment")]
+    poll_interval: Duration,
+
+    /// How long --wait-for-fulfillment waits before giving up, e.g. `1h`
+    #[arg(long, value_parser = parse_age, default_value = "1h", requires = "wait_for_fulfillment")]
+    fulfillment_timeout: Duration,
+
+    /// Directory --requests-file conversions are written to, one `<request_id>.json` each
+    #[arg(long, default_value = "routed")]
+    batch_output_dir: PathBuf,
+
+    /// Proofs of a --requests-file converted at the same time; other requests keep
+    /// downloading and submitting meanwhile
+    #[arg(long, default_value_t = 1)]
+    jobs: usize,
+
+    /// Path where to save the converted proof (default: proof.json, or proof.cbor, ... with --format)
     #[arg(long, default_value = "proof.json")]
     output: PathBuf,
 
+    /// Write the artifact size, proof variant, SP1 version, public values length, converted proof
+    /// size and conversion time of each conversion here: CSV for a `.csv` path, JSON otherwise
+    #[arg(long)]
+    stats: Option<PathBuf>,
+
+    /// Format of --output: json, cbor or msgpack (raw bytes instead of hex), or scale for the
+    /// pallet's SCALE-encoded `submit_proof` arguments, which cannot be submitted
+    #[arg(long, default_value = "json", conflicts_with = "requests_file")]
+    format: OutputFormat,
+
+    /// Compress converted proofs with zstd, adding `.zst` to --output (`proof.json.zst`) and to
+    /// --requests-file and watch outputs; compressed proofs are read back transparently
+    #[arg(long, default_value_t = false)]
+    compress: bool,
+
     /// Override explorer API base URL
     #[arg(long, default_value = "https://explorer.succinct.xyz")]
     api_base: String,
 
+    /// Ways of finding the artifact URL and VK, tried in order: json-api, next-data,
+    /// script-tags and dom
+    #[arg(long, value_delimiter = ',', default_value = "json-api,next-data,script-tags,dom")]
+    extraction_strategies: Vec<ExtractionStrategy>,
+
+    /// Explorer API URL returning a request's metadata as JSON, with `{request_id}` standing for the
+    /// request; the json-api strategy is skipped without it
+    #[arg(long)]
+    explorer_api_url: Option<String>,
+
+    /// Key for authenticated or rate-limited explorer API endpoints, sent on --explorer-api-url
+    /// requests as a bearer token
+    #[arg(long, env = "SUCCINCT_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+
+    /// Header to send --api-key in as is, e.g. `x-api-key`, instead of `Authorization: Bearer`
+    #[arg(long, requires = "api_key")]
+    api_key_header: Option<HeaderName>,
+
+    /// Chrome or Chromium binary rendering explorer pages (default: `CHROME`, then the usual
+    /// binary names on the PATH)
+    #[arg(long, env = "RUSTY_ROUTER_BROWSER")]
+    browser_path: Option<PathBuf>,
+
+    /// CSS selector to wait for before reading a rendered explorer page
+    #[arg(long)]
+    wait_for_selector: Option<String>,
+
+    /// Seconds allowed for rendering an explorer page, waiting for --wait-for-selector included
+    #[arg(long, default_value_t = 30)]
+    render_timeout: u64,
+
+    /// Explorer pages the headless browser loads at the same time; it is kept running and its
+    /// pages reused across requests of --requests-file and watch
+    #[arg(long, default_value_t = 4)]
+    browser_pool_size: usize,
+
     /// Enable verbose logging
     #[arg(long, default_value_t = false)]
     verbose: bool,
 
+    /// Print only requested output (JSON, tables, payloads) and errors, without status lines or progress bars
+    #[arg(long, default_value_t = false, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Format of console and --log-file logs: text, or json with one object per event
+    #[arg(long, env = "RUSTY_ROUTER_LOG_FORMAT", default_value = "text")]
+    log_format: LogFormat,
+
+    /// Also write logs, including status lines, to this file
+    #[arg(long, env = "RUSTY_ROUTER_LOG_FILE")]
+    log_file: Option<PathBuf>,
+
+    /// When --log-file starts a new file: never, hourly or daily (rotated files get a date suffix)
+    #[arg(long, default_value = "daily", requires = "log_file")]
+    log_rotation: LogRotation,
+
+    /// Rotated log files kept besides the current one (default: all)
+    #[arg(long, requires = "log_file")]
+    log_max_files: Option<usize>,
+
+    /// Export pipeline spans to this OTLP/HTTP traces endpoint
+    #[cfg(feature = "otel")]
+    #[arg(long, env = "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT")]
+    otlp_endpoint: Option<String>,
+
     /// WebSocket URL of the Substrate node
     #[arg(long, default_value = "wss://zkverify-volta-rpc.zkverify.io")]
     ws_url: String,
 
+    /// Talk to zkVerify through a light client synced from its boot nodes instead of trusting --ws-url
+    #[cfg(feature = "light-client")]
+    #[arg(long, default_value_t = false, conflicts_with = "aggregation_output")]
+    light_client: bool,
+
+    /// Chain spec to start the light client from (default: fetched from --ws-url)
+    #[cfg(feature = "light-client")]
+    #[arg(long, requires = "light_client")]
+    chain_spec: Option<PathBuf>,
+
 
 
     /// Send the proof as a system.remark transaction
     #[arg(long, default_value_t = false)]
     send_remark: bool,
 
+    /// Call --send-remark uses: remark_with_event, whose `Remarked` event confirms the data
+    /// landed, or plain remark
+    #[arg(long, default_value = "remark_with_event", requires = "send_remark")]
+    remark_call: RemarkCall,
+
     /// Submit existing proof to zkVerify network for validation
     #[arg(long, default_value_t = false)]
     submit_to_zkverify: bool,
 
+    /// Submission backend used by --submit-to-zkverify
+    #[arg(long, value_enum, default_value_t = Backend::Direct)]
+    backend: Backend,
+
+    /// Base URL of the zkVerify relayer API
+    #[arg(long, default_value = DEFAULT_RELAYER_URL)]
+    relayer_url: String,
+
+    /// API key for the zkVerify relayer
+    #[arg(long, env = "RELAYER_API_KEY", hide_env_values = true)]
+    relayer_api_key: Option<String>,
+
+    /// Seconds between relayer job status polls
+    #[arg(long, default_value_t = 5)]
+    relayer_poll_interval: u64,
+
     /// Extract and save detailed proof information without submitting
     #[arg(long, default_value_t = false)]
     get_proof: bool,
 
+    /// JSON schema of the ABI-encoded public values, `[{"name": ..., "type": ...}]` with types
+    /// bool, u8 to u256, bytes32 and address, to decode them by name in --get-proof details
+    #[arg(long, requires = "get_proof")]
+    pubs_abi: Option<PathBuf>,
+
+    /// Also write the shrunk proof, raw public values and VK bytes as separate files in this directory
+    #[arg(long)]
+    emit_intermediate: Option<PathBuf>,
+
+    /// Directory downloaded artifacts and explorer metadata are cached in (default: ~/.cache/rusty_router)
+    #[arg(long, env = "RUSTY_ROUTER_CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// Always fetch metadata and download artifacts, without reading or filling the cache
+    #[arg(long, default_value_t = false)]
+    no_cache: bool,
+
+    /// Proxy for the explorer, artifact storage and relayer, e.g. http://proxy.corp:3128 (default: HTTPS_PROXY/HTTP_PROXY, except NO_PROXY hosts)
+    #[arg(long)]
+    proxy: Option<String>,
+
+    /// PEM bundle of CA certificates trusted in addition to the usual roots, for HTTPS and the node connection
+    #[arg(long, env = "RUSTY_ROUTER_CA_BUNDLE")]
+    ca_bundle: Option<PathBuf>,
+
+    /// PEM client certificate presented to HTTPS servers and the node, for mutual TLS
+    #[arg(long)]
+    client_cert: Option<PathBuf>,
+
+    /// PEM private key of --client-cert, if it is not in the same file
+    #[arg(long, requires = "client_cert")]
+    client_key: Option<PathBuf>,
+
+    /// Seconds to wait for an HTTP connection before failing (0: no timeout)
+    #[arg(long, default_value_t = 10)]
+    http_connect_timeout: u64,
+
+    /// Seconds an HTTP server may stay silent, before answering or in the middle of a download (0: no timeout)
+    #[arg(long, default_value_t = 30)]
+    http_read_timeout: u64,
+
+    /// Seconds a whole HTTP request may take, including its body; unlimited by default, as artifacts can be large
+    #[arg(long)]
+    http_timeout: Option<u64>,
+
+    /// Retries of explorer, artifact storage and relayer requests after connection errors, timeouts, 429 and 5xx
+    #[arg(long, default_value_t = 3)]
+    http_retries: u32,
+
+    /// Seconds before the first HTTP retry, doubling after each one (with jitter, up to a minute)
+    #[arg(long, default_value_t = 1)]
+    http_backoff: u64,
+
+    /// Refuse to download artifacts larger than this, e.g. 2G or 500M
+    #[arg(long, value_parser = parse_byte_size)]
+    max_artifact_size: Option<u64>,
+
+    /// Cap artifact downloads at this many bytes per second, e.g. 10M
+    #[arg(long, value_parser = parse_byte_size)]
+    max_download_rate: Option<u64>,
+
+    /// Download S3 artifacts in parallel ranged parts, with the AWS SDK when AWS credentials are configured
+    #[cfg(feature = "s3")]
+    #[arg(long, default_value_t = false)]
+    s3: bool,
+
+    /// Size of each part of an S3 download, e.g. 16M
+    #[cfg(feature = "s3")]
+    #[arg(long, value_parser = parse_byte_size, default_value = "16M", requires = "s3")]
+    s3_part_size: u64,
+
+    /// Parts of an S3 download fetched at the same time
+    #[cfg(feature = "s3")]
+    #[arg(long, default_value_t = 8, requires = "s3")]
+    s3_concurrency: usize,
+
+    /// Region of buckets whose URL names none (default: from the AWS configuration)
+    #[cfg(feature = "s3")]
+    #[arg(long, requires = "s3")]
+    s3_region: Option<String>,
+
+    /// Download over the artifact URL even when AWS credentials are configured
+    #[cfg(feature = "s3")]
+    #[arg(long, default_value_t = false, requires = "s3")]
+    s3_no_sign_request: bool,
+
+    /// Pin each artifact and converted proof to the IPFS node with this HTTP API, e.g. http://127.0.0.1:5001
+    #[arg(long, env = "IPFS_API_URL")]
+    ipfs_api_url: Option<String>,
+
+    /// Bearer token for the IPFS API
+    #[arg(long, env = "IPFS_API_TOKEN", hide_env_values = true, requires = "ipfs_api_url")]
+    ipfs_api_token: Option<String>,
+
+    /// Skip fetching and conversion, submitting the existing conversion of --request-id in --output
+    #[arg(long, default_value_t = false, requires = "request_id")]
+    resubmit_only: bool,
+
     /// List available pallets (for debugging)
     #[arg(long, default_value_t = false)]
     list_pallets: bool,
+
+    /// Domain to queue the proof into for aggregation
+    #[arg(long)]
+    domain_id: Option<u32>,
+
+    /// Wait for the proof's aggregation receipt and write its Merkle path to this file
+    #[arg(long, requires = "domain_id")]
+    aggregation_output: Option<PathBuf>,
+
+    /// Seconds to wait for the aggregation receipt
+    #[arg(long, default_value_t = 3600)]
+    aggregation_timeout: u64,
+
+    /// Write ABI-encoded `verifyProofAggregation` calldata for the aggregation receipt to this file
+    #[arg(long)]
+    calldata_output: Option<PathBuf>,
+
+    /// Build calldata from an existing aggregation receipt file instead of this run's receipt
+    #[arg(long, requires = "calldata_output")]
+    calldata_from: Option<PathBuf>,
+
+    /// Ethereum RPC endpoint used to check aggregation attestations
+    #[cfg(feature = "eth")]
+    #[arg(long, env = "ETH_RPC_URL")]
+    eth_rpc_url: Option<String>,
+
+    /// Address of the zkVerify attestation contract on Ethereum
+    #[cfg(feature = "eth")]
+    #[arg(long)]
+    eth_contract: Option<String>,
+
+    /// Check an existing aggregation receipt file against the Ethereum contract
+    #[cfg(feature = "eth")]
+    #[arg(long, requires_all = ["eth_rpc_url", "eth_contract"])]
+    eth_verify: Option<PathBuf>,
+
+    /// Tip paid to the block author with each extrinsic, in planck
+    #[arg(long, default_value_t = 0)]
+    tip: u128,
+
+    /// Extrinsic mortality: `immortal` or a number of blocks
+    #[arg(long)]
+    era: Option<Era>,
+
+    /// Re-sign with a higher tip if not included within this many blocks (disabled by default)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    fee_bump_after_blocks: Option<u32>,
+
+    /// Tip added on each fee-bump attempt, in planck
+    #[arg(long, default_value_t = 1_000_000_000_000_000)]
+    fee_bump_increment: u128,
+
+    /// Maximum number of fee-bump attempts after the first submission
+    #[arg(long, default_value_t = 3)]
+    fee_bump_max_attempts: u32,
+
+    /// Times a submission the transaction pool rejects for a nonce already in use ("priority too
+    /// low") is signed again with a fresh nonce, with a doubling backoff
+    #[arg(long, default_value_t = 3)]
+    pool_retries: u32,
+
+    /// After submitting, wait until the account's finalized nonce includes the submitted transactions
+    #[arg(long, default_value_t = false)]
+    wait_nonce_settled: bool,
+
+    /// Seconds to wait for the nonce to settle
+    #[arg(long, default_value_t = 300)]
+    nonce_settle_timeout: u64,
+
+    /// Warn (W003) when the signer's free balance is below this amount, in planck
+    #[arg(long)]
+    balance_warning_threshold: Option<u128>,
+
+    /// Submit at most this many transactions per block
+    #[arg(long)]
+    max_submissions_per_block: Option<u32>,
+
+    /// Submit at most this many transactions in any rolling hour
+    #[arg(long)]
+    max_submissions_per_hour: Option<u32>,
+
+    /// Submit at most this many transactions a second, e.g. 0.5 for one every two seconds
+    #[arg(long, value_parser = parse_rate)]
+    max_submissions_per_second: Option<f64>,
+
+    /// Spread the --max-submissions-per-block quota evenly over the observed block time
+    #[arg(long, default_value_t = false, requires = "max_submissions_per_block")]
+    pace_submissions: bool,
+
+    /// SQLite database recording every processed request; already routed requests are not submitted again
+    #[arg(long, env = "RUSTY_ROUTER_HISTORY_DB")]
+    history_db: Option<PathBuf>,
+
+    /// Submit even if --history-db shows the request was already routed
+    #[arg(long, default_value_t = false)]
+    ignore_history: bool,
+
+    /// Finalized blocks searched for an earlier verification of the proof's statement before
+    /// submitting it directly; 0 submits without checking
+    #[arg(long, default_value_t = DEFAULT_DUPLICATE_CHECK_BLOCKS)]
+    duplicate_check_blocks: u32,
+
+    /// How direct submissions refer to the VK: inline (the whole VK), hash (of a VK registered
+    /// beforehand) or register (by hash, registering the VK in the same transaction if needed)
+    #[arg(long, default_value = "inline")]
+    vk_mode: VkMode,
+
+    /// Submit proofs from the multisig of these signatories (SS58 addresses, comma-separated,
+    /// the signing account included), approving as one of them
+    #[arg(long, value_delimiter = ',', requires = "multisig_threshold")]
+    multisig_signatories: Vec<String>,
+
+    /// Approvals the --multisig-signatories multisig needs to execute a call
+    #[arg(long, requires = "multisig_signatories")]
+    multisig_threshold: Option<u16>,
+
+    /// Submit proofs on behalf of this account (SS58 address) through the proxy pallet; the
+    /// signing account must be one of its proxies
+    #[arg(long)]
+    proxy_for: Option<String>,
+
+    /// File of program VKs (one per line) this environment may submit; others are rejected
+    #[arg(long, env = "VK_ALLOWLIST")]
+    vk_allowlist: Option<PathBuf>,
 }
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::from(FailureClass::of(&e).exit_code())
+        }
+    }
+}
+
+async fn run() -> anyhow::Result<()> {
     // Load environment variables from .env file
     dotenv().ok();
 
-    let args = Args::parse();
-
-    if args.verbose {
-        tracing_subscriber::fmt()
-            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-            .with_target(false)
-            .compact()
-            .init();
-        debug!("Verbose logging enabled");
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches)?;
+    // Aggregation receipts need the `aggregate_statementPath` RPC, which a light client cannot answer
+    #[cfg(feature = "light-client")]
+    if args.light_client && matches!(args.command, Some(Command::Domain { command: DomainCommand::Wait { .. } })) {
+        Args::command()
+            .error(clap::error::ErrorKind::ArgumentConflict, "--light-client cannot be used with `domain wait`")
+            .exit();
     }
 
+    set_quiet(args.quiet);
+    let _log_guard = logging::init(&LogOptions {
+        verbose: args.verbose,
+        format: args.log_format,
+        log_file: args.log_file.clone(),
+        rotation: args.log_rotation,
+        max_files: args.log_max_files,
+        #[cfg(feature = "otel")]
+        otlp_endpoint: args.otlp_endpoint.clone(),
+    })?;
+    debug!("Verbose logging enabled");
 
+    apply_config(&mut args, &matches).await.classify(FailureClass::BadInput)?;
+    // An explorer URL names the explorer too, overriding --api-base
+    if let Some(input) = &args.request_id {
+        let (request_id, api_base) = parse_request_input(input).classify(FailureClass::BadInput)?;
+        args.request_id = Some(request_id);
+        if let Some(api_base) = api_base {
+            args.api_base = api_base;
+        }
+    }
+    // The default --output is named after the format: proof.json, proof.cbor, ...
+    if matches.value_source("output") == Some(ValueSource::DefaultValue) {
+        args.output.set_extension(args.format.as_str());
+    }
+    if args.compress && args.output.extension().is_none_or(|extension| extension != "zst") {
+        args.output.as_mut_os_string().push(".zst");
+    }
+    let args = args;
 
-    // Handle proof conversion (original functionality) - only if request_id is provided
-    if let Some(request_id) = args.request_id {
-        println!("🌐 Loading explorer page for request: {}", request_id);
-        let client = ProofClient::new_with_options(&args.api_base, args.verbose);
-        let metadata = client.fetch_request_metadata(&request_id).await?;
+    let history = match &args.history_db {
+        Some(path) => Some(Arc::new(History::open(path)?)),
+        None => None,
+    };
+    let stats = args.stats.as_ref().map(|_| Arc::new(StatsRecorder::default()));
 
-        println!("📦 Downloading proof artifact...");
-        let artifact_data = client.download_artifact(&metadata.artifact_url).await?;
+    if let Some(command) = &args.command {
+        return match command {
+            Command::Capabilities => {
+                println!("{}", serde_json::to_string_pretty(&Capabilities::current())?);
+                Ok(())
+            }
+            Command::Account => {
+                let substrate_client = connect_substrate(&args).await?;
+                let balance = substrate_client.balance().await?;
+                let nonce = substrate_client.next_nonce().await?;
+                println!("Address:  {}", substrate_client.address());
+                println!("Free:     {}", format_balance(&args, balance.free));
+                println!("Reserved: {}", format_balance(&args, balance.reserved));
+                println!("Nonce:    {}", nonce);
 
-        // Create a temporary file to store the artifact
-        let temp_file = NamedTempFile::new()?;
-        let temp_file_path = temp_file.path().to_path_buf();
+                let low = balance.free == 0 || args.balance_warning_threshold.is_some_and(|threshold| balance.free < threshold);
+                if low && let Some(hint) = network(&args).and_then(|network| network.faucet_hint()) {
+                    status!("💧 {}", hint);
+                }
+                Ok(())
+            }
+            Command::Events { account, statement, json } => {
+                let filter = EventFilter {
+                    account: match account {
+                        Some(address) => Some(
+                            address
+                                .parse()
+                                .map_err(|e| FailureClass::BadInput.error(format!("Invalid account address {}: {:?}", address, e)))?,
+                        ),
+                        None => None,
+                    },
+                    statement: match statement {
+                        Some(statement) => Some(
+                            statement
+                                .parse()
+                                .map_err(|_| FailureClass::BadInput.error(format!("Invalid statement hash '{}'", statement)))?,
+                        ),
+                        None => None,
+                    },
+                };
+                let account_id = filter.account.clone().unwrap_or(AccountId32([0; 32]));
+                let substrate_client = SubstrateClient::new_without_signer_with_policy(&args.ws_url, account_id, rpc_policy(&args)).await?;
+                status!("👀 Following {} events...", WATCHED_PALLETS.join(" and "));
+                substrate_client
+                    .tail_events(&filter, |event| match json {
+                        true => println!("{}", serde_json::to_string(&event).expect("events serialize")),
+                        false => println!("{}", event),
+                    })
+                    .await?;
+                Ok(())
+            }
+            Command::Inspect { proof, json } => {
+                let inspection = inspect(&tokio::fs::read(proof).await.classify(FailureClass::BadInput)?);
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&inspection)?);
+                } else {
+                    let len = |len: Option<usize>| len.map_or("-".to_string(), |len| format!("{} bytes", len));
+                    println!("Proof:      {}", len(inspection.proof_bytes));
+                    println!("Pubs:       {}", len(inspection.pubs_bytes));
+                    println!("VK:         {}", len(inspection.vk_bytes));
+                    println!("VK hash:    {}", inspection.vk_hash.map_or("-".to_string(), |hash| format!("{:?}", hash)));
+                    println!("Request ID: {}", inspection.request_id.as_deref().unwrap_or("-"));
+                    for finding in &inspection.findings {
+                        match finding.severity {
+                            Severity::Error => println!("❌ {}", finding.message),
+                            Severity::Warning => println!("⚠️  {}", finding.message),
+                        }
+                    }
+                }
+                match inspection.is_valid() {
+                    true => Ok(()),
+                    false => Err(FailureClass::BadInput.error(format!("{} is not a valid converted proof", proof.display()))),
+                }
+            }
+            Command::Hash { proof, json } => {
+                let converted_proof = ProofConverter::new().load_proof(proof).await.classify(FailureClass::BadInput)?;
+                let statement = Statement::of(&converted_proof).classify(FailureClass::BadInput)?;
+                match json {
+                    true => println!("{}", serde_json::to_string_pretty(&statement)?),
+                    false => println!("{:?}", statement.statement),
+                }
+                Ok(())
+            }
+            Command::ConvertDir { dir, out_dir, glob } => {
+                let router = Router::new(proof_client(&args)?)
+                    .with_output_format(args.format)
+                    .with_compression(args.compress)
+                    .with_force_vk(args.force)
+                    .with_conversion_jobs(Some(args.jobs))
+                    .with_stats(stats.clone());
+                let options = ConvertDirOptions {
+                    input_dir: dir.clone(),
+                    output_dir: out_dir.clone(),
+                    pattern: glob.clone(),
+                    jobs: args.jobs,
+                    inputs: ProofInputs { force_vk: args.force, offline: args.offline, ..ProofInputs::default() },
+                };
+                let outcomes = convert_dir(&router, options).await?;
+                print_summary(&outcomes);
+                save_stats(&args, stats.as_deref()).await?;
+                let failed = outcomes.iter().filter(|outcome| outcome.status == BatchStatus::Failed).count();
+                match failed {
+                    0 => Ok(()),
+                    _ => Err(FailureClass::PartialFailure.error(format!("{} of {} artifacts failed", failed, outcomes.len()))),
+                }
+            }
+            Command::Status { tx_hash, receipt, block_hash, search_depth, json } => {
+                let (tx_hash, block_hash) = match receipt {
+                    Some(path) => {
+                        let bytes = tokio::fs::read(path)
+                            .await
+                            .map_err(|e| FailureClass::BadInput.error(format!("Failed to read receipt {}: {}", path.display(), e)))?;
+                        let receipt: Submission = serde_json::from_slice(&bytes)
+                            .map_err(|e| FailureClass::BadInput.error(format!("Invalid receipt {}: {}", path.display(), e)))?;
+                        (receipt.tx_hash, receipt.block_hash.or(block_hash.clone()))
+                    }
+                    None => (tx_hash.clone().expect("clap requires a tx hash or --receipt"), block_hash.clone()),
+                };
+                let parse_hash = |hash: &str| {
+                    hash.parse()
+                        .map_err(|_| FailureClass::BadInput.error(format!("Invalid hash '{}'", hash)))
+                };
+                let tx_hash = parse_hash(&tx_hash)?;
+                let block_hash = block_hash.as_deref().map(parse_hash).transpose()?;
 
-        println!("💾 Saving artifact to temporary file...");
-        tokio::fs::write(&temp_file_path, artifact_data).await?;
+                let substrate_client = SubstrateClient::new_without_signer_with_policy(&args.ws_url, AccountId32([0; 32]), rpc_policy(&args)).await?;
+                let report = substrate_client
+                    .extrinsic_report(tx_hash, block_hash, *search_depth)
+                    .await?
+                    .ok_or_else(|| match block_hash {
+                        Some(block_hash) => {
+                            FailureClass::BadInput.error(format!("Extrinsic {:?} is not in block {:?}", tx_hash, block_hash))
+                        }
+                        None => FailureClass::BadInput.error(format!(
+                            "Extrinsic {:?} is not in the last {} finalized blocks; pass --block-hash or a larger --search-depth",
+                            tx_hash, search_depth
+                        )),
+                    })?;
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                    return Ok(());
+                }
+                println!("Extrinsic: {:?}", report.tx_hash);
+                println!("Block:     #{} {:?} (index {})", report.block_number, report.block_hash, report.index);
+                println!("Outcome:   {}", if report.success { "success" } else { "failed" });
+                match report.fee_paid {
+                    Some(fee) => println!("Fee paid:  {}", format_balance(&args, fee)),
+                    None => println!("Fee paid:  -"),
+                }
+                println!("Events:");
+                for event in &report.events {
+                    println!("  {}.{} {}", event.pallet, event.event, event.fields);
+                }
+                print_explorer_link(&args, &format!("{:?}", report.tx_hash));
+                Ok(())
+            }
+            Command::Reassemble { tx_hash, block_hash, search_depth, output } => {
+                let parse_hash = |hash: &str| {
+                    hash.parse()
+                        .map_err(|_| FailureClass::BadInput.error(format!("Invalid hash '{}'", hash)))
+                };
+                let tx_hash = parse_hash(tx_hash)?;
+                let block_hash = block_hash.as_deref().map(parse_hash).transpose()?;
+                let substrate_client = SubstrateClient::new_without_signer_with_policy(&args.ws_url, AccountId32([0; 32]), rpc_policy(&args)).await?;
+                let remark = substrate_client
+                    .find_remark(tx_hash, block_hash, *search_depth)
+                    .await?
+                    .ok_or_else(|| {
+                        FailureClass::BadInput
+                            .error(format!("Remark {:?} not found; pass --block-hash or a larger --search-depth", tx_hash))
+                    })?;
+                let data = match ChunkManifest::parse(&remark) {
+                    Some(manifest) => {
+                        status!("🧩 Fetching {} chunks of {}...", manifest.chunks.len(), manifest.file_name);
+                        let mut chunks = Vec::with_capacity(manifest.chunks.len());
+                        for (index, chunk) in manifest.chunks.iter().enumerate() {
+                            let remark = substrate_client
+                                .find_remark(chunk.tx_hash, Some(chunk.block_hash), 0)
+                                .await?
+                                .ok_or_else(|| {
+                                    FailureClass::BadInput.error(format!(
+                                        "Chunk {} ({:?}) is not in block {:?}",
+                                        index + 1,
+                                        chunk.tx_hash,
+                                        chunk.block_hash
+                                    ))
+                                })?;
+                            chunks.push(remark);
+                        }
+                        reassemble(&manifest, &chunks).classify(FailureClass::BadInput)?
+                    }
+                    // A proof small enough to have been sent in one remark
+                    None => remark,
+                };
+                write_atomic(output, &data).await?;
+                status!("✅ Rebuilt {} bytes into {}", data.len(), output.display());
+                Ok(())
+            }
+            Command::Cache { command: CacheCommand::Clean { older_than } } => {
+                let cache = args
+                    .cache_dir
+                    .clone()
+                    .or_else(ArtifactCache::default_dir)
+                    .map(ArtifactCache::new)
+                    .ok_or_else(|| anyhow::anyhow!("No cache directory; pass --cache-dir"))?;
+                let stats = cache.clean(*older_than).await?;
+                status!(
+                    "🧹 Removed {} cache files ({} bytes) from {}",
+                    stats.files,
+                    stats.bytes,
+                    cache.dir().display()
+                );
+                Ok(())
+            }
+            Command::Keys { command } => {
+                // Nothing is connected to read the prefix from, so it comes from the network
+                let ss58_prefix = network(&args)
+                    .ok_or_else(|| {
+                        FailureClass::BadInput.error("--ws-url is not a network preset; pass --network to pick the address prefix")
+                    })?
+                    .ss58_prefix();
+                let (key, json) = match command {
+                    KeysCommand::Generate { words, json } => (KeyInfo::generate(*words, ss58_prefix)?, *json),
+                    KeysCommand::Inspect { secret, json } => {
+                        let secret = SecretString::new(match secret {
+                            Some(secret) => secret.clone(),
+                            None => {
+                                let mut secret = String::new();
+                                std::io::stdin().read_line(&mut secret)?;
+                                secret
+                            }
+                        });
+                        (KeyInfo::inspect(secret.expose(), ss58_prefix)?, *json)
+                    }
+                };
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&key)?);
+                    return Ok(());
+                }
+                if let Some(mnemonic) = &key.mnemonic {
+                    println!("Mnemonic:    {}", mnemonic.expose());
+                }
+                println!("Address:     {} (prefix {})", key.address, key.ss58_prefix);
+                println!("Public key:  {}", key.public_key);
+                if key.mnemonic.is_some() {
+                    status!("🔑 Store the mnemonic safely: anyone holding it controls the account");
+                }
+                Ok(())
+            }
+            Command::Vk { command } => match command {
+                VkCommand::Hash { vk } => {
+                    println!("{:?}", vk_hash(&vk.bytes().await?));
+                    Ok(())
+                }
+                VkCommand::Check { vk } => {
+                    let hash = vk_hash(&vk.bytes().await?);
+                    // Storage reads need no account; any id will do
+                    let substrate_client = SubstrateClient::new_without_signer_with_policy(&args.ws_url, AccountId32([0; 32]), rpc_policy(&args)).await?;
+                    match substrate_client.is_vk_registered(hash).await? {
+                        true => status!("✅ VK {:?} is registered", hash),
+                        false => status!("❌ VK {:?} is not registered", hash),
+                    }
+                    Ok(())
+                }
+                VkCommand::Register { vk } => {
+                    let vk = vk.bytes().await?;
+                    let hash = vk_hash(&vk);
+                    let substrate_client = connect_substrate(&args).await?;
+                    if substrate_client.is_vk_registered(hash).await? {
+                        status!("✅ VK {:?} is already registered", hash);
+                        return Ok(());
+                    }
+                    let (hash, block_hash) = substrate_client.register_vk(&vk).await?;
+                    status!("🎉 VK {:?} registered in block {:?}", hash, block_hash);
+                    Ok(())
+                }
+            },
+            Command::Domain { command } => match command {
+                DomainCommand::Status { domain_id, json } => {
+                    let substrate_client = SubstrateClient::new_without_signer_with_policy(&args.ws_url, AccountId32([0; 32]), rpc_policy(&args)).await?;
+                    let domain = substrate_client.domain_info(*domain_id).await?;
+                    if *json {
+                        println!("{}", serde_json::to_string_pretty(&domain)?);
+                        return Ok(());
+                    }
+                    let ready = domain.ready_aggregations.iter().map(u64::to_string).collect::<Vec<_>>();
+                    println!("Domain:           {}", domain.domain_id);
+                    println!("State:            {}", domain.state);
+                    println!("Next aggregation: {} ({}/{} statements)", domain.next_aggregation_id, domain.queue_size, domain.max_aggregation_size);
+                    println!("Ready to publish: {}", if ready.is_empty() { "-".to_string() } else { ready.join(", ") });
+                    Ok(())
+                }
+                DomainCommand::Aggregate { domain_id, aggregation_id } => {
+                    let substrate_client = connect_substrate(&args).await?;
+                    let (aggregation_id, receipt, block_hash) = substrate_client.aggregate(*domain_id, *aggregation_id).await?;
+                    status!("🎉 Aggregation {} published with root {:?} in block {:?}", aggregation_id, receipt, block_hash);
+                    Ok(())
+                }
+                DomainCommand::Wait { domain_id, statement, aggregation_id, output } => {
+                    let statement = match (&statement.statement, &statement.proof) {
+                        (Some(statement), _) => statement
+                            .parse()
+                            .map_err(|_| FailureClass::BadInput.error(format!("Invalid statement hash '{}'", statement)))?,
+                        (None, Some(proof)) => {
+                            let converted_proof = ProofConverter::new().load_proof(proof).await.classify(FailureClass::BadInput)?;
+                            Statement::of(&converted_proof).classify(FailureClass::BadInput)?.statement
+                        }
+                        (None, None) => unreachable!("clap requires a statement or a proof"),
+                    };
+                    let substrate_client = SubstrateClient::new_without_signer_with_policy(&args.ws_url, AccountId32([0; 32]), rpc_policy(&args)).await?;
+                    let receipt = tokio::time::timeout(
+                        Duration::from_secs(args.aggregation_timeout),
+                        substrate_client.wait_for_aggregation(statement, *domain_id, *aggregation_id),
+                    )
+                    .await
+                    .map_err(|_| anyhow::anyhow!("Timed out waiting for statement {:?} to be aggregated", statement))??;
+                    handle_aggregation_receipt(&args, &receipt, output).await
+                }
+            },
+            Command::Multisig { command } => {
+                let multisig = multisig(&args)?.ok_or_else(|| {
+                    FailureClass::BadInput.error("Multisig commands need --multisig-signatories and --multisig-threshold")
+                })?;
+                let parse_call_hash = |call_hash: &str| {
+                    call_hash
+                        .parse::<subxt::utils::H256>()
+                        .map_err(|_| FailureClass::BadInput.error(format!("Invalid call hash '{}'", call_hash)))
+                };
+                match command {
+                    MultisigCommand::CallHash { proof } => {
+                        enforce_vk_allowlist(&args, proof).await?;
+                        let substrate_client = SubstrateClient::new_without_signer_with_policy(&args.ws_url, AccountId32([0; 32]), rpc_policy(&args))
+                            .await?
+                            .with_vk_mode(args.vk_mode)
+                            .with_proxy_for(proxy_for(&args)?);
+                        let call_hash = substrate_client.multisig_call_hash(proof, args.domain_id).await?;
+                        println!("Multisig:  {}", ss58_address(&multisig.account_id(), substrate_client.ss58_prefix()));
+                        println!("Call hash: {:?}", call_hash);
+                        Ok(())
+                    }
+                    MultisigCommand::Approve { call_hash } => {
+                        let call_hash = parse_call_hash(call_hash)?;
+                        let substrate_client = connect_substrate(&args).await?;
+                        let block_hash = substrate_client.approve_multisig(&multisig, call_hash).await?;
+                        status!("✅ Approved call {:?} of multisig {} in block {:?}", call_hash, ss58_address(&multisig.account_id(), substrate_client.ss58_prefix()), block_hash);
+                        Ok(())
+                    }
+                    MultisigCommand::Status { call_hash, json } => {
+                        let call_hash = parse_call_hash(call_hash)?;
+                        let substrate_client = SubstrateClient::new_without_signer_with_policy(&args.ws_url, AccountId32([0; 32]), rpc_policy(&args)).await?;
+                        let pending = substrate_client.pending_multisig(&multisig, call_hash).await?;
+                        if *json {
+                            println!("{}", serde_json::to_string_pretty(&pending)?);
+                            return Ok(());
+                        }
+                        match pending {
+                            Some(pending) => {
+                                println!("Call hash:  {:?}", pending.call_hash);
+                                println!("Timepoint:  {}-{}", pending.height, pending.index);
+                                println!("Approvals:  {}/{}", pending.approvals.len(), pending.threshold);
+                                for approval in &pending.approvals {
+                                    println!("            {}", approval);
+                                }
+                            }
+                            None => println!("Call {:?} is not waiting for approvals (never approved or already executed)", call_hash),
+                        }
+                        Ok(())
+                    }
+                }
+            }
+            Command::Watch {
+                program_vk,
+                requester,
+                poll_interval,
+                output_dir,
+                include_existing,
+                webhook_url,
+                webhook_secret,
+                webhook_retries,
+                tasks,
+                canary_proof,
+                workers,
+                convert_attempts,
+                submit_attempts,
+                retry_backoff,
+                dead_letter_dir,
+            } => {
+                let filter = match (program_vk, requester) {
+                    (Some(vk), _) => RequestFilter::Program(vk.clone()),
+                    (None, Some(address)) => RequestFilter::Requester(address.clone()),
+                    (None, None) => unreachable!("clap requires --program-vk or --requester"),
+                };
+                let router = Router::new(proof_client(&args)?)
+                    .with_submitter(submitter(&args).await?)
+                    .with_domain_id(args.domain_id)
+                    .with_vk_allowlist(load_vk_allowlist(&args).await?)
+                    .with_history(history.clone())
+                    .with_cache(artifact_cache(&args))
+                    .with_archive(ipfs_client(&args)?)
+                    .with_compression(args.compress)
+                    .with_force_vk(args.force);
+                let options = WatchOptions {
+                    filter,
+                    poll_interval: *poll_interval,
+                    output_dir: output_dir.clone(),
+                    include_existing: *include_existing,
+                    webhook: webhook_url
+                        .as_deref()
+                        .map(|url| WebhookClient::new(url, webhook_secret.clone(), *webhook_retries)),
+                    scheduler: Scheduler::new(tasks.clone(), args.balance_warning_threshold, canary_proof.clone())?,
+                    fetch_concurrency: args.fetch_concurrency,
+                    queue: QueueOptions {
+                        workers: *workers,
+                        convert_retry: RetryPolicy::new(*convert_attempts, Duration::from_secs(*retry_backoff)),
+                        submit_retry: RetryPolicy::new(*submit_attempts, Duration::from_secs(*retry_backoff)),
+                        dead_letter_dir: dead_letter_dir.clone(),
+                        convert_only: false,
+                    },
+                };
+                watch(Arc::new(router), options).await
+            }
+            Command::PrepareTx {
+                signer,
+                proof,
+                tx_output,
+                #[cfg(feature = "vault")]
+                qr,
+                #[cfg(feature = "vault")]
+                signature_scheme,
+            } => {
+                let account_id = signer
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid signer address {}: {:?}", signer, e))?;
+                enforce_vk_allowlist(&args, proof).await?;
+                let substrate_client = SubstrateClient::new_without_signer_with_policy(&args.ws_url, account_id, rpc_policy(&args))
+                    .await?
+                    .with_tx_options(tx_options(&args))
+                    .with_vk_mode(args.vk_mode)
+                    .with_multisig(multisig(&args)?)
+                    .with_proxy_for(proxy_for(&args)?);
+                let unsigned = substrate_client.prepare_proof_submission(proof, args.domain_id).await?;
+                unsigned.save(tx_output).await?;
+                status!("📝 Prepared transaction saved to {}", tx_output.display());
+                status!("✍️  Sign this payload with {}:", unsigned.signer);
+                println!("{}", unsigned.signer_payload);
 
-        println!("🔄 Converting proof to zkVerify format...");
-        let converter = ProofConverter::new();
-        let converted_proof = converter
-            .convert_proof(&temp_file_path, &metadata.vk)
-            .await?;
+                #[cfg(feature = "vault")]
+                if *qr {
+                    let frames = rusty_router::vault::qr_frames(&rusty_router::vault::uos_payload(&unsigned, *signature_scheme)?);
+                    status!("📱 Scan with Polkadot Vault ({} frames):", frames.len());
+                    rusty_router::vault::show_qr(&frames).await?;
+                }
+                Ok(())
+            }
+            Command::History { status, limit, format, reconcile } => {
+                let history = history.ok_or_else(|| anyhow::anyhow!("history requires --history-db"))?;
+                if *reconcile {
+                    Router::new(proof_client(&args)?)
+                        .with_submitter(submitter(&args).await?)
+                        .with_history(Some(history.clone()))
+                        .reconcile_history()
+                        .await?;
+                }
+                print_history(&history.list(*status, *limit)?, *format)
+            }
+            Command::List { program_vk, requester, status, limit, format } => {
+                let filter = match (program_vk, requester) {
+                    (Some(vk), _) => RequestFilter::Program(vk.clone()),
+                    (None, Some(address)) => RequestFilter::Requester(address.clone()),
+                    (None, None) => unreachable!("clap requires --program-vk or --requester"),
+                };
+                let client = proof_client(&args)?;
+                status!("🌐 Loading explorer page for {}", filter);
+                let requests = client.list_requests(&filter).await;
+                client.close_browser().await;
+                let requests: Vec<RequestSummary> = requests?
+                    .into_iter()
+                    .filter(|request| {
+                        status.as_ref().is_none_or(|status| {
+                            request.status.as_ref().is_some_and(|shown| shown.eq_ignore_ascii_case(status))
+                        })
+                    })
+                    .take(limit.unwrap_or(usize::MAX))
+                    .collect();
+                print_requests(&requests, *format)
+            }
+            Command::SubmitSigned {
+                tx,
+                signature,
+                signature_scheme,
+                #[cfg(feature = "vault")]
+                signature_qr,
+            } => {
+                let unsigned = UnsignedTx::load(tx).await?;
+                #[cfg(feature = "vault")]
+                let signature = match (signature, signature_qr) {
+                    (None, Some(image)) => Some(rusty_router::vault::read_signature_qr(image)?),
+                    (signature, _) => signature.clone(),
+                };
+                #[cfg(not(feature = "vault"))]
+                let signature = signature.clone();
+                let signature = signature.ok_or_else(|| anyhow::anyhow!("No signature given"))?;
+                let signature = parse_signature(&signature, *signature_scheme)?;
+                let account_id = unsigned
+                    .signer
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid signer address {}: {:?}", unsigned.signer, e))?;
+                let substrate_client = SubstrateClient::new_without_signer_with_policy(&args.ws_url, account_id, rpc_policy(&args)).await?;
+                let tx_hash = substrate_client.submit_signed(&unsigned, &signature).await?;
+                status!("🎉 Signed transaction submitted successfully!");
+                status!("🔗 Extrinsic ID: {}", tx_hash);
+                print_explorer_link(&args, &tx_hash);
+                Ok(())
+            }
+        };
+    }
 
-        println!("💾 Saving converted proof...");
-        converter.save_proof(&converted_proof, &args.output).await?;
 
-        println!("✅ Conversion successful: {}", args.output.display());
 
-        // If --get-proof is specified, also save detailed proof information
-        if args.get_proof {
-            info!("Extracting detailed proof information...");
-            converter.save_detailed_proof_info(&temp_file_path, "proof_details.json").await?;
-            info!("Detailed proof information saved to proof_details.json");
+    if let Some(requests_file) = &args.requests_file {
+        let entries = load_requests_file(requests_file).await.classify(FailureClass::BadInput)?;
+        let mut router = Router::new(proof_client(&args)?)
+            .with_domain_id(args.domain_id)
+            .with_vk_allowlist(load_vk_allowlist(&args).await?)
+            .with_intermediate_dir(args.emit_intermediate.clone())
+            .with_history(history.clone())
+            .with_cache(artifact_cache(&args))
+            .with_archive(ipfs_client(&args)?)
+            .with_compression(args.compress)
+            .with_force_vk(args.force)
+            .with_conversion_jobs(Some(args.jobs))
+            .with_stats(stats.clone());
+        if args.submit_to_zkverify {
+            router = router.with_submitter(submitter(&args).await?);
         }
+        let options = BatchOptions {
+            output_dir: args.batch_output_dir.clone(),
+            skip_routed: args.submit_to_zkverify && !args.ignore_history,
+            fetch_concurrency: args.fetch_concurrency,
+            queue: QueueOptions {
+                // Spare workers download and submit while every conversion slot is busy
+                workers: args.jobs.max(1) * 2,
+                convert_only: !args.submit_to_zkverify,
+                ..QueueOptions::default()
+            },
+        };
+        let outcomes = run_batch(Arc::new(router), entries, options).await?;
+        print_summary(&outcomes);
+        save_stats(&args, stats.as_deref()).await?;
+        let failed = outcomes.iter().filter(|outcome| outcome.status == BatchStatus::Failed).count();
+        if failed > 0 {
+            return Err(FailureClass::PartialFailure.error(format!("{} of {} requests failed", failed, outcomes.len())));
+        }
+        return Ok(());
+    }
+
+    if let (Some(history), Some(request_id)) = (&history, &args.request_id)
+        && args.submit_to_zkverify
+        && !args.ignore_history
+        && let Some(entry) = history.get(request_id)?
+        && entry.status.is_routed()
+    {
+        status!(
+            "⏭️  {} was already routed in {}; pass --ignore-history to submit it again",
+            request_id,
+            entry.tx_hash.as_deref().unwrap_or("an earlier run")
+        );
+        return Ok(());
+    }
 
-        // Explicitly clean up the temporary file
-        drop(temp_file);
+    if args.format == OutputFormat::Scale && (args.submit_to_zkverify || args.send_remark || args.resubmit_only) {
+        return Err(FailureClass::BadInput.error(
+            "--format scale proofs cannot be submitted; convert to json, cbor or msgpack instead",
+        ));
+    }
+    if args.proof_type != ProofType::Sp1 && (args.artifact_path.is_none() || args.vk_file.is_none()) {
+        return Err(FailureClass::BadInput.error(format!(
+            "--proof-type {} converts a local proof: pass --artifact-path and --vk-file (explorer requests are SP1)",
+            args.proof_type
+        )));
+    }
+    if matches!(args.proof_type, ProofType::Groth16 | ProofType::Fflonk) && args.public_inputs.is_none() {
+        return Err(FailureClass::BadInput.error(format!(
+            "--proof-type {} needs the snarkjs public.json as --public-inputs",
+            args.proof_type
+        )));
+    }
+
+    // Whether --output was written by this run, so that later warnings may be added to it
+    let mut converted = false;
+
+    // Handle proof conversion (original functionality) - only if request_id is provided
+    if let Some(request_id) = &args.request_id {
+        // A previous conversion is only worth reusing when we are about to submit it
+        let submitting = args.send_remark || args.submit_to_zkverify;
+        let previous = if submitting || args.resubmit_only {
+            find_previous_conversion(&args, history.as_deref(), request_id).await
+        } else {
+            None
+        };
+
+        if let Some(previous) = previous {
+            status!("♻️  Reusing existing conversion of {} from {}", request_id, previous.display());
+            // The steps below submit --output
+            if previous != args.output {
+                tokio::fs::copy(&previous, &args.output)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to copy {} to {}: {}", previous.display(), args.output.display(), e))?;
+            }
+        } else if args.resubmit_only {
+            return Err(FailureClass::BadInput.error(format!(
+                "--resubmit-only: no previous conversion of {} found in {}, the history or {}",
+                request_id,
+                args.output.display(),
+                args.batch_output_dir.display()
+            )));
+        } else {
+            let router = Router::new(proof_client(&args)?)
+                .with_details_output(args.get_proof.then(|| PathBuf::from("proof_details.json")))
+                .with_pubs_abi(load_pubs_abi(&args).await?)
+                .with_intermediate_dir(args.emit_intermediate.clone())
+                .with_output_format(args.format)
+                .with_compression(args.compress)
+                .with_force_vk(args.force)
+                .with_history(history.clone())
+                .with_cache(artifact_cache(&args))
+                .with_archive(ipfs_client(&args)?)
+                .with_stats(stats.clone());
+            status!("🌐 Loading explorer page for request: {}", request_id);
+            let metadata = if args.wait_for_fulfillment {
+                router.wait_for_metadata(request_id, args.poll_interval, args.fulfillment_timeout).await
+            } else {
+                router.fetch_metadata(request_id).await
+            };
+            router.client().close_browser().await;
+            let mut metadata = metadata?;
+            if args.artifact_sha256.is_some() {
+                metadata.artifact_sha256 = args.artifact_sha256.clone();
+            }
+            if let Some(path) = &args.save_metadata {
+                let saved = serde_json::json!({
+                    "request_id": request_id,
+                    "explorer_url": format!("{}/request/{}", args.api_base, request_id),
+                    "artifact_url": metadata.artifact_url,
+                    "vk": metadata.vk,
+                    "artifact_sha256": metadata.artifact_sha256,
+                    "details": metadata.details,
+                    "fields": metadata.fields,
+                });
+                write_atomic(path, serde_json::to_string_pretty(&saved)?)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+                status!("🔎 Explorer metadata saved to {}", path.display());
+            }
+            router.convert(request_id, &metadata, &args.output).await?;
+            converted = true;
+        }
+    } else if let Some(artifact_path) = &args.artifact_path {
+        let router = Router::new(proof_client(&args)?)
+            .with_details_output(args.get_proof.then(|| PathBuf::from("proof_details.json")))
+            .with_pubs_abi(load_pubs_abi(&args).await?)
+            .with_intermediate_dir(args.emit_intermediate.clone())
+            .with_output_format(args.format)
+            .with_compression(args.compress)
+            .with_archive(ipfs_client(&args)?)
+            .with_stats(stats.clone());
+        status!("📦 Using local proof artifact {}", artifact_path.display());
+        let inputs = ProofInputs {
+            proof: artifact_path.clone(),
+            vk: args.vk.clone(),
+            force_vk: args.force,
+            offline: args.offline,
+            vk_file: args.vk_file.clone(),
+            public_inputs: args.public_inputs.clone(),
+            common_data: args.common_data.clone(),
+            plonky2: Plonky2Params { hash: args.plonky2_hash, compressed: args.plonky2_compressed },
+        };
+        // Refuse a disallowed program before spending minutes converting its proof
+        if args.submit_to_zkverify
+            && let Some(vk_allowlist) = load_vk_allowlist(&args).await?
+        {
+            vk_allowlist.check_vk(&args.proof_type.backend().derive_vk(&inputs).await?)?;
+        }
+        router.convert_local(args.proof_type, &inputs, args.artifact_sha256.as_deref(), &args.output).await?;
+        converted = true;
     } else {
         info!("No request_id provided, skipping proof conversion");
     }
+    save_stats(&args, stats.as_deref()).await?;
 
-    // Handle blockchain transactions (system.remark, zkVerify submission, or pallet listing)
-    if args.send_remark || args.submit_to_zkverify || args.list_pallets {
-        // Get mnemonic from environment
-        let mnemonic = std::env::var("ZKV_MNEMONIC")
-            .expect("ZKV_MNEMONIC environment variable not found. Please set it in your .env file");
+    if args.submit_to_zkverify {
+        enforce_vk_allowlist(&args, &args.output).await?;
+    }
+
+    // Request the submitted proof came from, for the history
+    let history_request_id = match (&history, &args.request_id) {
+        (None, _) => None,
+        (Some(_), Some(request_id)) => Some(request_id.clone()),
+        (Some(_), None) => ProofConverter::new().load_proof(&args.output).await.ok().and_then(|proof| proof.request_id),
+    };
+    let tracked = history.as_deref().zip(history_request_id.as_deref());
+
+    let submit_direct = args.submit_to_zkverify && args.backend == Backend::Direct;
+    if args.submit_to_zkverify && args.backend == Backend::Relayer {
+        let result = submit_via_relayer(&args, tracked).await;
+        // The relayer job is finished, even when it reported no block hash
+        record_outcome(tracked, &result);
+        save_receipt(&args, tracked, &result?).await;
+    }
 
-        info!("Connecting to Substrate node...");
-        let substrate_client = SubstrateClient::new(&args.ws_url, &mnemonic).await?;
+    // Handle blockchain transactions (system.remark, zkVerify submission, or pallet listing)
+    if args.send_remark || submit_direct || args.list_pallets {
+        let substrate_client = connect_signer(&args).await?;
+        if let Some(warning) = check_balance(&args, &substrate_client).await?
+            && converted
+            && args.format != OutputFormat::Scale
+        {
+            add_output_warning(&args, warning).await;
+        }
+        let start_nonce = substrate_client.next_nonce().await?;
+        let mut submitted = 0;
 
         if args.send_remark {
             info!("Sending proof as system.remark transaction...");
-            let tx_hash = substrate_client.send_proof_as_remark(&args.output).await?;
-            info!("Proof sent successfully! Transaction hash: {}", tx_hash);
+            let inclusion = substrate_client.send_proof_as_remark(&args.output).await?;
+            status!("🎉 Proof remark included in block {:?}", inclusion.block_hash);
+            status!("🔗 Extrinsic ID: {:?}", inclusion.tx_hash);
+            print_explorer_link(&args, &format!("{:?}", inclusion.tx_hash));
+            submitted += inclusion.chunks as u64 + 1;
         }
 
-        if args.submit_to_zkverify {
-            println!("🚀 Submitting proof to zkVerify network...");
-            let tx_hash = substrate_client.submit_proof_to_zkverify(&args.output).await?;
-            println!("🎉 Proof submitted successfully to zkVerify!");
-            println!("🔗 Extrinsic ID: {}", tx_hash);
-            println!("🌐 View on zkVerify Testnet Explorer: https://zkverify-testnet.subscan.io/extrinsic/{}", tx_hash);
+        if submit_direct {
+            let result = submit_direct_proof(&args, &substrate_client, tracked).await;
+            record_outcome(tracked, &result);
+            save_receipt(&args, tracked, &result?).await;
+            submitted += 1;
+        }
+
+        if args.wait_nonce_settled && submitted > 0 {
+            substrate_client
+                .wait_for_nonce_settled(start_nonce + submitted, Duration::from_secs(args.nonce_settle_timeout))
+                .await?;
         }
 
         if args.list_pallets {
@@ -137,5 +1783,608 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    if let (Some(receipt_path), Some(calldata_output)) = (&args.calldata_from, &args.calldata_output) {
+        let receipt = AggregationReceipt::load(receipt_path).await?;
+        save_calldata(&receipt, calldata_output).await?;
+    }
+
+    #[cfg(feature = "eth")]
+    if let (Some(receipt_path), Some(rpc_url), Some(contract)) = (&args.eth_verify, &args.eth_rpc_url, &args.eth_contract) {
+        let receipt = AggregationReceipt::load(receipt_path).await?;
+        verify_on_ethereum(rpc_url, contract, &receipt).await?;
+    }
+
+    Ok(())
+}
+
+// Summarize the conversions --stats recorded and write them out
+async fn save_stats(args: &Args, stats: Option<&StatsRecorder>) -> anyhow::Result<()> {
+    if let (Some(path), Some(stats)) = (&args.stats, stats) {
+        let stats = stats.snapshot();
+        print_totals(&stats);
+        write_stats(path, &stats).await?;
+        status!("📊 Stats written to {}", path.display());
+    }
+    Ok(())
+}
+
+// The submission backend selected with --backend
+async fn submitter(args: &Args) -> anyhow::Result<Submitter> {
+    Ok(match args.backend {
+        Backend::Direct => Submitter::Direct(Box::new(connect_substrate(args).await?)),
+        Backend::Relayer => Submitter::Relayer(Box::new(relayer_client(args)?)),
+    })
+}
+
+// Submit `args.output` with our own account, following up with the aggregation receipt if requested
+async fn submit_direct_proof(
+    args: &Args,
+    substrate_client: &SubstrateClient,
+    tracked: Option<(&History, &str)>,
+) -> anyhow::Result<Submission> {
+    let on_event = |event: TxEvent| {
+        if let Some((history, request_id)) = tracked
+            && let Err(e) = history.record_tx_event(request_id, &event)
+        {
+            warn!("Failed to update history: {:#}", e);
+        }
+    };
+
+    let converted_proof = ProofConverter::new().load_proof(&args.output).await.classify(FailureClass::BadInput)?;
+    if let Some(prior) = substrate_client.find_prior_verification(&converted_proof, args.domain_id).await? {
+        status!("⏭️  Statement {:?} was already verified at block {}; not submitting it again", prior.statement, prior.block_number);
+        status!("🔗 Extrinsic ID: {:?}", prior.tx_hash);
+        print_explorer_link(args, &format!("{:?}", prior.tx_hash));
+        if args.aggregation_output.is_some() {
+            match prior.aggregation {
+                Some((domain_id, aggregation_id)) => warn!(
+                    "The proof was queued into aggregation {} of domain {}; its receipt is not waited for again",
+                    aggregation_id, domain_id
+                ),
+                None => warn!("The earlier submission was not queued for aggregation; no receipt is written"),
+            }
+        }
+        return Ok(Submission {
+            tx_hash: format!("{:?}", prior.tx_hash),
+            block_hash: Some(format!("{:?}", prior.block_hash)),
+            block_number: Some(prior.block_number),
+            aggregation_id: prior.aggregation.map(|(_, aggregation_id)| aggregation_id),
+            events: Vec::new(),
+        });
+    }
+
+    status!("🚀 Submitting proof to zkVerify network...");
+    // Followed to finality, so that every submission gets its block, events and receipt
+    let inclusion = substrate_client
+        .submit_proof_and_track(&args.output, args.domain_id, &on_event)
+        .await?;
+    status!("🎉 Proof verified on zkVerify!");
+    status!("🔗 Extrinsic ID: {}", inclusion.tx_hash);
+    print_explorer_link(args, &inclusion.tx_hash);
+
+    if let Some(aggregation_output) = &args.aggregation_output {
+        let (Some(domain_id), Some(aggregation_id)) = (inclusion.domain_id, inclusion.aggregation_id) else {
+            anyhow::bail!("Proof was not queued for aggregation (no Aggregate.NewProof event)");
+        };
+        let receipt = tokio::time::timeout(
+            Duration::from_secs(args.aggregation_timeout),
+            substrate_client.wait_for_aggregation(inclusion.statement, domain_id, Some(aggregation_id)),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("Timed out waiting for aggregation {}", aggregation_id))??;
+        handle_aggregation_receipt(args, &receipt, aggregation_output).await?;
+    }
+    Ok(Submission {
+        tx_hash: inclusion.tx_hash,
+        block_hash: Some(format!("{:?}", inclusion.block_hash)),
+        block_number: inclusion.block_number,
+        aggregation_id: inclusion.aggregation_id,
+        events: inclusion.events,
+    })
+}
+
+// Write the receipt of a finalized submission of `args.output` next to it. The proof is on
+// chain by now, so problems are reported but do not fail the run.
+async fn save_receipt(args: &Args, tracked: Option<(&History, &str)>, submission: &Submission) {
+    let request_id = match (&args.request_id, tracked) {
+        (Some(request_id), _) => Some(request_id.clone()),
+        (None, Some((_, request_id))) => Some(request_id.to_string()),
+        (None, None) => ProofConverter::new().load_proof(&args.output).await.ok().and_then(|proof| proof.request_id),
+    };
+    let recorded_sha256 = tracked
+        .and_then(|(history, request_id)| history.get(request_id).ok().flatten())
+        .and_then(|entry| entry.artifact_sha256);
+    let artifact_sha256 = match (recorded_sha256, &args.artifact_sha256, &args.artifact_path) {
+        (Some(sha256), _, _) => Some(sha256),
+        (None, Some(sha256), _) => Some(sha256.trim_start_matches("0x").to_lowercase()),
+        (None, None, Some(artifact_path)) => receipt::artifact_sha256(artifact_path).await.ok(),
+        (None, None, None) => None,
+    };
+    let receipt = SubmissionReceipt::new(request_id.as_deref(), artifact_sha256.as_deref(), &args.output, args.domain_id, submission);
+    let receipt_path = SubmissionReceipt::path_for(&args.output);
+    match receipt.save(&receipt_path).await {
+        Ok(()) => status!("🧾 Receipt written to {}", receipt_path.display()),
+        Err(e) => warn!("Failed to write the submission receipt: {:#}", e),
+    }
+}
+
+// Record how a submission ended; history problems are reported but do not fail the run
+fn record_outcome(tracked: Option<(&History, &str)>, result: &anyhow::Result<Submission>) {
+    let Some((history, request_id)) = tracked else {
+        return;
+    };
+    let recorded = match result {
+        Ok(submission) => history.record_finalized(request_id, submission),
+        Err(e) => history.record_failure(request_id, &format!("{:#}", e)),
+    };
+    if let Err(e) = recorded {
+        warn!("Failed to update history: {:#}", e);
+    }
+}
+
+fn print_history(entries: &[rusty_router::history::HistoryEntry], format: HistoryFormat) -> anyhow::Result<()> {
+    match format {
+        HistoryFormat::Json => println!("{}", serde_json::to_string_pretty(entries)?),
+        HistoryFormat::Csv => {
+            println!(
+                "request_id,status,artifact_sha256,output_path,convert_seconds,cycles,gas_used,requester,fulfilled_at,proof_mode,artifact_cid,proof_cid,tx_hash,block_hash,from_block,relayer_job_id,error,created_at,updated_at"
+            );
+            for entry in entries {
+                let fields = [
+                    entry.request_id.clone(),
+                    entry.status.to_string(),
+                    entry.artifact_sha256.clone().unwrap_or_default(),
+                    entry.output_path.clone().unwrap_or_default(),
+                    entry.convert_seconds.map(|seconds| format!("{:.1}", seconds)).unwrap_or_default(),
+                    entry.cycles.map(|cycles| cycles.to_string()).unwrap_or_default(),
+                    entry.gas_used.map(|gas| gas.to_string()).unwrap_or_default(),
+                    entry.requester.clone().unwrap_or_default(),
+                    entry.fulfilled_at.clone().unwrap_or_default(),
+                    entry.proof_mode.clone().unwrap_or_default(),
+                    entry.artifact_cid.clone().unwrap_or_default(),
+                    entry.proof_cid.clone().unwrap_or_default(),
+                    entry.tx_hash.clone().unwrap_or_default(),
+                    entry.block_hash.clone().unwrap_or_default(),
+                    entry.from_block.map(|block| block.to_string()).unwrap_or_default(),
+                    entry.relayer_job_id.clone().unwrap_or_default(),
+                    entry.error.clone().unwrap_or_default(),
+                    entry.created_at.to_string(),
+                    entry.updated_at.to_string(),
+                ];
+                // Quote every field, doubling embedded quotes
+                let row: Vec<String> = fields.iter().map(|field| format!("\"{}\"", field.replace('"', "\"\""))).collect();
+                println!("{}", row.join(","));
+            }
+        }
+        HistoryFormat::Table => {
+            for entry in entries {
+                println!(
+                    "{}  {:<9}  {}",
+                    entry.request_id,
+                    entry.status,
+                    entry.tx_hash.as_deref().or(entry.error.as_deref()).unwrap_or("-")
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_requests(requests: &[RequestSummary], format: HistoryFormat) -> anyhow::Result<()> {
+    match format {
+        HistoryFormat::Json => println!("{}", serde_json::to_string_pretty(requests)?),
+        HistoryFormat::Csv => {
+            println!("request_id,status,created");
+            for request in requests {
+                let fields = [
+                    request.request_id.as_str(),
+                    request.status.as_deref().unwrap_or_default(),
+                    request.created.as_deref().unwrap_or_default(),
+                ];
+                // Quoted only where needed, so that --requests-file reads the IDs back
+                let row: Vec<String> = fields
+                    .iter()
+                    .map(|field| {
+                        if field.contains([',', '"']) {
+                            format!("\"{}\"", field.replace('"', "\"\""))
+                        } else {
+                            field.to_string()
+                        }
+                    })
+                    .collect();
+                println!("{}", row.join(","));
+            }
+        }
+        HistoryFormat::Table => {
+            for request in requests {
+                println!(
+                    "{}  {:<13}  {}",
+                    request.request_id,
+                    request.status.as_deref().unwrap_or("-"),
+                    request.created.as_deref().unwrap_or("-")
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+// Timeouts, retries and proxy of explorer, artifact storage and relayer requests, 0 disabling a timeout
+fn http_policy(args: &Args) -> HttpPolicy {
+    let seconds = |seconds| Some(Duration::from_secs(seconds)).filter(|timeout| !timeout.is_zero());
+    HttpPolicy {
+        timeout: args.http_timeout.and_then(seconds),
+        connect_timeout: seconds(args.http_connect_timeout),
+        read_timeout: seconds(args.http_read_timeout),
+        proxy: args.proxy.clone(),
+        tls: tls_options(args),
+        ..HttpPolicy::default()
+    }
+    .with_retries(args.http_retries, Duration::from_secs(args.http_backoff))
+}
+
+// Trust roots and client certificate for HTTPS and wss:// connections
+fn tls_options(args: &Args) -> TlsOptions {
+    TlsOptions {
+        ca_bundle: args.ca_bundle.clone(),
+        client_cert: args.client_cert.clone(),
+        client_key: args.client_key.clone(),
+    }
+}
+
+fn rpc_policy(args: &Args) -> RpcPolicy {
+    let policy = RpcPolicy::default().with_tls(tls_options(args));
+    #[cfg(feature = "light-client")]
+    let policy = match args.light_client {
+        true => policy.with_light_client(rusty_router::network::LightClientOptions { chain_spec: args.chain_spec.clone() }),
+        false => policy,
+    };
+    policy
+}
+
+// Explorer client with the HTTP policy and download limits from the arguments
+fn proof_client(args: &Args) -> anyhow::Result<ProofClient> {
+    let client = ProofClient::new_with_options(&args.api_base, args.verbose)
+        .with_http_policy(http_policy(args))?
+        .with_max_artifact_size(args.max_artifact_size)
+        .with_max_download_rate(args.max_download_rate)
+        .with_extraction_strategies(args.extraction_strategies.clone())
+        .with_explorer_api_url(args.explorer_api_url.clone())
+        .with_api_key(args.api_key.clone(), args.api_key_header.clone())
+        .with_browser_options(
+            BrowserOptions::default()
+                .with_executable(args.browser_path.clone())
+                .with_wait_for_selector(args.wait_for_selector.clone())
+                .with_timeout(Duration::from_secs(args.render_timeout)),
+        )
+        .with_browser_pool_size(args.browser_pool_size);
+    #[cfg(feature = "s3")]
+    let client = client.with_s3(args.s3.then(|| {
+        rusty_router::s3::S3Options::default()
+            .with_part_size(args.s3_part_size)
+            .with_concurrency(args.s3_concurrency)
+            .with_region(args.s3_region.clone())
+            .with_no_sign_request(args.s3_no_sign_request)
+    }));
+    Ok(client)
+}
+
+// IPFS node to archive conversions to, with the HTTP policy from the arguments
+fn ipfs_client(args: &Args) -> anyhow::Result<Option<IpfsClient>> {
+    args.ipfs_api_url
+        .as_deref()
+        .map(|url| Ok(IpfsClient::new(url).with_http_policy(http_policy(args))?.with_token(args.ipfs_api_token.clone())))
+        .transpose()
+}
+
+// Cache in --cache-dir or the default directory, unless --no-cache
+fn artifact_cache(args: &Args) -> Option<ArtifactCache> {
+    if args.no_cache {
+        return None;
+    }
+    args.cache_dir.clone().or_else(ArtifactCache::default_dir).map(ArtifactCache::new)
+}
+
+// The multisig proofs are submitted from, if --multisig-signatories is set
+fn multisig(args: &Args) -> anyhow::Result<Option<Multisig>> {
+    let Some(threshold) = args.multisig_threshold else {
+        return Ok(None);
+    };
+    let signatories = args
+        .multisig_signatories
+        .iter()
+        .map(|address| {
+            address
+                .parse()
+                .map_err(|e| FailureClass::BadInput.error(format!("Invalid signatory address {}: {:?}", address, e)))
+        })
+        .collect::<anyhow::Result<_>>()?;
+    Ok(Some(Multisig { threshold, signatories }))
+}
+
+// The account proofs are submitted on behalf of, if --proxy-for is set
+fn proxy_for(args: &Args) -> anyhow::Result<Option<AccountId32>> {
+    args.proxy_for
+        .as_ref()
+        .map(|address| {
+            address
+                .parse()
+                .map_err(|e| FailureClass::BadInput.error(format!("Invalid --proxy-for address {}: {:?}", address, e)))
+        })
+        .transpose()
+}
+
+// Connect the signing account, warning if its balance is running low
+async fn connect_substrate(args: &Args) -> anyhow::Result<SubstrateClient> {
+    let substrate_client = connect_signer(args).await?;
+    check_balance(args, &substrate_client).await?;
+    Ok(substrate_client)
+}
+
+async fn connect_signer(args: &Args) -> anyhow::Result<SubstrateClient> {
+    let mnemonic = resolve_mnemonic(args.key_source.as_ref(), args.mnemonic_file.as_deref(), &http_policy(args)).await?;
+
+    info!("Connecting to Substrate node...");
+    let substrate_client = SubstrateClient::new_with_policy(&args.ws_url, mnemonic.expose(), rpc_policy(args))
+        .await?
+        .with_tx_options(tx_options(args))
+        .with_remark_call(args.remark_call)
+        .with_duplicate_check_blocks(args.duplicate_check_blocks)
+        .with_vk_mode(args.vk_mode)
+        .with_multisig(multisig(args)?)
+        .with_proxy_for(proxy_for(args)?);
+    info!("Signing as {}", substrate_client.address());
+    Ok(substrate_client)
+}
+
+// The near-balance-threshold warning, if the signer's free balance is below --balance-warning-threshold
+async fn check_balance(args: &Args, substrate_client: &SubstrateClient) -> anyhow::Result<Option<Warning>> {
+    let Some(threshold) = args.balance_warning_threshold else {
+        return Ok(None);
+    };
+    let free = substrate_client.free_balance().await?;
+    Ok((free < threshold).then(|| {
+        Warning::emit(
+            WarningCode::NearBalanceThreshold,
+            format!("Free balance {} is below the warning threshold {}", free, threshold),
+        )
+    }))
+}
+
+// Record a warning raised after conversion in the `warnings` of the proof at --output. The
+// warning was already printed, so failing to record it is not worth failing the run.
+async fn add_output_warning(args: &Args, warning: Warning) {
+    let added = async {
+        let mut converted_proof = ProofConverter::new().load_proof(&args.output).await?;
+        converted_proof.warnings.push(warning);
+        let mut content = converted_proof.to_bytes(args.format)?;
+        if args.compress {
+            content = compress(&content)?;
+        }
+        write_atomic(&args.output, content).await?;
+        anyhow::Ok(())
+    };
+    if let Err(e) = added.await {
+        warn!("Failed to add the warning to {}: {:#}", args.output.display(), e);
+    }
+}
+
+// Fill in the settings the command line leaves at their defaults, from the selected config
+// profile first and the network preset second
+async fn apply_config(args: &mut Args, matches: &ArgMatches) -> anyhow::Result<()> {
+    let profile = load_profile(args).await?.unwrap_or_default();
+    args.network = args.network.or(profile.network);
+    let network = args.network;
+
+    let defaulted = |matches: &ArgMatches, id: &str| {
+        !matches!(matches.value_source(id), Some(ValueSource::CommandLine | ValueSource::EnvVariable))
+    };
+    if let Some(api_base) = profile.api_base.or(network.map(|network| network.api_base().to_string()))
+        && defaulted(matches, "api_base")
+    {
+        args.api_base = api_base;
+    }
+    if let Some(ws_url) = profile.ws_url.or(network.map(|network| network.ws_url().to_string()))
+        && defaulted(matches, "ws_url")
+    {
+        args.ws_url = ws_url;
+    }
+    args.domain_id = args.domain_id.or(profile.domain_id);
+    args.key_source = args.key_source.take().or(profile.key);
+    if let Some(output_dir) = &profile.output_dir {
+        if defaulted(matches, "batch_output_dir") {
+            args.batch_output_dir = output_dir.clone();
+        }
+        if let Some(Command::Watch { output_dir: watch_output_dir, .. }) = &mut args.command
+            && let Some(watch_matches) = matches.subcommand_matches("watch")
+            && defaulted(watch_matches, "output_dir")
+        {
+            *watch_output_dir = output_dir.clone();
+        }
+    }
+    Ok(())
+}
+
+async fn load_profile(args: &Args) -> anyhow::Result<Option<Profile>> {
+    let path = match (&args.config, Config::default_path()) {
+        (Some(path), _) => path.clone(),
+        (None, Some(path)) if path.exists() => path,
+        _ if args.profile.is_some() => anyhow::bail!("--profile needs a config file; pass --config"),
+        _ => return Ok(None),
+    };
+    let config = Config::load(&path).await?;
+    let profile = config.profile(args.profile.as_deref())?.cloned();
+    if profile.is_some() {
+        info!("Applying config profile from {}", path.display());
+    }
+    Ok(profile)
+}
+
+// The network preset selected, or the one --ws-url points at; none for other endpoints, which
+// get no explorer links, faucet hints or token symbols
+fn network(args: &Args) -> Option<Network> {
+    args.network.or_else(|| Network::from_ws_url(&args.ws_url))
+}
+
+// A balance in tokens and planck, or only planck when the network is not known
+fn format_balance(args: &Args, planck: u128) -> String {
+    match network(args) {
+        Some(network) => format!("{} ({} planck)", network.format_balance(planck), planck),
+        None => format!("{} planck", planck),
+    }
+}
+
+// Link to the transaction on the network's block explorer, if it has one
+fn print_explorer_link(args: &Args, tx_hash: &str) {
+    if let Some(url) = network(args).and_then(|network| network.extrinsic_url(tx_hash)) {
+        status!("🌐 View on block explorer: {}", url);
+    }
+}
+
+async fn load_vk_allowlist(args: &Args) -> anyhow::Result<Option<VkAllowlist>> {
+    match &args.vk_allowlist {
+        Some(path) => Ok(Some(VkAllowlist::load(path).await.classify(FailureClass::BadInput)?)),
+        None => Ok(None),
+    }
+}
+
+async fn load_pubs_abi(args: &Args) -> anyhow::Result<Option<PubsAbi>> {
+    match &args.pubs_abi {
+        Some(path) => Ok(Some(PubsAbi::load(path).await.classify(FailureClass::BadInput)?)),
+        None => Ok(None),
+    }
+}
+
+// Refuse to submit a converted proof whose program is not allowed in this environment
+async fn enforce_vk_allowlist(args: &Args, proof_path: &Path) -> anyhow::Result<()> {
+    if let Some(vk_allowlist) = load_vk_allowlist(args).await? {
+        vk_allowlist.check(&ProofConverter::new().load_proof(proof_path).await?)?;
+    }
+    Ok(())
+}
+
+fn tx_options(args: &Args) -> TxOptions {
+    TxOptions {
+        tip: args.tip,
+        era: args.era,
+        fee_bump: args.fee_bump_after_blocks.map(|after_blocks| FeeBump {
+            after_blocks,
+            tip_increment: args.fee_bump_increment,
+            max_attempts: args.fee_bump_max_attempts,
+        }),
+        max_per_block: args.max_submissions_per_block,
+        max_per_hour: args.max_submissions_per_hour,
+        max_per_second: args.max_submissions_per_second,
+        pace: args.pace_submissions,
+        pool_retries: args.pool_retries,
+    }
+}
+
+fn relayer_client(args: &Args) -> anyhow::Result<RelayerClient> {
+    let api_key = args
+        .relayer_api_key
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("--backend relayer requires --relayer-api-key or RELAYER_API_KEY"))?;
+    RelayerClient::new(&args.relayer_url, api_key, Duration::from_secs(args.relayer_poll_interval))
+        .with_http_policy(http_policy(args))
+}
+
+async fn submit_via_relayer(args: &Args, tracked: Option<(&History, &str)>) -> anyhow::Result<Submission> {
+    let relayer = relayer_client(args)?;
+
+    status!("🚀 Submitting proof through the zkVerify relayer...");
+    let converted_proof = ProofConverter::new().load_proof(&args.output).await?;
+    let job_id = relayer.submit_proof(&converted_proof, args.domain_id).await?;
+    status!("📨 Relayer job created: {}", job_id);
+    if let Some((history, request_id)) = tracked
+        && let Err(e) = history.record_relayer_job(request_id, &job_id)
+    {
+        warn!("Failed to update history: {:#}", e);
+    }
+
+    let status = match args.aggregation_output {
+        Some(_) => tokio::time::timeout(Duration::from_secs(args.aggregation_timeout), relayer.wait_for_job(&job_id, true))
+            .await
+            .map_err(|_| anyhow::anyhow!("Timed out waiting for relayer job {} to be aggregated", job_id))??,
+        None => relayer.wait_for_job(&job_id, false).await?,
+    };
+    status!("🎉 Proof {} via relayer!", status.status.to_lowercase());
+    if let Some(tx_hash) = &status.tx_hash {
+        status!("🔗 Extrinsic ID: {}", tx_hash);
+        print_explorer_link(args, tx_hash);
+    }
+
+    if let (Some(aggregation_output), Some(domain_id)) = (&args.aggregation_output, args.domain_id) {
+        let receipt = status
+            .aggregation_receipt(domain_id)
+            .ok_or_else(|| anyhow::anyhow!("Relayer job {} has no aggregation details", job_id))?;
+        handle_aggregation_receipt(args, &receipt, aggregation_output).await?;
+    }
+
+    Ok(Submission {
+        tx_hash: status.tx_hash.unwrap_or(job_id),
+        block_hash: status.block_hash,
+        block_number: None,
+        aggregation_id: status.aggregation_id,
+        events: Vec::new(),
+    })
+}
+
+// Persist a published aggregation and run the follow-up steps that consume it
+async fn handle_aggregation_receipt(args: &Args, receipt: &AggregationReceipt, aggregation_output: &Path) -> anyhow::Result<()> {
+    receipt.save(aggregation_output).await?;
+    status!("🌳 Aggregation receipt and Merkle path saved to {}", aggregation_output.display());
+
+    if let Some(calldata_output) = &args.calldata_output {
+        save_calldata(receipt, calldata_output).await?;
+    }
+
+    #[cfg(feature = "eth")]
+    if let (Some(rpc_url), Some(contract)) = (&args.eth_rpc_url, &args.eth_contract) {
+        verify_on_ethereum(rpc_url, contract, receipt).await?;
+    }
+
     Ok(())
 }
+
+async fn save_calldata(receipt: &AggregationReceipt, calldata_output: &Path) -> anyhow::Result<()> {
+    Calldata::from_receipt(receipt).save(calldata_output).await?;
+    status!("📝 verifyProofAggregation calldata saved to {}", calldata_output.display());
+    Ok(())
+}
+
+#[cfg(feature = "eth")]
+async fn verify_on_ethereum(rpc_url: &str, contract: &str, receipt: &AggregationReceipt) -> anyhow::Result<()> {
+    status!("⛓️  Checking attestation on Ethereum...");
+    let eth_client = rusty_router::eth::EthClient::new(rpc_url);
+    if eth_client.verify_proof_aggregation(contract, receipt).await? {
+        status!("✅ Aggregation {} is attested on Ethereum", receipt.aggregation_id);
+        Ok(())
+    } else {
+        anyhow::bail!("Aggregation {} is not attested by {}", receipt.aggregation_id, contract)
+    }
+}
+
+// The first file recorded as a conversion of `request_id`, looking in --output, at the output
+// path the history records for it and in the --requests-file output directory, which watch
+// shares unless given its own --output-dir
+async fn find_previous_conversion(args: &Args, history: Option<&History>, request_id: &str) -> Option<PathBuf> {
+    let mut candidates = vec![args.output.clone()];
+    if let Some(history) = history
+        && let Ok(Some(entry)) = history.get(request_id)
+        && let Some(output_path) = entry.output_path
+    {
+        candidates.push(PathBuf::from(output_path));
+    }
+    for name in [format!("{}.{}", request_id, args.format), format!("{}.{}.zst", request_id, args.format)] {
+        candidates.push(args.batch_output_dir.join(name));
+    }
+    for candidate in candidates {
+        if let Ok(converted_proof) = ProofConverter::new().load_proof(&candidate).await
+            && converted_proof.request_id.as_deref() == Some(request_id)
+        {
+            return Some(candidate);
+        }
+    }
+    None
+}
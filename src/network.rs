@@ -0,0 +1,469 @@
+use anyhow::Result;
+use reqwest::{Certificate, Client, ClientBuilder, Identity, NoProxy, Proxy, RequestBuilder, Response, StatusCode};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::warn;
+
+use crate::failure::FailureClass;
+
+/// Timeouts, retries and rate limit for HTTP requests to the explorer, artifact storage and relayer.
+/// The default connects within 10 seconds, gives up on a server silent for 30 seconds and keeps
+/// connections alive, with no whole-request timeout, no retries and no rate limit.
+#[derive(Debug, Clone)]
+pub struct HttpPolicy {
+    /// Whole-request timeout, including reading the body
+    pub timeout: Option<Duration>,
+    pub connect_timeout: Option<Duration>,
+    /// Longest wait for the response to start, and then for each chunk of its body
+    pub read_timeout: Option<Duration>,
+    /// Interval of TCP keep-alive probes on idle connections
+    pub keep_alive: Option<Duration>,
+    /// Proxy URL for all requests, instead of the one in `HTTPS_PROXY`/`HTTP_PROXY`/`ALL_PROXY`.
+    /// Hosts in `NO_PROXY` are reached directly either way.
+    pub proxy: Option<String>,
+    pub tls: TlsOptions,
+    /// Retries after connection errors and resets, timeouts, 429 and 5xx responses
+    pub max_retries: u32,
+    /// Wait before the first retry, doubling after each one
+    pub backoff: Duration,
+    /// Longest wait between retries
+    pub max_backoff: Duration,
+    /// Wait a random 50-100% of each backoff, so clients failing together do not retry together
+    pub jitter: bool,
+    pub max_requests_per_second: Option<u32>,
+}
+
+impl Default for HttpPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            connect_timeout: Some(Duration::from_secs(10)),
+            read_timeout: Some(Duration::from_secs(30)),
+            keep_alive: Some(Duration::from_secs(60)),
+            proxy: None,
+            tls: TlsOptions::default(),
+            max_retries: 0,
+            backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            jitter: true,
+            max_requests_per_second: None,
+        }
+    }
+}
+
+impl HttpPolicy {
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    pub fn with_read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = Some(read_timeout);
+        self
+    }
+
+    pub fn with_keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+
+    pub fn with_proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    pub fn with_tls(mut self, tls: TlsOptions) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// No connect, read or whole-request timeout, waiting on a silent server forever
+    pub fn without_timeouts(mut self) -> Self {
+        self.timeout = None;
+        self.connect_timeout = None;
+        self.read_timeout = None;
+        self
+    }
+
+    pub fn with_retries(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn with_rate_limit(mut self, max_requests_per_second: u32) -> Self {
+        self.max_requests_per_second = Some(max_requests_per_second);
+        self
+    }
+
+    /// Wait before retry number `retry`, counting from 1
+    pub(crate) fn retry_delay(&self, retry: u32) -> Duration {
+        let delay = self
+            .backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_backoff);
+        match self.jitter {
+            true => delay.mul_f64(0.5 + fastrand::f64() * 0.5),
+            false => delay,
+        }
+    }
+}
+
+/// Timeouts, retries and rate limit for the read-only RPC queries made to the zkVerify node.
+/// Transactions are never resent by the policy. The default has no timeout, no retries and no rate limit.
+#[derive(Debug, Clone)]
+pub struct RpcPolicy {
+    /// Timeout of connecting and of each query
+    pub request_timeout: Option<Duration>,
+    /// Trust and identity for `wss://` connections
+    pub tls: TlsOptions,
+    /// Retries after failed or timed out connections and queries
+    pub max_retries: u32,
+    /// Wait before the first retry, doubling after each one
+    pub backoff: Duration,
+    pub max_requests_per_second: Option<u32>,
+    /// Sync a light client instead of trusting the node's answers
+    #[cfg(feature = "light-client")]
+    pub light_client: Option<LightClientOptions>,
+}
+
+impl Default for RpcPolicy {
+    fn default() -> Self {
+        Self {
+            request_timeout: None,
+            tls: TlsOptions::default(),
+            max_retries: 0,
+            backoff: Duration::from_secs(1),
+            max_requests_per_second: None,
+            #[cfg(feature = "light-client")]
+            light_client: None,
+        }
+    }
+}
+
+/// Chain a light client is started for. The node is then only asked for the chain spec, and
+/// not even that when one is given.
+#[cfg(feature = "light-client")]
+#[derive(Debug, Clone, Default)]
+pub struct LightClientOptions {
+    /// Chain spec JSON with the chain's boot nodes and, ideally, a recent sync checkpoint
+    pub chain_spec: Option<PathBuf>,
+}
+
+impl RpcPolicy {
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = Some(request_timeout);
+        self
+    }
+
+    pub fn with_tls(mut self, tls: TlsOptions) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub fn with_retries(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn with_rate_limit(mut self, max_requests_per_second: u32) -> Self {
+        self.max_requests_per_second = Some(max_requests_per_second);
+        self
+    }
+
+    /// Connect through a light client synced from the chain's boot nodes
+    #[cfg(feature = "light-client")]
+    pub fn with_light_client(mut self, light_client: LightClientOptions) -> Self {
+        self.light_client = Some(light_client);
+        self
+    }
+
+    /// Run `query` under the policy, retrying failures and timeouts
+    pub(crate) async fn run<T, F, Fut>(&self, limiter: &RateLimiter, what: &str, mut query: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut delay = self.backoff;
+        let mut attempt = 0;
+        loop {
+            limiter.wait().await;
+            attempt += 1;
+            let result = match self.request_timeout {
+                Some(timeout) => tokio::time::timeout(timeout, query())
+                    .await
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("{} timed out after {:?}", what, timeout))),
+                None => query().await,
+            };
+            match result {
+                Err(e) if attempt <= self.max_retries => {
+                    warn!("{} failed ({:#}), retrying in {:?}", what, e, delay);
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Extra trust roots and a client certificate, for servers behind a TLS-terminating gateway
+/// with an internal CA or requiring mutual TLS. Files are PEM; the default trusts the usual
+/// public roots and presents no certificate.
+#[derive(Debug, Clone, Default)]
+pub struct TlsOptions {
+    /// CA certificates trusted in addition to the usual roots
+    pub ca_bundle: Option<PathBuf>,
+    /// Client certificate chain, presented to servers asking for one
+    pub client_cert: Option<PathBuf>,
+    /// Private key of the client certificate, if it is not in the same file
+    pub client_key: Option<PathBuf>,
+}
+
+impl TlsOptions {
+    pub fn with_ca_bundle(mut self, ca_bundle: impl Into<PathBuf>) -> Self {
+        self.ca_bundle = Some(ca_bundle.into());
+        self
+    }
+
+    pub fn with_client_cert(mut self, client_cert: impl Into<PathBuf>, client_key: Option<PathBuf>) -> Self {
+        self.client_cert = Some(client_cert.into());
+        self.client_key = client_key;
+        self
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.ca_bundle.is_none() && self.client_cert.is_none()
+    }
+
+    fn apply(&self, mut builder: ClientBuilder) -> Result<ClientBuilder> {
+        if let Some(ca_bundle) = &self.ca_bundle {
+            let certificates = Certificate::from_pem_bundle(&read_pem(ca_bundle)?)
+                .map_err(|e| invalid_pem(ca_bundle, &e))?;
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        if let Some(client_cert) = &self.client_cert {
+            let mut pem = read_pem(client_cert)?;
+            if let Some(client_key) = &self.client_key {
+                pem.push(b'\n');
+                pem.extend(read_pem(client_key)?);
+            }
+            let identity = Identity::from_pem(&pem).map_err(|e| invalid_pem(client_cert, &e))?;
+            builder = builder.identity(identity);
+        }
+        Ok(builder)
+    }
+
+    /// rustls configuration for WebSocket connections to the node
+    pub(crate) fn rustls_config(&self) -> Result<rustls::ClientConfig> {
+        let mut roots = rustls::RootCertStore::empty();
+        let native = rustls_native_certs::load_native_certs();
+        for e in &native.errors {
+            warn!("Failed to load a system CA certificate: {}", e);
+        }
+        roots.add_parsable_certificates(native.certs);
+        if let Some(ca_bundle) = &self.ca_bundle {
+            for certificate in CertificateDer::pem_slice_iter(&read_pem(ca_bundle)?) {
+                let certificate = certificate.map_err(|e| invalid_pem(ca_bundle, &e))?;
+                roots.add(certificate).map_err(|e| invalid_pem(ca_bundle, &e))?;
+            }
+        }
+
+        let builder = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(roots);
+        let Some(client_cert) = &self.client_cert else {
+            return Ok(builder.with_no_client_auth());
+        };
+        let cert_pem = read_pem(client_cert)?;
+        let chain = CertificateDer::pem_slice_iter(&cert_pem)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| invalid_pem(client_cert, &e))?;
+        let key_path = self.client_key.as_ref().unwrap_or(client_cert);
+        let key = PrivateKeyDer::from_pem_slice(&read_pem(key_path)?).map_err(|e| invalid_pem(key_path, &e))?;
+        builder
+            .with_client_auth_cert(chain, key)
+            .map_err(|e| FailureClass::BadInput.error(format!("Invalid client certificate {}: {}", client_cert.display(), e)))
+    }
+}
+
+fn read_pem(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| FailureClass::BadInput.error(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+fn invalid_pem(path: &Path, e: &dyn std::fmt::Display) -> anyhow::Error {
+    FailureClass::BadInput.error(format!("Invalid PEM file {}: {}", path.display(), e))
+}
+
+/// Spaces requests evenly to stay under a requests-per-second limit
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    interval: Option<Duration>,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(max_requests_per_second: Option<u32>) -> Self {
+        Self {
+            interval: max_requests_per_second
+                .filter(|&per_second| per_second > 0)
+                .map(|per_second| Duration::from_secs(1) / per_second),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub(crate) async fn wait(&self) {
+        let Some(interval) = self.interval else { return };
+        let mut next_slot = self.next_slot.lock().await;
+        let now = Instant::now();
+        if *next_slot > now {
+            tokio::time::sleep_until(*next_slot).await;
+        }
+        *next_slot = (*next_slot).max(now) + interval;
+    }
+}
+
+/// reqwest client sending every request under an [`HttpPolicy`]
+#[derive(Debug)]
+pub(crate) struct HttpClient {
+    client: Client,
+    policy: HttpPolicy,
+    limiter: RateLimiter,
+}
+
+impl HttpClient {
+    pub(crate) fn new(policy: HttpPolicy) -> Result<Self> {
+        let mut builder = policy.tls.apply(Client::builder())?;
+        if let Some(timeout) = policy.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = policy.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(keep_alive) = policy.keep_alive {
+            builder = builder.tcp_keepalive(keep_alive);
+        }
+        if let Some(proxy) = &policy.proxy {
+            let invalid = |e: &dyn std::fmt::Display| FailureClass::BadInput.error(format!("Invalid proxy '{}': {}", proxy, e));
+            let url = reqwest::Url::parse(proxy).map_err(|e| invalid(&e))?;
+            let proxy = Proxy::all(url).map_err(|e| invalid(&e))?;
+            builder = builder.proxy(proxy.no_proxy(NoProxy::from_env()));
+        }
+        Ok(Self {
+            client: builder.build()?,
+            limiter: RateLimiter::new(policy.max_requests_per_second),
+            policy,
+        })
+    }
+
+    pub(crate) fn policy(&self) -> &HttpPolicy {
+        &self.policy
+    }
+
+    /// Send the request built by `request`, rebuilding it for each retry. Once retries
+    /// are used up the last response is returned as is, so callers still see its status.
+    pub(crate) async fn send(&self, request: impl Fn(&Client) -> RequestBuilder) -> Result<Response> {
+        self.send_retrying(request, self.policy.max_retries).await
+    }
+
+    /// Send a request that must not be repeated, such as a submission, without retries; the
+    /// policy's timeouts and rate limit still apply
+    pub(crate) async fn send_once(&self, request: impl Fn(&Client) -> RequestBuilder) -> Result<Response> {
+        self.send_retrying(request, 0).await
+    }
+
+    async fn send_retrying(&self, request: impl Fn(&Client) -> RequestBuilder, max_retries: u32) -> Result<Response> {
+        let mut retry = 0;
+        loop {
+            self.limiter.wait().await;
+            let request = request(&self.client).build()?;
+            // Without the query, which for artifacts holds the presigned URL's signature
+            let target = format!("{}{}", request.url().host_str().unwrap_or_default(), request.url().path());
+            let retries_left = retry < max_retries;
+            let sent = self.client.execute(request);
+            let result = match self.policy.read_timeout {
+                Some(read_timeout) => tokio::time::timeout(read_timeout, sent)
+                    .await
+                    .map_err(|_| format!("no response within {:?}", read_timeout)),
+                None => Ok(sent.await),
+            };
+            let error = match result {
+                Ok(Ok(response)) if !retries_left || !is_retryable(response.status()) => return Ok(response),
+                Ok(Ok(response)) => format!("server answered {}", response.status()),
+                Ok(Err(e)) if !retries_left || !is_transient(&e) => return Err(e.into()),
+                Ok(Err(e)) => e.to_string(),
+                Err(timeout) if !retries_left => anyhow::bail!("HTTP request to {} failed: {}", target, timeout),
+                Err(timeout) => timeout,
+            };
+            retry += 1;
+            let delay = self.policy.retry_delay(retry);
+            warn!(
+                "HTTP request to {} failed ({}), retry {}/{} in {:?}",
+                target, error, retry, max_retries, delay
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Run a fallible step that is not a plain request, such as rendering a page, retrying
+    /// its failures as the policy retries requests
+    pub(crate) async fn retry<T, F, Fut>(&self, what: &str, mut step: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut retry = 0;
+        loop {
+            self.limiter.wait().await;
+            match step().await {
+                Err(e) if retry < self.policy.max_retries => {
+                    retry += 1;
+                    let delay = self.policy.retry_delay(retry);
+                    warn!("{} failed ({:#}), retry {}/{} in {:?}", what, e, retry, self.policy.max_retries, delay);
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        // Only fails where `Client::new` would panic too, when TLS cannot be initialized
+        Self::new(HttpPolicy::default()).expect("Failed to build the HTTP client")
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+// Timeouts, refused connections and connections reset before the response arrived
+fn is_transient(e: &reqwest::Error) -> bool {
+    e.is_timeout() || e.is_connect() || e.is_request()
+}
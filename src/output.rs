@@ -0,0 +1,53 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tokio::io::AsyncWriteExt;
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Silence status lines and progress bars; requested output such as JSON is still printed
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Print a status line unless `--quiet` is given. The line is also logged under the
+/// `status` target, so a log file captures it.
+#[macro_export]
+macro_rules! status {
+    () => {
+        $crate::status!("")
+    };
+    ($($arg:tt)*) => {{
+        let line = format!($($arg)*);
+        ::tracing::info!(target: "status", "{}", line);
+        if !$crate::output::is_quiet() {
+            println!("{}", line);
+        }
+    }};
+}
+
+/// Write `data` to a temporary file next to `path`, flush it to disk and rename it into place,
+/// so that `path` holds either its previous content or all of `data`, never part of it
+pub async fn write_atomic(path: &Path, data: impl AsRef<[u8]>) -> std::io::Result<()> {
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let temp_path = dir.join(format!(".{}.{}-{}.tmp", name, std::process::id(), WRITES.fetch_add(1, Ordering::Relaxed)));
+    let written = async {
+        let mut file = tokio::fs::File::create(&temp_path).await?;
+        file.write_all(data.as_ref()).await?;
+        file.sync_all().await?;
+        tokio::fs::rename(&temp_path, path).await
+    }
+    .await;
+    if written.is_err() {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+    }
+    written
+}
@@ -0,0 +1,192 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use subxt::ext::codec::Encode;
+use subxt::ext::futures::future::BoxFuture;
+
+use crate::backend::{bytes_value, check_scalars, CallArgs, ProofBackend, ProofInputs, ScaleArgs};
+use crate::converter::{ConvertedProof, ProofType, read_raw_or_hex, to_hex_with_prefix};
+use crate::failure::FailureClass;
+
+/// Size of a Goldilocks field element, little-endian
+pub const FIELD_SIZE: usize = 8;
+
+/// Hasher the circuit was built with (`PoseidonGoldilocksConfig` or `KeccakGoldilocksConfig`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Plonky2Hash {
+    #[default]
+    Poseidon,
+    Keccak,
+}
+
+impl Plonky2Hash {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Plonky2Hash::Poseidon => "poseidon",
+            Plonky2Hash::Keccak => "keccak",
+        }
+    }
+
+    /// Name of the config in the pallet's `Plonky2Config` enum
+    pub fn variant(&self) -> &'static str {
+        match self {
+            Plonky2Hash::Poseidon => "Poseidon",
+            Plonky2Hash::Keccak => "Keccak",
+        }
+    }
+
+    // Index of the config in `Plonky2Config { Keccak, Poseidon }`
+    fn index(&self) -> u8 {
+        match self {
+            Plonky2Hash::Keccak => 0,
+            Plonky2Hash::Poseidon => 1,
+        }
+    }
+}
+
+impl fmt::Display for Plonky2Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Plonky2Hash {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "poseidon" => Ok(Plonky2Hash::Poseidon),
+            "keccak" => Ok(Plonky2Hash::Keccak),
+            _ => anyhow::bail!("Unknown Plonky2 hash '{}': expected poseidon or keccak", s),
+        }
+    }
+}
+
+/// How a Plonky2 proof is verified, recorded in the converted proof
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Plonky2Params {
+    #[serde(default)]
+    pub hash: Plonky2Hash,
+    /// The proof is a `CompressedProofWithPublicInputs`
+    #[serde(default)]
+    pub compressed: bool,
+}
+
+/// Split serialized `ProofWithPublicInputs` (or its compressed form) into the proof and its
+/// public inputs, which plonky2 writes last: a u32 count, then each element as a u64
+pub fn split_public_inputs(bytes: &[u8]) -> Result<(&[u8], &[u8])> {
+    // The count sits right before the elements it counts. Scanning from the largest
+    // candidate keeps small trailing elements from passing for a count of zero.
+    if bytes.len() >= 4 {
+        for count in (0..=(bytes.len() - 4) / FIELD_SIZE).rev() {
+            let start = bytes.len() - count * FIELD_SIZE;
+            let prefix = u32::from_le_bytes(bytes[start - 4..start].try_into()?);
+            if prefix as usize == count {
+                return Ok((&bytes[..start - 4], &bytes[start..]));
+            }
+        }
+    }
+    Err(FailureClass::BadInput.error("No public inputs found at the end of the Plonky2 proof: is it a serialized ProofWithPublicInputs?"))
+}
+
+/// Convert a Plonky2 proof written by `ProofWithPublicInputs::to_bytes`, with its circuit's
+/// verifier data, for zkVerify's Plonky2 pallet. The verifier data is either
+/// `VerifierCircuitData::to_bytes`, or `VerifierOnlyCircuitData::to_bytes` with
+/// `CommonCircuitData::to_bytes` in `common_data_path`; gates must be serialized as
+/// zkVerify's verifier expects. Files may be raw bytes or hex.
+pub async fn convert(
+    proof_path: &Path,
+    vk_path: &Path,
+    common_data_path: Option<&Path>,
+    params: Plonky2Params,
+) -> Result<ConvertedProof> {
+    let proof_with_pubs = read_raw_or_hex(proof_path).await?;
+    let (proof, pubs) = split_public_inputs(&proof_with_pubs)?;
+    let vk = read_vk(vk_path, common_data_path).await?;
+    if proof.is_empty() || vk.is_empty() {
+        return Err(FailureClass::BadInput.error("Empty Plonky2 proof or verifier data"));
+    }
+    status!(
+        "✅ Plonky2 {}proof ({} hash) with {} public inputs",
+        if params.compressed { "compressed " } else { "" },
+        params.hash,
+        pubs.len() / FIELD_SIZE
+    );
+
+    Ok(ConvertedProof { plonky2: Some(params), ..ConvertedProof::new(ProofType::Plonky2, proof, pubs, &vk) })
+}
+
+// `VerifierCircuitData` is the verifier-only data followed by the common data
+async fn read_vk(vk_path: &Path, common_data_path: Option<&Path>) -> Result<Vec<u8>> {
+    let mut vk = read_raw_or_hex(vk_path).await?;
+    if let Some(common_data_path) = common_data_path {
+        vk.extend(read_raw_or_hex(common_data_path).await?);
+    }
+    Ok(vk)
+}
+
+/// Plonky2 proofs for zkVerify's Plonky2 pallet
+pub struct Plonky2Backend;
+
+impl ProofBackend for Plonky2Backend {
+    fn proof_type(&self) -> ProofType {
+        ProofType::Plonky2
+    }
+
+    fn pallet(&self) -> &'static str {
+        "SettlementPlonky2Pallet"
+    }
+
+    fn convert<'a>(&'a self, inputs: &'a ProofInputs) -> BoxFuture<'a, Result<ConvertedProof>> {
+        Box::pin(async move {
+            convert(&inputs.proof, inputs.vk_file(ProofType::Plonky2)?, inputs.common_data.as_deref(), inputs.plonky2).await
+        })
+    }
+
+    fn derive_vk<'a>(&'a self, inputs: &'a ProofInputs) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let vk = read_vk(inputs.vk_file(ProofType::Plonky2)?, inputs.common_data.as_deref()).await?;
+            Ok(to_hex_with_prefix(&vk))
+        })
+    }
+
+    // Public inputs are 8-byte field elements; the proof and verifier data are only checked
+    // by the verifier
+    fn verify_locally(&self, converted_proof: &ConvertedProof) -> Result<()> {
+        let (proof, pubs, vk) = converted_proof.decode()?;
+        check_scalars(&pubs, FIELD_SIZE, ProofType::Plonky2)?;
+        if proof.is_empty() || vk.is_empty() {
+            return Err(FailureClass::BadInput.error("Empty Plonky2 proof or verifier data"));
+        }
+        Ok(())
+    }
+
+    fn call_args(&self, converted_proof: &ConvertedProof) -> Result<CallArgs> {
+        let params = converted_proof.plonky2.unwrap_or_default();
+        let (proof, pubs, vk) = converted_proof.decode()?;
+        Ok(CallArgs {
+            vk: subxt::dynamic::Value::named_composite(vec![
+                ("config", subxt::dynamic::Value::unnamed_variant(params.hash.variant(), vec![])),
+                ("bytes", bytes_value(&vk)),
+            ]),
+            proof: subxt::dynamic::Value::named_composite(vec![
+                ("compress", subxt::dynamic::Value::bool(params.compressed)),
+                ("bytes", bytes_value(&proof)),
+            ]),
+            pubs: bytes_value(&pubs),
+        })
+    }
+
+    fn encode_args(&self, converted_proof: &ConvertedProof) -> Result<ScaleArgs> {
+        let params = converted_proof.plonky2.unwrap_or_default();
+        let (proof, pubs, vk) = converted_proof.decode()?;
+        Ok(ScaleArgs {
+            vk: (params.hash.index(), vk).encode(),
+            proof: (params.compressed, proof).encode(),
+            pubs: pubs.encode(),
+        })
+    }
+}
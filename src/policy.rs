@@ -0,0 +1,62 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use std::path::Path;
+use tracing::info;
+
+use crate::converter::ConvertedProof;
+use crate::failure::FailureClass;
+
+/// Program VKs the router is permitted to submit in this environment
+#[derive(Debug, Clone)]
+pub struct VkAllowlist {
+    vks: HashSet<String>,
+}
+
+// VKs compare case-insensitively, with or without the 0x prefix
+fn normalize_vk(vk: &str) -> String {
+    format!("0x{}", vk.trim().trim_start_matches("0x").to_ascii_lowercase())
+}
+
+impl VkAllowlist {
+    pub fn new<I: IntoIterator<Item = S>, S: AsRef<str>>(vks: I) -> Self {
+        Self {
+            vks: vks.into_iter().map(|vk| normalize_vk(vk.as_ref())).collect(),
+        }
+    }
+
+    /// Load an allowlist file: one VK per line, `#` starts a comment
+    pub async fn load(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path).await?;
+        let allowlist = Self::new(
+            content
+                .lines()
+                .map(|line| line.split('#').next().unwrap_or("").trim())
+                .filter(|line| !line.is_empty()),
+        );
+        if allowlist.vks.is_empty() {
+            anyhow::bail!("VK allowlist {} is empty", path.display());
+        }
+        info!("Loaded {} allowed VKs from {}", allowlist.vks.len(), path.display());
+        Ok(allowlist)
+    }
+
+    pub fn is_allowed(&self, vk: &str) -> bool {
+        self.vks.contains(&normalize_vk(vk))
+    }
+
+    /// Refuse proofs whose program VK is not on the allowlist
+    pub fn check(&self, converted_proof: &ConvertedProof) -> Result<()> {
+        self.check_vk(&converted_proof.vk)
+    }
+
+    /// Refuse `vk` unless it is on the allowlist
+    pub fn check_vk(&self, vk: &str) -> Result<()> {
+        if !self.is_allowed(vk) {
+            return Err(FailureClass::BadInput.error(format!(
+                "Policy error: program VK {} is not in the submission allowlist",
+                vk
+            )));
+        }
+        Ok(())
+    }
+}
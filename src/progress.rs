@@ -0,0 +1,51 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressFinish, ProgressStyle};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Steps of a submission's confirmation: broadcast, in a block, finalized
+pub const CONFIRMATION_STEPS: u64 = 3;
+
+// Bars of concurrent jobs stack instead of drawing over each other. They go to stderr,
+// are hidden when it is not a terminal or with `--quiet`, and are cleared once dropped.
+fn bars() -> &'static MultiProgress {
+    static BARS: OnceLock<MultiProgress> = OnceLock::new();
+    BARS.get_or_init(|| match crate::output::is_quiet() {
+        true => MultiProgress::with_draw_target(ProgressDrawTarget::hidden()),
+        false => MultiProgress::new(),
+    })
+}
+
+fn style(template: &str) -> ProgressStyle {
+    ProgressStyle::with_template(template)
+        .expect("progress templates are valid")
+        .progress_chars("=> ")
+}
+
+fn add(bar: ProgressBar, message: String) -> ProgressBar {
+    let bar = bars().add(bar.with_message(message).with_finish(ProgressFinish::AndClear));
+    bar.enable_steady_tick(Duration::from_millis(120));
+    bar
+}
+
+/// Byte counts of a download of `len` bytes, or of unknown size
+pub fn download(message: String, len: Option<u64>) -> ProgressBar {
+    let bar = match len {
+        Some(len) => ProgressBar::new(len)
+            .with_style(style("{spinner} {msg} [{bar:30}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta} left)")),
+        None => ProgressBar::no_length().with_style(style("{spinner} {msg} {bytes} ({bytes_per_sec})")),
+    };
+    add(bar, message)
+}
+
+/// Spinner with the elapsed time, for work that reports no progress of its own
+pub fn spinner(message: String) -> ProgressBar {
+    add(ProgressBar::new_spinner().with_style(style("{spinner} {msg} [{elapsed}]")), message)
+}
+
+/// Steps a submission has gone through out of [`CONFIRMATION_STEPS`]
+pub fn confirmation(message: String) -> ProgressBar {
+    add(
+        ProgressBar::new(CONFIRMATION_STEPS).with_style(style("{spinner} [{bar:3}] {msg} [{elapsed}]")),
+        message,
+    )
+}
@@ -0,0 +1,249 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{info_span, warn, Instrument};
+
+use crate::client::ProofRequestMetadata;
+use crate::output::write_atomic;
+use crate::router::{Router, Submission};
+
+/// Pipeline stage a job failed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    Convert,
+    Submit,
+}
+
+/// How many times a stage is attempted, waiting `backoff` (doubling each time) in between
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, backoff: Duration) -> Self {
+        Self { max_attempts: max_attempts.max(1), backoff }
+    }
+
+    async fn run<T, F, Fut>(&self, stage: Stage, mut attempt: F) -> Result<T, JobFailure>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut delay = self.backoff;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match attempt().await {
+                Ok(value) => return Ok(value),
+                Err(error) if attempts >= self.max_attempts => return Err(JobFailure { stage, attempts, error }),
+                Err(e) => {
+                    warn!("{:?} attempt {}/{} failed ({:#}), retrying in {:?}", stage, attempts, self.max_attempts, e, delay);
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+            }
+        }
+    }
+}
+
+/// A fulfilled request waiting to be converted into `output` and submitted
+pub struct Job {
+    pub number: usize,
+    pub request_id: String,
+    pub metadata: ProofRequestMetadata,
+    pub output: PathBuf,
+    /// Domain to submit to instead of the router's
+    pub domain_id: Option<u32>,
+}
+
+/// Last error of a stage that ran out of attempts
+#[derive(Debug)]
+pub struct JobFailure {
+    pub stage: Stage,
+    pub attempts: u32,
+    pub error: anyhow::Error,
+}
+
+pub struct JobResult {
+    pub number: usize,
+    pub request_id: String,
+    /// `None` when the queue only converts
+    pub outcome: Result<Option<Submission>, JobFailure>,
+}
+
+// Written to the dead-letter directory for each permanently failed job
+#[derive(Serialize)]
+struct DeadLetter<'a> {
+    request_id: &'a str,
+    stage: Stage,
+    attempts: u32,
+    error: String,
+    output: &'a PathBuf,
+    failed_at: u64,
+}
+
+pub struct QueueOptions {
+    /// Jobs processed at the same time
+    pub workers: usize,
+    pub convert_retry: RetryPolicy,
+    pub submit_retry: RetryPolicy,
+    /// Permanently failed jobs are written here as `<request_id>.json`
+    pub dead_letter_dir: Option<PathBuf>,
+    /// Stop after converting, without submitting
+    pub convert_only: bool,
+}
+
+impl Default for QueueOptions {
+    /// One worker, each stage attempted once
+    fn default() -> Self {
+        Self {
+            workers: 1,
+            convert_retry: RetryPolicy::new(1, Duration::from_secs(5)),
+            submit_retry: RetryPolicy::new(1, Duration::from_secs(5)),
+            dead_letter_dir: None,
+            convert_only: false,
+        }
+    }
+}
+
+struct Worker {
+    router: Arc<Router>,
+    options: Arc<QueueOptions>,
+    jobs: Arc<Mutex<mpsc::UnboundedReceiver<Job>>>,
+    results: mpsc::UnboundedSender<JobResult>,
+    // Submissions sign from one account, so they go one at a time to keep nonces apart
+    submit_lock: Arc<Mutex<()>>,
+}
+
+impl Worker {
+    async fn run(self) {
+        loop {
+            let job = self.jobs.lock().await.recv().await;
+            let Some(job) = job else { return };
+
+            let outcome = self
+                .process(&job)
+                .instrument(info_span!("job", number = job.number, request_id = %job.request_id))
+                .await;
+            if let Err(failure) = &outcome
+                && let Err(e) = self.dead_letter(&job, failure).await
+            {
+                warn!("Failed to dead-letter {}: {:#}", job.request_id, e);
+            }
+            let result = JobResult { number: job.number, request_id: job.request_id, outcome };
+            if self.results.send(result).is_err() {
+                return;
+            }
+        }
+    }
+
+    async fn process(&self, job: &Job) -> Result<Option<Submission>, JobFailure> {
+        self.options
+            .convert_retry
+            .run(Stage::Convert, || self.router.convert(&job.request_id, &job.metadata, &job.output))
+            .await?;
+        if self.options.convert_only {
+            return Ok(None);
+        }
+        let submission = self
+            .options
+            .submit_retry
+            .run(Stage::Submit, || async {
+                let _guard = self.submit_lock.lock().await;
+                let domain_id = job.domain_id.or(self.router.domain_id());
+                self.router.submit_to_domain(&job.output, domain_id).await
+            })
+            .await?;
+        Ok(Some(submission))
+    }
+
+    async fn dead_letter(&self, job: &Job, failure: &JobFailure) -> Result<()> {
+        let Some(dir) = &self.options.dead_letter_dir else {
+            return Ok(());
+        };
+        let letter = DeadLetter {
+            request_id: &job.request_id,
+            stage: failure.stage,
+            attempts: failure.attempts,
+            error: format!("{:#}", failure.error),
+            output: &job.output,
+            failed_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default(),
+        };
+        tokio::fs::create_dir_all(dir).await?;
+        let path = dir.join(format!("{}.json", job.request_id));
+        write_atomic(&path, serde_json::to_string_pretty(&letter)?).await?;
+        status!("🪦 {} dead-lettered to {}", job.request_id, path.display());
+        Ok(())
+    }
+}
+
+/// Converts and submits jobs on a fixed number of workers, retrying each stage per its policy
+pub struct JobQueue {
+    jobs: mpsc::UnboundedSender<Job>,
+    results: mpsc::UnboundedReceiver<JobResult>,
+    workers: Vec<JoinHandle<()>>,
+    pending: usize,
+}
+
+impl JobQueue {
+    pub fn start(router: Arc<Router>, options: QueueOptions) -> Self {
+        let (jobs, job_receiver) = mpsc::unbounded_channel();
+        let (result_sender, results) = mpsc::unbounded_channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+        let submit_lock = Arc::new(Mutex::new(()));
+        let worker_count = options.workers.max(1);
+        let options = Arc::new(options);
+
+        let workers = (0..worker_count)
+            .map(|_| {
+                let worker = Worker {
+                    router: router.clone(),
+                    options: options.clone(),
+                    jobs: job_receiver.clone(),
+                    results: result_sender.clone(),
+                    submit_lock: submit_lock.clone(),
+                };
+                tokio::spawn(worker.run())
+            })
+            .collect();
+        Self { jobs, results, workers, pending: 0 }
+    }
+
+    pub fn push(&mut self, job: Job) {
+        // Workers only stop once the queue is dropped, so the receiver is still alive
+        if self.jobs.send(job).is_ok() {
+            self.pending += 1;
+        }
+    }
+
+    /// Jobs queued or in progress
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Wait for the next job to finish; pends forever while the queue is empty
+    pub async fn next_result(&mut self) -> JobResult {
+        if self.pending == 0 {
+            return std::future::pending().await;
+        }
+        let result = self.results.recv().await.expect("workers outlive the queue");
+        self.pending -= 1;
+        result
+    }
+}
+
+impl Drop for JobQueue {
+    fn drop(&mut self) {
+        for worker in &self.workers {
+            worker.abort();
+        }
+    }
+}
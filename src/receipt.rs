@@ -0,0 +1,78 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::cache::hash_file;
+use crate::output::write_atomic;
+use crate::router::Submission;
+use crate::substrate::SubmissionEvent;
+
+/// Where a proof landed on zkVerify, written next to it as `<proof>.receipt.json` once its
+/// submission is finalized. It is also a receipt `status --receipt` reads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionReceipt {
+    pub request_id: Option<String>,
+    /// Hex SHA-256 of the artifact the proof was converted from, when known
+    pub artifact_sha256: Option<String>,
+    pub proof_path: PathBuf,
+    pub tx_hash: String,
+    pub block_number: Option<u32>,
+    pub block_hash: Option<String>,
+    /// Fee charged for the extrinsic, tip included, in planck; unknown through the relayer
+    pub fee_paid: Option<u128>,
+    pub domain_id: Option<u32>,
+    pub aggregation_id: Option<u64>,
+    #[serde(default)]
+    pub events: Vec<SubmissionEvent>,
+    /// Unix timestamp, in seconds
+    pub written_at: u64,
+}
+
+impl SubmissionReceipt {
+    pub fn new(
+        request_id: Option<&str>,
+        artifact_sha256: Option<&str>,
+        proof_path: &Path,
+        domain_id: Option<u32>,
+        submission: &Submission,
+    ) -> Self {
+        let fee_paid = submission.events.iter().find_map(|event| match event {
+            SubmissionEvent::TransactionFeePaid { actual_fee, .. } => Some(*actual_fee),
+            _ => None,
+        });
+        Self {
+            request_id: request_id.map(str::to_string),
+            artifact_sha256: artifact_sha256.map(str::to_string),
+            proof_path: proof_path.to_path_buf(),
+            tx_hash: submission.tx_hash.clone(),
+            block_number: submission.block_number,
+            block_hash: submission.block_hash.clone(),
+            fee_paid,
+            // A domain is only known to have been used when the proof was queued into it
+            domain_id: domain_id.filter(|_| submission.aggregation_id.is_some()),
+            aggregation_id: submission.aggregation_id,
+            events: submission.events.clone(),
+            written_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or_default(),
+        }
+    }
+
+    /// Where the receipt of the proof at `proof_path` is written, e.g. `proof.receipt.json`
+    pub fn path_for(proof_path: &Path) -> PathBuf {
+        proof_path.with_extension("receipt.json")
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        write_atomic(path, serde_json::to_string_pretty(self)?)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// Hex SHA-256 of a local artifact, as recorded in receipts
+pub async fn artifact_sha256(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hash_file(path, &mut hasher).await?;
+    Ok(hex::encode(hasher.finalize()))
+}
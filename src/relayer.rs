@@ -0,0 +1,205 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use subxt::utils::H256;
+use tracing::{debug, info};
+
+use crate::aggregation::{AggregationReceipt, MerkleProof};
+use crate::converter::{ConvertedProof, ProofType};
+use crate::failure::FailureClass;
+use crate::network::{HttpClient, HttpPolicy};
+
+pub const DEFAULT_RELAYER_URL: &str = "https://relayer-api.horizenlabs.io/api/v1";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProofData<'a> {
+    proof: &'a str,
+    public_signals: &'a str,
+    vk: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SubmitProofRequest<'a> {
+    proof_type: &'static str,
+    vk_registered: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    domain_id: Option<u32>,
+    proof_data: ProofData<'a>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubmitProofResponse {
+    job_id: String,
+    #[serde(default)]
+    optimistic_verify: Option<String>,
+}
+
+/// Aggregation data attached to a job once it reaches `Aggregated`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregationDetails {
+    pub receipt: H256,
+    pub receipt_block_hash: H256,
+    pub root: H256,
+    pub leaf: H256,
+    pub leaf_index: u32,
+    pub number_of_leaves: u32,
+    pub merkle_proof: Vec<H256>,
+}
+
+/// Status of a relayer job, as reported by `job-status`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStatus {
+    pub job_id: String,
+    pub status: String,
+    #[serde(default)]
+    pub tx_hash: Option<String>,
+    #[serde(default)]
+    pub block_hash: Option<String>,
+    #[serde(default)]
+    pub statement: Option<String>,
+    #[serde(default)]
+    pub aggregation_id: Option<u64>,
+    #[serde(default)]
+    pub error_details: Option<String>,
+    #[serde(default)]
+    pub aggregation_details: Option<AggregationDetails>,
+}
+
+impl JobStatus {
+    /// Whether the relayer will not report any further progress for this job
+    pub fn is_terminal(&self, wait_for_aggregation: bool) -> bool {
+        match self.status.as_str() {
+            "Failed" | "Aggregated" => true,
+            "Finalized" => !wait_for_aggregation,
+            _ => false,
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.status == "Failed"
+    }
+
+    /// Same receipt the direct backend produces, once the job is aggregated
+    pub fn aggregation_receipt(&self, domain_id: u32) -> Option<AggregationReceipt> {
+        let details = self.aggregation_details.as_ref()?;
+        let statement = self.statement.as_deref()?.parse().ok()?;
+        Some(AggregationReceipt {
+            domain_id,
+            aggregation_id: self.aggregation_id?,
+            statement,
+            receipt: details.receipt,
+            receipt_block_hash: details.receipt_block_hash,
+            merkle_path: MerkleProof {
+                root: details.root,
+                proof: details.merkle_proof.clone(),
+                number_of_leaves: details.number_of_leaves,
+                leaf_index: details.leaf_index,
+                leaf: details.leaf,
+            },
+        })
+    }
+}
+
+/// Client for the hosted zkVerify relayer, which submits proofs on the caller's behalf
+pub struct RelayerClient {
+    client: HttpClient,
+    base_url: String,
+    api_key: String,
+    poll_interval: Duration,
+}
+
+impl RelayerClient {
+    pub fn new(base_url: &str, api_key: &str, poll_interval: Duration) -> Self {
+        Self {
+            client: HttpClient::default(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            poll_interval,
+        }
+    }
+
+    /// Send relayer API calls under `policy` instead of the default (no retries); submissions
+    /// themselves are never resent, so a proof is not submitted twice
+    pub fn with_http_policy(mut self, policy: HttpPolicy) -> Result<Self> {
+        self.client = HttpClient::new(policy)?;
+        Ok(self)
+    }
+
+    /// Submit a converted proof, returning the relayer job id
+    pub async fn submit_proof(&self, converted_proof: &ConvertedProof, domain_id: Option<u32>) -> Result<String> {
+        if converted_proof.proof_type != ProofType::Sp1 {
+            return Err(FailureClass::BadInput.error(format!(
+                "The relayer backend only submits SP1 proofs, not {}: use --backend direct",
+                converted_proof.proof_type
+            )));
+        }
+        let request = SubmitProofRequest {
+            proof_type: "sp1",
+            vk_registered: false,
+            domain_id,
+            proof_data: ProofData {
+                proof: &converted_proof.proof,
+                public_signals: &converted_proof.pubs,
+                vk: &converted_proof.vk,
+            },
+        };
+
+        let url = format!("{}/submit-proof/{}", self.base_url, self.api_key);
+        // A resent submission after a timeout or 5xx could be accepted twice and paid twice
+        let response = self.client.send_once(|client| client.post(&url).json(&request)).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            let class = if status.is_server_error() { FailureClass::Network } else { FailureClass::ChainRejected };
+            return Err(class.error(format!("Relayer rejected the proof ({}): {}", status, body)));
+        }
+
+        let response: SubmitProofResponse = response.json().await?;
+        info!(
+            "Relayer accepted job {} (optimistic verification: {})",
+            response.job_id,
+            response.optimistic_verify.as_deref().unwrap_or("unknown")
+        );
+        Ok(response.job_id)
+    }
+
+    pub async fn job_status(&self, job_id: &str) -> Result<JobStatus> {
+        let url = format!("{}/job-status/{}/{}", self.base_url, self.api_key, job_id);
+        let response = self.client.send(|client| client.get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(FailureClass::Network.error(format!("Failed to fetch relayer job status: {}", response.status())));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Poll a job until it is finalized (or aggregated, if requested) or fails
+    pub async fn wait_for_job(&self, job_id: &str, wait_for_aggregation: bool) -> Result<JobStatus> {
+        let mut last_status = String::new();
+        loop {
+            let status = self.job_status(job_id).await?;
+            if status.status != last_status {
+                status!("📡 Relayer job {}: {}", job_id, status.status);
+                last_status = status.status.clone();
+            }
+
+            if status.is_terminal(wait_for_aggregation) {
+                if status.is_failed() {
+                    return Err(FailureClass::ChainRejected.error(format!(
+                        "Relayer job {} failed: {}",
+                        job_id,
+                        status.error_details.as_deref().unwrap_or("no details")
+                    )));
+                }
+                return Ok(status);
+            }
+
+            debug!("Job {} not done yet, polling again in {:?}", job_id, self.poll_interval);
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subxt::utils::H256;
+
+/// Start of every chunk remark, followed by `<id>:<index>/<total>:` and the chunk's bytes
+pub const CHUNK_PREFIX: &[u8] = b"rusty_router:chunk:";
+
+/// Version of the manifest remark format
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Remark sent after the chunks of a file too large for one remark, listing them in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkManifest {
+    /// Manifest format version; identifies the remark as a manifest
+    pub rusty_router_chunks: u32,
+    /// Name of the file the chunks were cut from
+    pub file_name: String,
+    /// Size of the whole file
+    pub size: usize,
+    /// Hex SHA-256 of the whole file
+    pub sha256: String,
+    pub chunks: Vec<ChunkRef>,
+}
+
+/// Where a chunk remark landed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkRef {
+    pub tx_hash: H256,
+    pub block_hash: H256,
+}
+
+impl ChunkManifest {
+    /// The manifest a remark holds, if it is one
+    pub fn parse(remark: &[u8]) -> Option<Self> {
+        serde_json::from_slice::<Self>(remark)
+            .ok()
+            .filter(|manifest| manifest.rusty_router_chunks == MANIFEST_VERSION)
+    }
+}
+
+// Chunks carry the first 8 bytes of the file's SHA-256, so chunks of another file are told apart
+fn file_id(sha256: &str) -> &str {
+    sha256.get(..16).unwrap_or(sha256)
+}
+
+/// Hex SHA-256 of a file's content, as in its manifest
+pub fn content_sha256(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Cut `data` into numbered chunk remarks of at most `max_remark` bytes each, header included
+pub fn split(data: &[u8], max_remark: usize) -> Result<Vec<Vec<u8>>> {
+    let sha256 = content_sha256(data);
+    // Headers grow with the chunk numbers, so size the chunks for the largest header possible
+    let header_len = |total: usize| chunk_header(file_id(&sha256), total, total).len();
+    let mut total = 1;
+    loop {
+        let payload = max_remark.saturating_sub(header_len(total));
+        if payload == 0 {
+            anyhow::bail!("Remarks of {} bytes are too small to carry chunks", max_remark);
+        }
+        let needed = data.len().div_ceil(payload).max(1);
+        if needed <= total {
+            return Ok(data
+                .chunks(payload)
+                .enumerate()
+                .map(|(index, chunk)| [chunk_header(file_id(&sha256), index + 1, needed).as_slice(), chunk].concat())
+                .collect());
+        }
+        total = needed;
+    }
+}
+
+fn chunk_header(id: &str, index: usize, total: usize) -> Vec<u8> {
+    [CHUNK_PREFIX, format!("{}:{}/{}:", id, index, total).as_bytes()].concat()
+}
+
+/// The file id, chunk number (from 1), chunk count and bytes of a chunk remark
+pub fn parse_chunk(remark: &[u8]) -> Result<(&str, usize, usize, &[u8])> {
+    let invalid = || anyhow::anyhow!("Remark is not a rusty_router chunk");
+    let rest = remark.strip_prefix(CHUNK_PREFIX).ok_or_else(invalid)?;
+    let mut parts = rest.splitn(3, |byte| *byte == b':');
+    let id = std::str::from_utf8(parts.next().ok_or_else(invalid)?)?;
+    let numbers = std::str::from_utf8(parts.next().ok_or_else(invalid)?)?;
+    let data = parts.next().ok_or_else(invalid)?;
+    let (index, total) = numbers.split_once('/').ok_or_else(invalid)?;
+    Ok((id, index.parse()?, total.parse()?, data))
+}
+
+/// Put the file of `manifest` back together from its chunk remarks, in manifest order,
+/// checking their numbering and the file's SHA-256
+pub fn reassemble(manifest: &ChunkManifest, remarks: &[Vec<u8>]) -> Result<Vec<u8>> {
+    let total = manifest.chunks.len();
+    let mut data = Vec::new();
+    for (position, remark) in remarks.iter().enumerate() {
+        let (id, index, chunk_total, chunk) = parse_chunk(remark)?;
+        if id != file_id(&manifest.sha256) || index != position + 1 || chunk_total != total {
+            anyhow::bail!(
+                "Chunk {} of {} is chunk {}/{} of file {}, not of {}",
+                position + 1,
+                total,
+                index,
+                chunk_total,
+                id,
+                file_id(&manifest.sha256)
+            );
+        }
+        data.extend_from_slice(chunk);
+    }
+    if data.len() != manifest.size || content_sha256(&data) != manifest.sha256 {
+        anyhow::bail!("Reassembled {} does not match the SHA-256 of its manifest", manifest.file_name);
+    }
+    Ok(data)
+}
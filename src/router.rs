@@ -0,0 +1,831 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempfile::NamedTempFile;
+use tokio::sync::Semaphore;
+use tracing::{info, info_span, instrument, warn, Instrument};
+
+use crate::abi::PubsAbi;
+use crate::backend::{ProofBackend, ProofInputs};
+use crate::cache::{hash_file, ArtifactCache};
+use crate::client::{ProofClient, ProofRequestMetadata, DEFAULT_API_BASE};
+use crate::converter::{compress, ConvertedProof, OutputFormat, ProofConverter, ProofType};
+use crate::extract::RequestDetails;
+use crate::failure::{FailureClass, ResultExt};
+use crate::history::History;
+use crate::ipfs::IpfsClient;
+use crate::network::{HttpPolicy, RpcPolicy};
+use crate::output::write_atomic;
+use crate::policy::VkAllowlist;
+use crate::progress;
+use crate::receipt::SubmissionReceipt;
+use crate::secret::SecretString;
+use crate::relayer::RelayerClient;
+use crate::stats::{ProofStats, StatsRecorder};
+use crate::substrate::{ExtrinsicLookup, SubmissionEvent, SubstrateClient, TxEvent, TxOptions};
+
+/// Where converted proofs are sent
+pub enum Submitter {
+    /// Sign and submit with our own account
+    Direct(Box<SubstrateClient>),
+    /// Hand the proof to the hosted zkVerify relayer
+    Relayer(Box<RelayerClient>),
+}
+
+/// Where a routed proof landed on zkVerify
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Submission {
+    pub tx_hash: String,
+    pub block_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u32>,
+    /// Aggregation the proof was queued into, when submitted to a domain
+    pub aggregation_id: Option<u64>,
+    /// Events of the extrinsic, when it was submitted and waited for with our own account
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<SubmissionEvent>,
+}
+
+/// Fetch, convert and submit pipeline for Succinct proof requests
+pub struct Router {
+    client: ProofClient,
+    converter: ProofConverter,
+    submitter: Option<Submitter>,
+    domain_id: Option<u32>,
+    vk_allowlist: Option<VkAllowlist>,
+    details_output: Option<PathBuf>,
+    pubs_abi: Option<PubsAbi>,
+    intermediate_dir: Option<PathBuf>,
+    history: Option<Arc<History>>,
+    // Bounds the conversions running at once; downloads and submissions are not limited
+    conversion_slots: Option<Semaphore>,
+    cache: Option<ArtifactCache>,
+    archive: Option<IpfsClient>,
+    output_format: OutputFormat,
+    compress: bool,
+    force_vk: bool,
+    stats: Option<Arc<StatsRecorder>>,
+}
+
+/// How often a running conversion reports that it is still alive
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+// Elapsed time, plus an ETA when earlier conversions give an idea of how long it takes
+fn heartbeat(request_id: &str, elapsed: Duration, expected: Option<Duration>) -> String {
+    match expected {
+        Some(expected) if expected > elapsed => format!(
+            "{}: converting for {} (usually ~{}, about {} left)",
+            request_id,
+            format_duration(elapsed),
+            format_duration(expected),
+            format_duration(expected - elapsed)
+        ),
+        Some(expected) => format!(
+            "{}: converting for {}, longer than the usual ~{}",
+            request_id,
+            format_duration(elapsed),
+            format_duration(expected)
+        ),
+        None => format!("{}: converting for {}", request_id, format_duration(elapsed)),
+    }
+}
+
+// SHA-256 of a local artifact, refusing it when it differs from `expected_sha256`
+async fn local_sha256(artifact_path: &Path, expected_sha256: Option<&str>) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hash_file(artifact_path, &mut hasher)
+        .await
+        .map_err(|e| FailureClass::BadInput.error(format!("Failed to read {}: {}", artifact_path.display(), e)))?;
+    let artifact_sha256 = hex::encode(hasher.finalize());
+    if let Some(expected) = expected_sha256
+        && !expected.trim_start_matches("0x").eq_ignore_ascii_case(&artifact_sha256)
+    {
+        return Err(FailureClass::BadInput.error(format!(
+            "Refusing to convert {}: SHA-256 {} does not match the expected {}",
+            artifact_path.display(),
+            artifact_sha256,
+            expected
+        )));
+    }
+    status!("🔒 Artifact SHA-256: {}", artifact_sha256);
+    Ok(artifact_sha256)
+}
+
+/// Backend the [`RouterBuilder`] connects
+enum BackendConfig {
+    Direct { ws_url: String, mnemonic: SecretString, tx_options: TxOptions },
+    Relayer { base_url: String, api_key: String, poll_interval: Duration },
+}
+
+/// Builds a [`Router`] whose clients follow the given network policies. Everything else is
+/// set with the `with_*` methods of the built router.
+pub struct RouterBuilder {
+    api_base: String,
+    verbose: bool,
+    http_policy: HttpPolicy,
+    rpc_policy: RpcPolicy,
+    backend: Option<BackendConfig>,
+}
+
+impl RouterBuilder {
+    /// Succinct explorer base URL
+    pub fn with_api_base(mut self, api_base: &str) -> Self {
+        self.api_base = api_base.to_string();
+        self
+    }
+
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Applies to the explorer, artifact downloads and the relayer
+    pub fn with_http_policy(mut self, http_policy: HttpPolicy) -> Self {
+        self.http_policy = http_policy;
+        self
+    }
+
+    /// Applies to the zkVerify node connection and its read-only queries
+    pub fn with_rpc_policy(mut self, rpc_policy: RpcPolicy) -> Self {
+        self.rpc_policy = rpc_policy;
+        self
+    }
+
+    /// Sign and submit with the account of `mnemonic` through the node at `ws_url`
+    pub fn with_direct_submitter(mut self, ws_url: &str, mnemonic: &str, tx_options: TxOptions) -> Self {
+        self.backend = Some(BackendConfig::Direct {
+            ws_url: ws_url.to_string(),
+            mnemonic: SecretString::new(mnemonic),
+            tx_options,
+        });
+        self
+    }
+
+    /// Hand proofs to the hosted relayer
+    pub fn with_relayer_submitter(mut self, base_url: &str, api_key: &str, poll_interval: Duration) -> Self {
+        self.backend = Some(BackendConfig::Relayer {
+            base_url: base_url.to_string(),
+            api_key: api_key.to_string(),
+            poll_interval,
+        });
+        self
+    }
+
+    /// Create the clients, connecting to the node for the direct backend
+    pub async fn build(self) -> Result<Router> {
+        let client = ProofClient::new_with_options(&self.api_base, self.verbose).with_http_policy(self.http_policy.clone())?;
+        let mut router = Router::new(client);
+        router.submitter = match self.backend {
+            Some(BackendConfig::Direct { ws_url, mnemonic, tx_options }) => {
+                let substrate_client = SubstrateClient::new_with_policy(&ws_url, mnemonic.expose(), self.rpc_policy)
+                    .await?
+                    .with_tx_options(tx_options);
+                Some(Submitter::Direct(Box::new(substrate_client)))
+            }
+            Some(BackendConfig::Relayer { base_url, api_key, poll_interval }) => Some(Submitter::Relayer(Box::new(
+                RelayerClient::new(&base_url, &api_key, poll_interval).with_http_policy(self.http_policy)?,
+            ))),
+            None => None,
+        };
+        Ok(router)
+    }
+}
+
+impl Router {
+    /// Configure the network policies and backend before the clients are created
+    pub fn builder() -> RouterBuilder {
+        RouterBuilder {
+            api_base: DEFAULT_API_BASE.to_string(),
+            verbose: false,
+            http_policy: HttpPolicy::default(),
+            rpc_policy: RpcPolicy::default(),
+            backend: None,
+        }
+    }
+
+    pub fn new(client: ProofClient) -> Self {
+        Self {
+            client,
+            converter: ProofConverter::new(),
+            submitter: None,
+            domain_id: None,
+            vk_allowlist: None,
+            details_output: None,
+            pubs_abi: None,
+            intermediate_dir: None,
+            history: None,
+            conversion_slots: None,
+            cache: None,
+            archive: None,
+            output_format: OutputFormat::default(),
+            compress: false,
+            force_vk: false,
+            stats: None,
+        }
+    }
+
+    pub fn with_submitter(mut self, submitter: Submitter) -> Self {
+        self.submitter = Some(submitter);
+        self
+    }
+
+    /// Domain proofs are queued into for aggregation
+    pub fn with_domain_id(mut self, domain_id: Option<u32>) -> Self {
+        self.domain_id = domain_id;
+        self
+    }
+
+    /// Only submit proofs of these programs
+    pub fn with_vk_allowlist(mut self, vk_allowlist: Option<VkAllowlist>) -> Self {
+        self.vk_allowlist = vk_allowlist;
+        self
+    }
+
+    /// Also save detailed information about each downloaded artifact here
+    pub fn with_details_output(mut self, details_output: Option<PathBuf>) -> Self {
+        self.details_output = details_output;
+        self
+    }
+
+    /// Decode the public values with this schema in the detailed information
+    pub fn with_pubs_abi(mut self, pubs_abi: Option<PubsAbi>) -> Self {
+        self.pubs_abi = pubs_abi;
+        self
+    }
+
+    /// Also write each proof's components (shrunk proof, public values, VK) as separate files here
+    pub fn with_intermediate_dir(mut self, intermediate_dir: Option<PathBuf>) -> Self {
+        self.intermediate_dir = intermediate_dir;
+        self
+    }
+
+    /// Record conversions and submissions in this job history
+    pub fn with_history(mut self, history: Option<Arc<History>>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Run at most `jobs` conversions at the same time when the router is shared between tasks
+    pub fn with_conversion_jobs(mut self, jobs: Option<usize>) -> Self {
+        self.conversion_slots = jobs.map(|jobs| Semaphore::new(jobs.max(1)));
+        self
+    }
+
+    /// Reuse explorer metadata and artifacts cached here, and cache new ones
+    pub fn with_cache(mut self, cache: Option<ArtifactCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Pin each artifact and converted proof to this IPFS node once converted
+    pub fn with_archive(mut self, archive: Option<IpfsClient>) -> Self {
+        self.archive = archive;
+        self
+    }
+
+    /// Write converted proofs as `output_format`; SCALE proofs cannot be submitted
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    /// Convert with the explorer's VK even when the artifact carries a different one
+    pub fn with_force_vk(mut self, force_vk: bool) -> Self {
+        self.force_vk = force_vk;
+        self
+    }
+
+    /// Compress converted proofs with zstd
+    pub fn with_compression(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Record the sizes and timing of each conversion here
+    pub fn with_stats(mut self, stats: Option<Arc<StatsRecorder>>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    pub fn domain_id(&self) -> Option<u32> {
+        self.domain_id
+    }
+
+    pub fn history(&self) -> Option<&History> {
+        self.history.as_deref()
+    }
+
+    /// Whether the history records the request as finalized or in flight. A history that
+    /// cannot be read only costs the skip: the request is then treated as not routed.
+    pub fn already_routed(&self, request_id: &str) -> bool {
+        let Some(history) = self.history() else {
+            return false;
+        };
+        history.is_routed(request_id).unwrap_or_else(|e| {
+            warn!("Could not check history for request {}: {:#}", request_id, e);
+            false
+        })
+    }
+
+    pub fn client(&self) -> &ProofClient {
+        &self.client
+    }
+
+    /// Name of a request's converted proof in an output directory, e.g. `<request_id>.json`
+    /// or `<request_id>.cbor.zst`
+    pub fn output_file_name(&self, request_id: &str) -> String {
+        match self.compress {
+            true => format!("{}.{}.zst", request_id, self.output_format),
+            false => format!("{}.{}", request_id, self.output_format),
+        }
+    }
+
+    /// Signing client of the direct backend, if that is the configured submitter
+    pub fn substrate_client(&self) -> Option<&SubstrateClient> {
+        match &self.submitter {
+            Some(Submitter::Direct(substrate_client)) => Some(substrate_client),
+            _ => None,
+        }
+    }
+
+    /// Fetch a request's explorer page, then download and convert its proof into `output`
+    #[instrument(skip(self, output))]
+    pub async fn convert_request(&self, request_id: &str, output: &Path) -> Result<ConvertedProof> {
+        status!("🌐 Loading explorer page for request: {}", request_id);
+        let metadata = self.fetch_metadata(request_id).await;
+        self.client.close_browser().await;
+        self.convert(request_id, &metadata?, output).await
+    }
+
+    /// Explorer metadata of a request, from the cache if it has been fetched before
+    pub async fn fetch_metadata(&self, request_id: &str) -> Result<ProofRequestMetadata> {
+        if let Some(cache) = &self.cache
+            && let Some(metadata) = cache.metadata(request_id).await
+        {
+            return Ok(metadata);
+        }
+        let metadata = self
+            .client
+            .fetch_request_metadata(request_id)
+            .instrument(info_span!("fetch_metadata"))
+            .await?;
+        // The metadata is fetched either way; failing to cache it only costs a later run a fetch
+        if let Some(cache) = &self.cache
+            && let Err(e) = cache.store_metadata(request_id, &metadata).await
+        {
+            warn!("Failed to cache the metadata of {}: {:#}", request_id, e);
+        }
+        Ok(metadata)
+    }
+
+    /// [`Self::fetch_metadata`], checking again every `poll_interval` for up to `timeout` while
+    /// the request has no artifact yet because it is still being proven. Failures other than
+    /// network ones end the wait at once, as does a request the explorer shows as unfulfillable.
+    pub async fn wait_for_metadata(
+        &self,
+        request_id: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<ProofRequestMetadata> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.fetch_metadata(request_id).await {
+                Err(e) if FailureClass::of(&e) == FailureClass::Network => {
+                    if Instant::now() + poll_interval > deadline {
+                        return Err(FailureClass::Network.error(format!(
+                            "Request {} still not fulfilled after {:?}: {:#}",
+                            request_id, timeout, e
+                        )));
+                    }
+                    info!("Request {} not ready: {:#}", request_id, e);
+                    status!("⏳ Request {} not fulfilled yet, checking again in {:?}", request_id, poll_interval);
+                    tokio::time::sleep(poll_interval).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Download and convert the proof of an already fetched request into `output`
+    #[instrument(skip(self, metadata, output))]
+    pub async fn convert(
+        &self,
+        request_id: &str,
+        metadata: &ProofRequestMetadata,
+        output: &Path,
+    ) -> Result<ConvertedProof> {
+        let cached = match &self.cache {
+            Some(cache) => cache.artifact(request_id).await,
+            None => None,
+        };
+        // Without a cache the artifact goes to a temporary file, removed once converted
+        let mut temp_file = None;
+        let from_cache = cached.is_some();
+        let (mut artifact_path, artifact_sha256) = match cached {
+            Some((path, sha256)) => {
+                status!("📦 Using cached proof artifact {}", path.display());
+                (path, sha256)
+            }
+            None => {
+                status!("📦 Downloading proof artifact...");
+                let path = match &self.cache {
+                    Some(cache) => cache.partial_path(request_id),
+                    None => temp_file.insert(NamedTempFile::new()?).path().to_path_buf(),
+                };
+                let downloaded = self
+                    .client
+                    .download_artifact(&metadata.artifact_url, &path)
+                    .instrument(info_span!("download_artifact"))
+                    .await?;
+                (path, downloaded.sha256)
+            }
+        };
+        if let Some(expected) = &metadata.artifact_sha256
+            && !expected.trim_start_matches("0x").eq_ignore_ascii_case(&artifact_sha256)
+        {
+            if !from_cache {
+                tokio::fs::remove_file(&artifact_path).await.ok();
+            }
+            // The expected checksum comes from the user, so downloading again would not help
+            return Err(FailureClass::BadInput.error(format!(
+                "Refusing to convert {}: artifact SHA-256 {} does not match the expected {}",
+                request_id, artifact_sha256, expected
+            )));
+        }
+        status!("🔒 Artifact SHA-256: {}", artifact_sha256);
+        if !from_cache
+            && let Some(cache) = &self.cache
+        {
+            match cache.store_artifact(request_id, &artifact_path, &artifact_sha256).await {
+                Ok(cached) => artifact_path = cached,
+                Err(e) => warn!("Failed to cache the artifact of {}: {:#}", request_id, e),
+            }
+        }
+
+        let inputs = ProofInputs {
+            vk: Some(metadata.vk.clone()),
+            force_vk: self.force_vk,
+            ..ProofInputs::new(&artifact_path)
+        };
+        let converted_proof = self
+            .convert_artifact(Some(request_id), Some(&metadata.details), ProofType::Sp1.backend(), &inputs, &artifact_sha256, output)
+            .await?;
+
+        // Explicitly clean up the temporary file
+        drop(temp_file);
+
+        Ok(converted_proof)
+    }
+
+    /// Convert a proof of `proof_type` already on disk into `output`, without the explorer.
+    /// An SP1 VK is read from the artifact unless given.
+    #[instrument(skip(self, inputs, output))]
+    pub async fn convert_local(
+        &self,
+        proof_type: ProofType,
+        inputs: &ProofInputs,
+        expected_sha256: Option<&str>,
+        output: &Path,
+    ) -> Result<ConvertedProof> {
+        let artifact_sha256 = local_sha256(&inputs.proof, expected_sha256).await?;
+        self.convert_artifact(None, None, proof_type.backend(), inputs, &artifact_sha256, output).await
+    }
+
+    // Convert the proof of `inputs` with `backend`, recording it under `request_id` if it has
+    // one, with the request's explorer details
+    async fn convert_artifact(
+        &self,
+        request_id: Option<&str>,
+        details: Option<&RequestDetails>,
+        backend: &dyn ProofBackend,
+        inputs: &ProofInputs,
+        artifact_sha256: &str,
+        output: &Path,
+    ) -> Result<ConvertedProof> {
+        let artifact_path = inputs.proof.as_path();
+        let label = match request_id {
+            Some(request_id) => request_id.to_string(),
+            None => artifact_path.display().to_string(),
+        };
+        let slot = match &self.conversion_slots {
+            Some(slots) => Some(match slots.try_acquire() {
+                Ok(slot) => slot,
+                Err(_) => {
+                    status!("⏳ Waiting for a free conversion slot...");
+                    slots.acquire().await?
+                }
+            }),
+            None => None,
+        };
+        status!("🔄 Converting proof to zkVerify format...");
+        let expected = match &self.history {
+            Some(history) => history.average_conversion_time()?,
+            None => None,
+        };
+        let started = Instant::now();
+        let conversion = backend
+            .convert(inputs)
+            .instrument(info_span!("conversion", proof_type = %backend.proof_type()));
+        tokio::pin!(conversion);
+        // Conversions report no progress of their own, so show that the conversion is still alive:
+        // as a spinner on a terminal, as periodic heartbeat lines otherwise
+        let spinner = progress::spinner(match expected {
+            Some(expected) => format!("{}: converting (usually ~{})", label, format_duration(expected)),
+            None => format!("{}: converting", label),
+        });
+        let mut heartbeat_interval = tokio::time::interval_at((started + HEARTBEAT_INTERVAL).into(), HEARTBEAT_INTERVAL);
+        let mut converted_proof = loop {
+            tokio::select! {
+                result = &mut conversion => break result.classify(FailureClass::Conversion)?,
+                _ = heartbeat_interval.tick() => {
+                    let message = heartbeat(&label, started.elapsed(), expected);
+                    if spinner.is_hidden() {
+                        status!("⏳ {}", message);
+                    } else {
+                        spinner.set_message(message);
+                    }
+                }
+            }
+        };
+        drop(spinner);
+        let conversion_time = started.elapsed();
+        drop(slot);
+        status!("⏱️  Converted in {}", format_duration(conversion_time));
+        converted_proof.request_id = request_id.map(str::to_string);
+
+        status!("💾 Saving converted proof...");
+        let mut content = converted_proof.to_bytes(self.output_format)?;
+        if self.compress {
+            content = compress(&content)?;
+        }
+        let converted_bytes = content.len();
+        write_atomic(output, content).await?;
+
+        status!("✅ Conversion successful: {}", output.display());
+        if let (Some(history), Some(request_id)) = (&self.history, request_id) {
+            history.record_conversion(request_id, artifact_sha256, output, conversion_time, details)?;
+        }
+        if let Some(stats) = &self.stats {
+            match ProofStats::collect(&label, artifact_path, &converted_proof, converted_bytes, conversion_time) {
+                Ok(proof_stats) => stats.record(proof_stats),
+                Err(e) => warn!("Failed to collect proof stats: {:#}", e),
+            }
+        }
+
+        self.archive_and_split(request_id, artifact_path, artifact_sha256, &converted_proof, output).await?;
+
+        // Also save detailed proof information if requested
+        if let Some(details_output) = &self.details_output
+            && backend.proof_type() == ProofType::Sp1
+        {
+            info!("Extracting detailed proof information...");
+            self.converter
+                .save_detailed_proof_info(artifact_path, details, self.pubs_abi.as_ref(), details_output)
+                .await?;
+            info!("Detailed proof information saved to {}", details_output.display());
+        }
+
+        Ok(converted_proof)
+    }
+
+    // Pin the artifact and the saved proof to IPFS and write out the proof's components,
+    // when configured
+    async fn archive_and_split(
+        &self,
+        request_id: Option<&str>,
+        artifact_path: &Path,
+        artifact_sha256: &str,
+        converted_proof: &ConvertedProof,
+        output: &Path,
+    ) -> Result<()> {
+        // The proof is saved by now, so an archive that fails does not fail the conversion
+        if let Some(archive) = &self.archive {
+            status!("📌 Pinning artifact and proof to IPFS...");
+            match archive.archive(request_id, artifact_path, artifact_sha256, output).await {
+                Ok(receipt) => {
+                    status!("📌 Pinned: artifact {}, proof {}", receipt.artifact_cid, receipt.proof_cid);
+                    if let (Some(history), Some(request_id)) = (&self.history, request_id)
+                        && let Err(e) = history.record_archive(request_id, &receipt.artifact_cid, &receipt.proof_cid)
+                    {
+                        warn!("Failed to update history: {:#}", e);
+                    }
+                }
+                Err(e) => warn!("Failed to archive the proof to IPFS: {:#}", e),
+            }
+        }
+
+        if let Some(intermediate_dir) = &self.intermediate_dir {
+            converted_proof.write_components(intermediate_dir).await?;
+            status!("🧩 Proof components written to {}", intermediate_dir.display());
+        }
+        Ok(())
+    }
+
+    /// Submit a converted proof with the configured submitter and wait until it is finalized
+    pub async fn submit(&self, proof_path: &Path) -> Result<Submission> {
+        self.submit_to_domain(proof_path, self.domain_id).await
+    }
+
+    /// [`Self::submit`], queueing the proof into `domain_id` instead of the configured domain
+    pub async fn submit_to_domain(&self, proof_path: &Path, domain_id: Option<u32>) -> Result<Submission> {
+        self.submit_checked(proof_path, domain_id, true).await
+    }
+
+    /// [`Self::submit`] without looking for an earlier verification of the same statement, for
+    /// proofs such as the canary that are meant to be verified again
+    pub async fn submit_again(&self, proof_path: &Path) -> Result<Submission> {
+        self.submit_checked(proof_path, self.domain_id, false).await
+    }
+
+    #[instrument(skip(self))]
+    async fn submit_checked(&self, proof_path: &Path, domain_id: Option<u32>, check_duplicates: bool) -> Result<Submission> {
+        let converted_proof = self.converter.load_proof(proof_path).await.classify(FailureClass::BadInput)?;
+        if let Some(vk_allowlist) = &self.vk_allowlist {
+            vk_allowlist.check(&converted_proof)?;
+        }
+
+        // Track the submission's progress in the history as it happens
+        let tracked = self.history.as_deref().zip(converted_proof.request_id.as_deref());
+        let submission = match &self.submitter {
+            Some(Submitter::Direct(substrate_client)) => {
+                let on_event = |event: TxEvent| {
+                    if let TxEvent::Broadcast { tx_hash, from_block } = &event {
+                        info!(request_id = converted_proof.request_id.as_deref(), tx_hash = %tx_hash, from_block, "Transaction broadcast");
+                    }
+                    if let Some((history, request_id)) = tracked
+                        && let Err(e) = history.record_tx_event(request_id, &event)
+                    {
+                        warn!("Failed to update history: {:#}", e);
+                    }
+                };
+                let prior = match check_duplicates {
+                    true => substrate_client.find_prior_verification(&converted_proof, domain_id).await?,
+                    false => None,
+                };
+                if let Some(prior) = prior {
+                    status!("⏭️  Statement {:?} was already verified at block {}; not submitting it again", prior.statement, prior.block_number);
+                    Submission {
+                        tx_hash: format!("{:?}", prior.tx_hash),
+                        block_hash: Some(format!("{:?}", prior.block_hash)),
+                        block_number: Some(prior.block_number),
+                        aggregation_id: prior.aggregation.map(|(_, aggregation_id)| aggregation_id),
+                        events: Vec::new(),
+                    }
+                } else {
+                    let inclusion = substrate_client
+                        .submit_proof_and_track(proof_path, domain_id, &on_event)
+                        .await?;
+                    Submission {
+                        tx_hash: inclusion.tx_hash,
+                        block_hash: Some(format!("{:?}", inclusion.block_hash)),
+                        block_number: inclusion.block_number,
+                        aggregation_id: inclusion.aggregation_id,
+                        events: inclusion.events,
+                    }
+                }
+            }
+            Some(Submitter::Relayer(relayer)) => {
+                let job_id = relayer.submit_proof(&converted_proof, domain_id).await?;
+                status!("📨 Relayer job created: {}", job_id);
+                if let Some((history, request_id)) = tracked
+                    && let Err(e) = history.record_relayer_job(request_id, &job_id)
+                {
+                    warn!("Failed to update history: {:#}", e);
+                }
+                let status = relayer.wait_for_job(&job_id, false).await?;
+                Submission {
+                    tx_hash: status.tx_hash.unwrap_or(job_id),
+                    block_hash: status.block_hash,
+                    block_number: None,
+                    aggregation_id: status.aggregation_id,
+                    events: Vec::new(),
+                }
+            }
+            None => anyhow::bail!("No submission backend configured"),
+        };
+        info!(
+            request_id = converted_proof.request_id.as_deref(),
+            tx_hash = %submission.tx_hash,
+            block_hash = submission.block_hash.as_deref(),
+            "Proof finalized"
+        );
+
+        if let Some((history, request_id)) = tracked
+            && let Err(e) = history.record_finalized(request_id, &submission)
+        {
+            warn!("Failed to update history: {:#}", e);
+        }
+
+        // The proof is on chain by now, so a receipt that cannot be written does not fail it
+        let artifact_sha256 = match tracked {
+            Some((history, request_id)) => history.get(request_id).ok().flatten().and_then(|entry| entry.artifact_sha256),
+            None => None,
+        };
+        let receipt = SubmissionReceipt::new(
+            converted_proof.request_id.as_deref(),
+            artifact_sha256.as_deref(),
+            proof_path,
+            domain_id,
+            &submission,
+        );
+        let receipt_path = SubmissionReceipt::path_for(proof_path);
+        match receipt.save(&receipt_path).await {
+            Ok(()) => status!("🧾 Receipt written to {}", receipt_path.display()),
+            Err(e) => warn!("Failed to write the submission receipt: {:#}", e),
+        }
+        Ok(submission)
+    }
+
+    /// Settle history entries left submitted or in a block by an earlier run, checking the
+    /// chain (or the relayer) for where each one ended up. Entries that may still land stay as they are.
+    pub async fn reconcile_history(&self) -> Result<()> {
+        let Some(history) = &self.history else {
+            return Ok(());
+        };
+        let entries = history.in_flight()?;
+        if entries.is_empty() {
+            return Ok(());
+        }
+        status!("🔎 Reconciling {} submissions left in flight", entries.len());
+
+        for entry in entries {
+            let request_id = &entry.request_id;
+            match (&self.submitter, &entry.relayer_job_id, &entry.tx_hash, entry.from_block) {
+                (Some(Submitter::Relayer(relayer)), Some(job_id), _, _) => {
+                    let status = relayer.job_status(job_id).await?;
+                    if status.is_failed() {
+                        let error = status.error_details.as_deref().unwrap_or("relayer job failed");
+                        history.record_failure(request_id, error)?;
+                        status!("❌ {}: relayer job {} failed", request_id, job_id);
+                    } else if status.is_terminal(false) {
+                        let submission = Submission {
+                            tx_hash: status.tx_hash.clone().unwrap_or_else(|| job_id.clone()),
+                            block_hash: status.block_hash.clone(),
+                            block_number: None,
+                            aggregation_id: status.aggregation_id,
+                            events: Vec::new(),
+                        };
+                        history.record_finalized(request_id, &submission)?;
+                        status!("✅ {}: finalized in {}", request_id, submission.tx_hash);
+                    } else {
+                        status!("⏳ {}: relayer job {} is still {}", request_id, job_id, status.status);
+                    }
+                }
+                (Some(Submitter::Direct(substrate_client)), None, Some(tx_hash), Some(from_block)) => {
+                    // Any attempt a fee bump replaced may be the one that was included
+                    let mut lookup = ExtrinsicLookup::Expired;
+                    let mut tx_hash = tx_hash;
+                    for attempt in std::iter::once(tx_hash).chain(&entry.replaced_tx_hashes) {
+                        match substrate_client.find_extrinsic(attempt, from_block).await? {
+                            ExtrinsicLookup::Expired => {}
+                            ExtrinsicLookup::Pending => lookup = ExtrinsicLookup::Pending,
+                            finalized => {
+                                lookup = finalized;
+                                tx_hash = attempt;
+                                break;
+                            }
+                        }
+                    }
+                    match lookup {
+                        ExtrinsicLookup::Finalized { block_hash, success: true } => {
+                            let submission = Submission {
+                                tx_hash: tx_hash.clone(),
+                                block_hash: Some(format!("{:?}", block_hash)),
+                                block_number: None,
+                                aggregation_id: None,
+                                events: Vec::new(),
+                            };
+                            history.record_finalized(request_id, &submission)?;
+                            status!("✅ {}: finalized in block {:?}", request_id, block_hash);
+                        }
+                        ExtrinsicLookup::Finalized { block_hash, success: false } => {
+                            history.record_failure(request_id, &format!("Extrinsic failed in block {:?}", block_hash))?;
+                            status!("❌ {}: extrinsic failed in block {:?}", request_id, block_hash);
+                        }
+                        ExtrinsicLookup::Expired => {
+                            history.record_failure(request_id, "Extrinsic expired without being included")?;
+                            status!("❌ {}: extrinsic expired without being included", request_id);
+                        }
+                        ExtrinsicLookup::Pending => {
+                            status!("⏳ {}: {} not finalized yet", request_id, tx_hash);
+                        }
+                    }
+                }
+                _ => warn!("Cannot reconcile {} with the configured backend, leaving it {}", request_id, entry.status),
+            }
+        }
+        Ok(())
+    }
+}
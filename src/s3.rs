@@ -0,0 +1,378 @@
+use anyhow::Result;
+use aws_config::{BehaviorVersion, SdkConfig};
+use aws_sdk_s3::config::{ProvideCredentials, Region};
+use aws_sdk_s3::error::DisplayErrorContext;
+use aws_sdk_s3::types::ChecksumMode;
+use indicatif::ProgressBar;
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+use std::io::SeekFrom;
+use std::path::Path;
+use std::time::Duration;
+use subxt::ext::futures::{stream, StreamExt, TryStreamExt};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt, BufWriter};
+use tokio::sync::OnceCell;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use crate::cache::hash_file;
+use crate::client::{too_large, DownloadedArtifact};
+use crate::failure::{FailureClass, ResultExt};
+use crate::network::HttpClient;
+use crate::progress;
+
+/// How S3 artifacts are downloaded in parallel ranged parts
+#[derive(Debug, Clone)]
+pub struct S3Options {
+    /// Region of buckets whose URL names none; the AWS configuration's, or `us-east-1`, when unset
+    pub region: Option<String>,
+    /// Bytes in each ranged part
+    pub part_size: u64,
+    /// Parts downloaded at the same time
+    pub concurrency: usize,
+    /// Never sign requests with AWS credentials, downloading over the artifact URL
+    pub no_sign_request: bool,
+}
+
+impl Default for S3Options {
+    fn default() -> Self {
+        Self { region: None, part_size: 16 << 20, concurrency: 8, no_sign_request: false }
+    }
+}
+
+impl S3Options {
+    pub fn with_region(mut self, region: Option<String>) -> Self {
+        self.region = region;
+        self
+    }
+
+    pub fn with_part_size(mut self, part_size: u64) -> Self {
+        self.part_size = part_size.max(1);
+        self
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn with_no_sign_request(mut self, no_sign_request: bool) -> Self {
+        self.no_sign_request = no_sign_request;
+        self
+    }
+}
+
+/// An object in an S3 bucket, as named by its URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Object {
+    pub bucket: String,
+    pub key: String,
+    /// Region in the host name, e.g. `us-east-2`
+    pub region: Option<String>,
+}
+
+/// The bucket and key of a virtual-hosted (`https://<bucket>.s3.<region>.amazonaws.com/<key>`)
+/// or path-style (`https://s3.<region>.amazonaws.com/<bucket>/<key>`) S3 URL
+pub fn parse_s3_url(url: &str) -> Option<S3Object> {
+    let url = reqwest::Url::parse(url).ok()?;
+    if url.scheme() != "https" && url.scheme() != "http" {
+        return None;
+    }
+    let host = url.host_str()?.strip_suffix(".amazonaws.com")?;
+    let path = percent_decode(url.path().trim_start_matches('/'))?;
+    let (bucket, key, endpoint) = match host.rfind(".s3") {
+        Some(index) => (host[..index].to_string(), path, &host[index + 3..]),
+        None => {
+            let endpoint = host.strip_prefix("s3")?;
+            let (bucket, key) = path.split_once('/')?;
+            (bucket.to_string(), key.to_string(), endpoint)
+        }
+    };
+    if bucket.is_empty() || key.is_empty() {
+        return None;
+    }
+    // `.us-east-2`, the older `-us-east-2`, or nothing for us-east-1
+    let region = endpoint.trim_start_matches(['.', '-']).trim_start_matches("dualstack.");
+    Some(S3Object { bucket, key, region: (!region.is_empty()).then(|| region.to_string()) })
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Downloads S3 artifacts in ranged parts, several at a time. Parts are fetched with the AWS SDK
+/// when AWS credentials are configured (environment, profile, SSO or instance role), and
+/// otherwise as ranged GETs of the artifact URL, which is enough for presigned URLs.
+pub struct S3Downloader {
+    options: S3Options,
+    // Loaded on the first download; `None` without credentials
+    config: OnceCell<Option<SdkConfig>>,
+}
+
+// Where the parts of one artifact come from
+enum Source<'a> {
+    Sdk { client: aws_sdk_s3::Client, object: &'a S3Object },
+    Url(&'a str),
+}
+
+impl S3Downloader {
+    pub fn new(options: S3Options) -> Self {
+        Self { options, config: OnceCell::new() }
+    }
+
+    async fn config(&self) -> Option<&SdkConfig> {
+        self.config
+            .get_or_init(|| async {
+                if self.options.no_sign_request {
+                    return None;
+                }
+                let config = aws_config::defaults(BehaviorVersion::latest()).load().await;
+                match config.credentials_provider()?.provide_credentials().await {
+                    Ok(_) => Some(config),
+                    Err(e) => {
+                        debug!("No AWS credentials ({}), downloading S3 artifacts over their URL", e);
+                        None
+                    }
+                }
+            })
+            .await
+            .as_ref()
+    }
+
+    /// Download the artifact at `url`, naming `object`, into `path`, replacing what is there.
+    /// The result is as [`crate::client::ProofClient::download_artifact`] gives it.
+    pub(crate) async fn download(
+        &self,
+        http: &HttpClient,
+        url: &str,
+        object: &S3Object,
+        path: &Path,
+        max_artifact_size: Option<u64>,
+        max_download_rate: Option<u64>,
+    ) -> Result<DownloadedArtifact> {
+        let source = match self.config().await {
+            Some(config) => {
+                let region = object
+                    .region
+                    .clone()
+                    .or_else(|| self.options.region.clone())
+                    .or_else(|| config.region().map(ToString::to_string))
+                    .unwrap_or_else(|| "us-east-1".to_string());
+                let config = aws_sdk_s3::config::Builder::from(config).region(Region::new(region)).build();
+                Source::Sdk { client: aws_sdk_s3::Client::from_conf(config), object }
+            }
+            None => Source::Url(url),
+        };
+        let (len, checksum) = self.object_size(http, &source).await.classify(FailureClass::Network)?;
+        if let Some(max_size) = max_artifact_size
+            && len > max_size
+        {
+            return Err(too_large(len, max_size));
+        }
+
+        let file = tokio::fs::File::create(path).await?;
+        file.set_len(len).await?;
+        drop(file);
+        let parts: Vec<(u64, u64)> = (0..len)
+            .step_by(self.options.part_size as usize)
+            .map(|first| (first, (first + self.options.part_size).min(len) - 1))
+            .collect();
+        info!(
+            "Downloading s3://{}/{} ({} bytes) in {} parts {}",
+            object.bucket,
+            object.key,
+            len,
+            parts.len(),
+            match source {
+                Source::Sdk { .. } => "with the AWS SDK",
+                Source::Url(_) => "over its URL",
+            }
+        );
+        // The rate is shared out between the parts downloading at once
+        let concurrency = self.options.concurrency.min(parts.len()).max(1);
+        let part_rate = max_download_rate.map(|rate| (rate / concurrency as u64).max(1));
+        let bar = progress::download(format!("Downloading artifact in {} parts", parts.len()), Some(len));
+        let downloaded = stream::iter(parts)
+            .map(|(first, last)| self.download_part(http, &source, path, first, last, &bar, part_rate))
+            .buffer_unordered(concurrency)
+            .try_collect::<Vec<()>>()
+            .await;
+        if downloaded.is_err() {
+            // Parts may be missing anywhere, so the file cannot be resumed
+            tokio::fs::remove_file(path).await.ok();
+        }
+        downloaded.classify(FailureClass::Network)?;
+
+        let mut hasher = Sha256::new();
+        hash_file(path, &mut hasher).await?;
+        let sha256 = hasher.finalize();
+        if let Some(checksum) = checksum
+            && base64::Engine::encode(&base64::engine::general_purpose::STANDARD, sha256) != checksum
+        {
+            tokio::fs::remove_file(path).await.ok();
+            return Err(FailureClass::Network.error(format!(
+                "Artifact is corrupted: SHA-256 {} does not match the storage checksum {}",
+                hex::encode(sha256),
+                checksum
+            )));
+        }
+        Ok(DownloadedArtifact { len, sha256: hex::encode(sha256) })
+    }
+
+    // Size of the object and its full-object SHA-256 (base64), when the storage reports one
+    async fn object_size(&self, http: &HttpClient, source: &Source<'_>) -> Result<(u64, Option<String>)> {
+        match source {
+            Source::Sdk { client, object } => {
+                let head = client
+                    .head_object()
+                    .bucket(&object.bucket)
+                    .key(&object.key)
+                    .checksum_mode(ChecksumMode::Enabled)
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        anyhow::anyhow!("HeadObject of s3://{}/{} failed: {}", object.bucket, object.key, DisplayErrorContext(e))
+                    })?;
+                let len = head.content_length().and_then(|len| u64::try_from(len).ok());
+                let checksum = head.checksum_sha256().filter(|checksum| !checksum.contains('-'));
+                Ok((
+                    len.ok_or_else(|| anyhow::anyhow!("S3 reported no size for s3://{}/{}", object.bucket, object.key))?,
+                    checksum.map(str::to_string),
+                ))
+            }
+            // A presigned URL is signed for GET only, so the size comes from a one-byte range
+            Source::Url(url) => {
+                let response = http.send(|client| client.get(*url).header(RANGE, "bytes=0-0")).await?;
+                let len = match response.status() {
+                    StatusCode::PARTIAL_CONTENT => response
+                        .headers()
+                        .get(CONTENT_RANGE)
+                        .and_then(|range| range.to_str().ok()?.rsplit('/').next()?.parse().ok()),
+                    status => anyhow::bail!("Failed to download artifact: {}", status),
+                };
+                Ok((len.ok_or_else(|| anyhow::anyhow!("Artifact storage reported no size"))?, None))
+            }
+        }
+    }
+
+    // Download bytes `first..=last` into their place in `path`, resuming the part after an
+    // interruption as long as retries are left
+    #[allow(clippy::too_many_arguments)]
+    async fn download_part(
+        &self,
+        http: &HttpClient,
+        source: &Source<'_>,
+        path: &Path,
+        first: u64,
+        last: u64,
+        bar: &ProgressBar,
+        max_rate: Option<u64>,
+    ) -> Result<()> {
+        let policy = http.policy();
+        let mut written = 0;
+        let mut interruptions = 0;
+        loop {
+            match self.fetch_range(http, source, path, first + written, last, &mut written, bar, max_rate).await {
+                Ok(()) => return Ok(()),
+                Err(e) if interruptions < policy.max_retries => {
+                    interruptions += 1;
+                    let delay = policy.retry_delay(interruptions);
+                    warn!(
+                        "Artifact part {}-{} interrupted at {} bytes ({:#}), retry {}/{} in {:?}",
+                        first, last, written, e, interruptions, policy.max_retries, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => {
+                    return Err(anyhow::anyhow!("Artifact part {}-{} interrupted at {} bytes: {:#}", first, last, written, e));
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn fetch_range(
+        &self,
+        http: &HttpClient,
+        source: &Source<'_>,
+        path: &Path,
+        first: u64,
+        last: u64,
+        written: &mut u64,
+        bar: &ProgressBar,
+        max_rate: Option<u64>,
+    ) -> Result<()> {
+        let range = format!("bytes={}-{}", first, last);
+        let mut file = tokio::fs::OpenOptions::new().write(true).open(path).await?;
+        file.seek(SeekFrom::Start(first)).await?;
+        let mut writer = BufWriter::new(file);
+        let (mut sdk_body, mut http_body) = (None, None);
+        match source {
+            Source::Sdk { client, object } => {
+                let response = client
+                    .get_object()
+                    .bucket(&object.bucket)
+                    .key(&object.key)
+                    .range(&range)
+                    .send()
+                    .await
+                    .map_err(|e| anyhow::anyhow!("GetObject failed: {}", DisplayErrorContext(e)))?;
+                sdk_body = Some(response.body);
+            }
+            Source::Url(url) => {
+                let response = http.send(|client| client.get(*url).header(RANGE, &range)).await?;
+                if response.status() != StatusCode::PARTIAL_CONTENT {
+                    anyhow::bail!("storage answered {} to a range request", response.status());
+                }
+                http_body = Some(response);
+            }
+        }
+
+        let started = Instant::now();
+        let mut received = 0;
+        let read_timeout = http.policy().read_timeout.unwrap_or(Duration::MAX);
+        let interruption = loop {
+            let next = async {
+                match (&mut sdk_body, &mut http_body) {
+                    (Some(body), _) => body.next().await.transpose().map_err(anyhow::Error::from),
+                    (_, Some(response)) => response.chunk().await.map_err(anyhow::Error::from),
+                    _ => Ok(None),
+                }
+            };
+            let chunk = match tokio::time::timeout(read_timeout, next).await {
+                Ok(Ok(Some(chunk))) => chunk,
+                Ok(Ok(None)) if first + received <= last => break Some(anyhow::anyhow!("connection closed early")),
+                Ok(Ok(None)) => break None,
+                Ok(Err(e)) => break Some(e),
+                Err(_) => break Some(anyhow::anyhow!("no data for {:?}", read_timeout)),
+            };
+            writer.write_all(&chunk).await?;
+            *written += chunk.len() as u64;
+            bar.inc(chunk.len() as u64);
+            // Hold back until the bytes so far fit the rate
+            received += chunk.len() as u64;
+            if let Some(rate) = max_rate {
+                tokio::time::sleep_until(started + Duration::from_secs_f64(received as f64 / rate as f64)).await;
+            }
+        };
+        // Flushed either way, as a retry resumes after the bytes counted in `written`
+        writer.flush().await?;
+        match interruption {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::Result;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{info_span, Instrument};
+
+use crate::router::Router;
+use crate::warnings::{Warning, WarningCode};
+
+/// Periodic housekeeping run alongside watch mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskKind {
+    /// Warn (W003) when the signer's free balance drops below the threshold
+    BalanceCheck,
+    /// Submit a known-good proof to check the whole submission path end to end
+    Canary,
+}
+
+/// A task and how often to run it, parsed from `<task>:<seconds>`
+#[derive(Debug, Clone)]
+pub struct TaskSpec {
+    pub kind: TaskKind,
+    pub interval: Duration,
+}
+
+impl FromStr for TaskSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (kind, seconds) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("Invalid task '{}': expected <task>:<seconds>", s))?;
+        let kind = match kind {
+            "balance-check" => TaskKind::BalanceCheck,
+            "canary" => TaskKind::Canary,
+            _ => anyhow::bail!("Unknown task '{}': expected balance-check or canary", kind),
+        };
+        let seconds: u64 = seconds
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid interval '{}' for task {:?}", seconds, kind))?;
+        if seconds == 0 {
+            anyhow::bail!("Task interval must be at least one second");
+        }
+        Ok(TaskSpec { kind, interval: Duration::from_secs(seconds) })
+    }
+}
+
+struct ScheduledTask {
+    spec: TaskSpec,
+    next_run: Instant,
+}
+
+pub struct Scheduler {
+    tasks: Vec<ScheduledTask>,
+    balance_threshold: Option<u128>,
+    canary_proof: Option<PathBuf>,
+}
+
+impl Scheduler {
+    /// Schedule the tasks, each first running one interval from now
+    pub fn new(specs: Vec<TaskSpec>, balance_threshold: Option<u128>, canary_proof: Option<PathBuf>) -> Result<Self> {
+        for spec in &specs {
+            match spec.kind {
+                TaskKind::BalanceCheck if balance_threshold.is_none() => {
+                    anyhow::bail!("The balance-check task requires --balance-warning-threshold")
+                }
+                TaskKind::Canary if canary_proof.is_none() => {
+                    anyhow::bail!("The canary task requires --canary-proof")
+                }
+                _ => {}
+            }
+        }
+
+        let now = Instant::now();
+        let tasks = specs
+            .into_iter()
+            .map(|spec| ScheduledTask { next_run: now + spec.interval, spec })
+            .collect();
+        Ok(Self { tasks, balance_threshold, canary_proof })
+    }
+
+    /// When the earliest task is due, if any are scheduled
+    pub fn next_due(&self) -> Option<Instant> {
+        self.tasks.iter().map(|task| task.next_run).min()
+    }
+
+    /// Run every task that is due; failures are reported and the task stays scheduled
+    pub async fn run_due(&mut self, router: &Router) {
+        let now = Instant::now();
+        for index in 0..self.tasks.len() {
+            if self.tasks[index].next_run > now {
+                continue;
+            }
+            let kind = self.tasks[index].spec.kind.clone();
+            if let Err(e) = self.run(&kind, router).instrument(info_span!("task", ?kind)).await {
+                status!("❌ Scheduled {:?} failed: {:#}", kind, e);
+            }
+            let task = &mut self.tasks[index];
+            task.next_run = now + task.spec.interval;
+        }
+    }
+
+    async fn run(&self, kind: &TaskKind, router: &Router) -> Result<()> {
+        match kind {
+            TaskKind::BalanceCheck => {
+                let substrate_client = router
+                    .substrate_client()
+                    .ok_or_else(|| anyhow::anyhow!("balance-check needs the direct backend"))?;
+                let free = substrate_client.free_balance().await?;
+                if let Some(threshold) = self.balance_threshold
+                    && free < threshold
+                {
+                    Warning::emit(
+                        WarningCode::NearBalanceThreshold,
+                        format!("Free balance {} is below the warning threshold {}", free, threshold),
+                    );
+                }
+                Ok(())
+            }
+            TaskKind::Canary => {
+                let canary_proof = self.canary_proof.as_deref().expect("checked in Scheduler::new");
+                let submission = router.submit_again(canary_proof).await?;
+                status!("🐤 Canary submitted in {}", submission.tx_hash);
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use regex::Regex;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::{LazyLock, RwLock};
+use tracing_subscriber::fmt::MakeWriter;
+use zeroize::Zeroizing;
+
+/// Shown in place of secrets in Debug output and logs
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Hex runs at least this long are cut short in logs; only proof payloads, VKs and call data are
+/// that long, while hashes, keys and addresses stay whole
+const MAX_LOGGED_HEX: usize = 256;
+
+// Every secret a `SecretString` was made from, scrubbed from log lines until the process exits.
+// Redacting a secret out of free text needs the secret itself; a hash or length cannot find it
+// in a line. So these copies, one per distinct secret, outlive the `SecretString`s they came
+// from: a static is never dropped, and a core dump of the process still shows them.
+static SECRETS: RwLock<Vec<Zeroizing<String>>> = RwLock::new(Vec::new());
+
+static LONG_HEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!("(0x)?[0-9a-fA-F]{{{},}}", MAX_LOGGED_HEX)).expect("hex pattern"));
+
+/// A mnemonic, seed or other key material: wiped from memory when dropped, shown as
+/// `[REDACTED]` by Debug and scrubbed from log lines while the process runs
+#[derive(Clone)]
+pub struct SecretString(Zeroizing<String>);
+
+impl SecretString {
+    pub fn new(secret: impl Into<String>) -> Self {
+        let secret = Zeroizing::new(secret.into());
+        let trimmed = secret.trim();
+        if !trimmed.is_empty() {
+            let mut secrets = SECRETS.write().expect("secrets lock");
+            if !secrets.iter().any(|known| known.as_str() == trimmed) {
+                secrets.push(Zeroizing::new(trimmed.to_string()));
+            }
+        }
+        Self(secret)
+    }
+
+    /// The secret itself, for deriving keys from it; never log or print it
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+/// `text` with every known secret replaced by `[REDACTED]` and long hex payloads cut short
+pub fn redact(text: &str) -> String {
+    let mut text = text.to_string();
+    for secret in SECRETS.read().expect("secrets lock").iter() {
+        if text.contains(secret.as_str()) {
+            text = text.replace(secret.as_str(), REDACTED);
+        }
+    }
+    LONG_HEX
+        .replace_all(&text, |captures: &regex::Captures| {
+            let hex = &captures[0];
+            format!("{}…({} hex digits elided)", &hex[..18], hex.len() - 18)
+        })
+        .into_owned()
+}
+
+/// Wraps the writer of a log layer so each event is redacted before it is written
+pub struct Redacting<M>(pub M);
+
+impl<'writer, M: MakeWriter<'writer>> MakeWriter<'writer> for Redacting<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'writer self) -> Self::Writer {
+        RedactingWriter { inner: self.0.make_writer(), buffer: Vec::new() }
+    }
+}
+
+/// Collects one event and writes it redacted when dropped
+pub struct RedactingWriter<W: Write> {
+    inner: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            let buffer = Zeroizing::new(std::mem::take(&mut self.buffer));
+            self.inner.write_all(redact(&String::from_utf8_lossy(&buffer)).as_bytes())?;
+        }
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for RedactingWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::str::FromStr;
+use subxt::{
+    ext::{codec::Decode, subxt_core},
+    tx::Payload,
+    utils::{MultiSignature, H256},
+    Metadata,
+};
+
+use crate::output::write_atomic;
+
+/// Block a mortal transaction's validity window starts from
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Mortality {
+    pub period: u64,
+    pub block_number: u64,
+    pub block_hash: H256,
+}
+
+/// A transaction prepared by `prepare-tx`, waiting for a signature made elsewhere
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UnsignedTx {
+    /// SS58 address of the account that must sign
+    pub signer: String,
+    pub genesis_hash: H256,
+    pub spec_version: u32,
+    pub nonce: u64,
+    pub tip: u128,
+    /// `None` for an immortal transaction
+    pub mortality: Option<Mortality>,
+    pub call_data: String,
+    /// Signed extension bytes that follow the call in the unhashed signing payload
+    #[serde(default)]
+    pub extensions: String,
+    /// Exact bytes to sign (already hashed by the runtime rules when longer than 256 bytes)
+    pub signer_payload: String,
+}
+
+impl UnsignedTx {
+    pub fn call_data_bytes(&self) -> Result<Vec<u8>> {
+        Ok(hex::decode(self.call_data.trim_start_matches("0x"))?)
+    }
+
+    pub async fn save(&self, output_path: &Path) -> Result<()> {
+        let json_content = serde_json::to_string_pretty(self)?;
+        write_atomic(output_path, json_content).await?;
+        Ok(())
+    }
+
+    pub async fn load(input_path: &Path) -> Result<Self> {
+        let json_content = tokio::fs::read(input_path).await?;
+        Ok(serde_json::from_slice(&json_content)?)
+    }
+}
+
+/// Already encoded call data, replayed as-is into a transaction
+pub(crate) struct RawCall(pub Vec<u8>);
+
+impl Payload for RawCall {
+    fn encode_call_data_to(&self, _metadata: &Metadata, out: &mut Vec<u8>) -> Result<(), subxt_core::Error> {
+        out.extend_from_slice(&self.0);
+        Ok(())
+    }
+}
+
+/// Key type of a raw (untagged) signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureScheme {
+    Sr25519,
+    Ed25519,
+    Ecdsa,
+}
+
+impl FromStr for SignatureScheme {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "sr25519" => Ok(SignatureScheme::Sr25519),
+            "ed25519" => Ok(SignatureScheme::Ed25519),
+            "ecdsa" => Ok(SignatureScheme::Ecdsa),
+            _ => anyhow::bail!("Unknown signature scheme '{}': expected sr25519, ed25519 or ecdsa", s),
+        }
+    }
+}
+
+/// Parse a hex signature, either raw for `scheme` or SCALE-encoded `MultiSignature`
+/// (scheme byte first, as produced by Polkadot Vault).
+pub fn parse_signature(signature_hex: &str, scheme: SignatureScheme) -> Result<MultiSignature> {
+    let bytes = hex::decode(signature_hex.trim().trim_start_matches("0x"))?;
+
+    let raw = match (scheme, bytes.len()) {
+        (SignatureScheme::Sr25519, 64) => Some(MultiSignature::Sr25519(bytes[..].try_into()?)),
+        (SignatureScheme::Ed25519, 64) => Some(MultiSignature::Ed25519(bytes[..].try_into()?)),
+        (SignatureScheme::Ecdsa, 65) => Some(MultiSignature::Ecdsa(bytes[..].try_into()?)),
+        _ => None,
+    };
+    if let Some(signature) = raw {
+        return Ok(signature);
+    }
+
+    let mut input = &bytes[..];
+    let signature = MultiSignature::decode(&mut input)
+        .map_err(|e| anyhow::anyhow!("Signature is neither a raw {:?} signature nor a MultiSignature: {}", scheme, e))?;
+    if !input.is_empty() {
+        anyhow::bail!("Signature has {} trailing bytes", input.len());
+    }
+    Ok(signature)
+}
@@ -0,0 +1,126 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::failure::FailureClass;
+
+/// Byte order field elements are written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Endian {
+    Little,
+    Big,
+}
+
+pub(crate) fn bad_input(message: String) -> anyhow::Error {
+    FailureClass::BadInput.error(message)
+}
+
+pub(crate) async fn read_json(path: &Path) -> Result<Value> {
+    let content = tokio::fs::read(path)
+        .await
+        .map_err(|e| bad_input(format!("Failed to read {}: {}", path.display(), e)))?;
+    serde_json::from_slice(&content).map_err(|e| bad_input(format!("{} is not valid JSON: {}", path.display(), e)))
+}
+
+pub(crate) fn field<'a>(object: &'a Value, name: &str, path: &Path) -> Result<&'a Value> {
+    object.get(name).ok_or_else(|| bad_input(format!("Missing '{}' in {}", name, path.display())))
+}
+
+/// Refuse a file made for another proof system, when it says which one
+pub(crate) fn check_protocol(object: &Value, path: &Path, protocol: &str) -> Result<()> {
+    match object.get("protocol").and_then(Value::as_str) {
+        Some(found) if found != protocol => {
+            Err(bad_input(format!("{} is a {} file, not {}", path.display(), found, protocol)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Entries of a `public.json`
+pub(crate) fn public_inputs<'a>(public: &'a Value, path: &Path) -> Result<&'a [Value]> {
+    public
+        .as_array()
+        .map(Vec::as_slice)
+        .ok_or_else(|| bad_input(format!("{} is not a list of public inputs", path.display())))
+}
+
+fn decimal(value: &Value, name: &str) -> Result<String> {
+    match value {
+        Value::String(decimal) => Ok(decimal.trim().to_string()),
+        Value::Number(number) => Ok(number.to_string()),
+        _ => Err(bad_input(format!("'{}' is not a decimal number", name))),
+    }
+}
+
+/// The decimal field element `value` as `size` bytes
+pub(crate) fn field_element(value: &Value, name: &str, size: usize, endian: Endian) -> Result<Vec<u8>> {
+    let decimal = decimal(value, name)?;
+    if decimal.is_empty() || !decimal.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(bad_input(format!("'{}' is not a decimal field element", name)));
+    }
+    // Little-endian while accumulating
+    let mut bytes = vec![0u8; size];
+    for digit in decimal.bytes() {
+        let mut carry = (digit - b'0') as u32;
+        for byte in bytes.iter_mut() {
+            let value = *byte as u32 * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return Err(bad_input(format!("'{}' ({}) does not fit in {} bytes", name, decimal, size)));
+        }
+    }
+    if endian == Endian::Big {
+        bytes.reverse();
+    }
+    Ok(bytes)
+}
+
+fn coordinates<'a>(value: &'a Value, name: &str, len: usize) -> Result<&'a [Value]> {
+    value
+        .as_array()
+        .filter(|coordinates| coordinates.len() == len || coordinates.len() == len + 1)
+        .map(Vec::as_slice)
+        .ok_or_else(|| bad_input(format!("'{}' is not a curve point", name)))
+}
+
+/// Uncompressed G1 point: x then y. Only affine points (z = 1) are taken.
+pub(crate) fn g1(value: &Value, name: &str, size: usize, endian: Endian) -> Result<Vec<u8>> {
+    let coordinates = coordinates(value, name, 2)?;
+    if let Some(z) = coordinates.get(2)
+        && decimal(z, name)? != "1"
+    {
+        return Err(bad_input(format!("'{}' is not in affine form (z = 1)", name)));
+    }
+    let mut bytes = field_element(&coordinates[0], name, size, endian)?;
+    bytes.extend(field_element(&coordinates[1], name, size, endian)?);
+    Ok(bytes)
+}
+
+/// Uncompressed G2 point: x.c0, x.c1, y.c0 then y.c1. Only affine points (z = 1) are taken.
+pub(crate) fn g2(value: &Value, name: &str, size: usize, endian: Endian) -> Result<Vec<u8>> {
+    let coordinates = coordinates(value, name, 2)?;
+    if let Some(z) = coordinates.get(2) {
+        let z = coordinates_of(z, name)?;
+        if decimal(&z[0], name)? != "1" || decimal(&z[1], name)? != "0" {
+            return Err(bad_input(format!("'{}' is not in affine form (z = 1)", name)));
+        }
+    }
+    let mut bytes = Vec::with_capacity(4 * size);
+    for coordinate in &coordinates[..2] {
+        for c in coordinates_of(coordinate, name)? {
+            bytes.extend(field_element(c, name, size, endian)?);
+        }
+    }
+    Ok(bytes)
+}
+
+// The two components (c0, c1) of an extension field element
+fn coordinates_of<'a>(value: &'a Value, name: &str) -> Result<&'a [Value]> {
+    value
+        .as_array()
+        .filter(|components| components.len() == 2)
+        .map(Vec::as_slice)
+        .ok_or_else(|| bad_input(format!("'{}' is not a G2 point", name)))
+}
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use regex::bytes::Regex;
+use sp1_sdk::SP1ProofWithPublicValues;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use tracing::warn;
+
+use crate::failure::FailureClass;
+
+/// SP1 major versions whose artifacts this build deserializes and converts: those of the
+/// pinned `sp1-sdk` and the `sp1-zkv-sdk` shrinking for zkVerify's SP1 pallet. There are no
+/// feature-gated paths for older lines: the pallet verifies proofs shrunk by its own SP1
+/// release, so an older artifact read with its own SDK would still not verify on chain.
+pub const SUPPORTED_MAJOR_VERSIONS: [u64; 1] = [5];
+
+/// The `sp1_version` an artifact declares, e.g. `v5.2.1`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sp1Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Sp1Version {
+    /// Whether this build reads artifacts of this version's release line
+    pub fn is_supported(&self) -> bool {
+        SUPPORTED_MAJOR_VERSIONS.contains(&self.major)
+    }
+}
+
+impl fmt::Display for Sp1Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for Sp1Version {
+    type Err = anyhow::Error;
+
+    // Pre-release and build suffixes, as in `v4.0.0-rc.1`, are ignored
+    fn from_str(s: &str) -> Result<Self> {
+        let core = s.trim().trim_start_matches('v');
+        let core = core.split(['-', '+']).next().unwrap_or_default();
+        let parts: Vec<u64> = core.split('.').map(str::parse).collect::<Result<_, _>>()?;
+        match parts[..] {
+            [major, minor, patch] => Ok(Self { major, minor, patch }),
+            _ => anyhow::bail!("Invalid SP1 version '{}'", s),
+        }
+    }
+}
+
+/// Supported release lines as shown to users, e.g. `v5.x`
+pub fn supported_releases() -> Vec<String> {
+    SUPPORTED_MAJOR_VERSIONS.iter().map(|major| format!("v{}.x", major)).collect()
+}
+
+/// The SP1 version an artifact declares, found without deserializing it. The version is
+/// written after the proof, so the last bincode string (u64 little-endian length, then the
+/// text) shaped like one is taken.
+pub fn declared_version(content: &[u8]) -> Option<Sp1Version> {
+    let version_re = Regex::new(r"(?-u)v[0-9]{1,3}\.[0-9]{1,3}\.[0-9]{1,3}(-[0-9A-Za-z.]+)?").expect("valid regex");
+    version_re
+        .find_iter(content)
+        .filter(|found| {
+            let start = found.start();
+            start >= 8 && content[start - 8..start] == (found.len() as u64).to_le_bytes()
+        })
+        .last()
+        .and_then(|found| std::str::from_utf8(found.as_bytes()).ok()?.parse().ok())
+}
+
+/// Load an SP1 artifact, explaining a failure by its declared version when it is from a
+/// release line this build does not read, and warning when a loaded one is
+pub fn load_artifact(path: &Path) -> Result<SP1ProofWithPublicValues> {
+    let proof = match SP1ProofWithPublicValues::load(path) {
+        Ok(proof) => proof,
+        Err(e) => {
+            let content = std::fs::read(path)?;
+            return Err(match declared_version(&content) {
+                Some(version) if !version.is_supported() => FailureClass::BadInput.error(format!(
+                    "{} is an SP1 {} artifact, but this build reads SP1 {} artifacts: convert it with a \
+                     rusty_router built against that SDK release ({})",
+                    path.display(),
+                    version,
+                    supported_releases().join(", "),
+                    e
+                )),
+                _ => e.context(format!("Failed to read SP1 artifact {}", path.display())),
+            });
+        }
+    };
+    match proof.sp1_version.parse::<Sp1Version>() {
+        Ok(version) if !version.is_supported() => warn!(
+            "{} declares SP1 {}, outside the supported {}; the conversion may fail",
+            path.display(),
+            version,
+            supported_releases().join(", ")
+        ),
+        Ok(_) => {}
+        Err(e) => warn!("{} declares an unrecognized SP1 version: {:#}", path.display(), e),
+    }
+    Ok(proof)
+}
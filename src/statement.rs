@@ -0,0 +1,49 @@
+use anyhow::Result;
+use serde::Serialize;
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+use subxt::utils::H256;
+
+use crate::converter::ConvertedProof;
+use crate::substrate::vk_hash;
+
+/// The parts zkVerify hashes into a proof's statement, and the statement itself
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Statement {
+    /// Keccak-256 of the verifier's name, e.g. `sp1` (`PROVING_SYSTEM_ID` in Solidity)
+    pub context: H256,
+    /// Keccak-256 of the SCALE-encoded VK, as registered
+    pub vk_hash: H256,
+    /// SHA-256 of the verifier version, empty for every proof system supported (zkVerify's
+    /// `NO_VERSION_HASH`)
+    pub version_hash: H256,
+    /// Keccak-256 of the public inputs, concatenated
+    pub pubs_hash: H256,
+    /// Keccak-256 of the four hashes above: the statement `ProofVerified` reports, and the leaf
+    /// the proof has in its aggregation's Merkle tree
+    pub statement: H256,
+}
+
+impl Statement {
+    /// Compute the statement of a converted proof exactly as the chain does, without
+    /// submitting it, e.g. for the value an Ethereum contract checks the aggregation for
+    pub fn of(converted_proof: &ConvertedProof) -> Result<Self> {
+        let backend = converted_proof.proof_type.backend();
+        backend.verify_locally(converted_proof)?;
+        let (_, pubs, _) = converted_proof.decode()?;
+        let context = keccak(converted_proof.proof_type.as_str().as_bytes());
+        let vk_hash = vk_hash(&backend.encode_args(converted_proof)?.vk);
+        let version_hash = H256(Sha256::digest(b"").into());
+        let pubs_hash = keccak(&pubs);
+
+        let mut data = Vec::with_capacity(4 * 32);
+        for hash in [context, vk_hash, version_hash, pubs_hash] {
+            data.extend_from_slice(hash.as_bytes());
+        }
+        Ok(Self { context, vk_hash, version_hash, pubs_hash, statement: keccak(&data) })
+    }
+}
+
+fn keccak(data: &[u8]) -> H256 {
+    H256(Keccak256::digest(data).into())
+}
@@ -0,0 +1,124 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::converter::{ConvertedProof, ProofType};
+use crate::output::write_atomic;
+
+/// Sizes and timing of one conversion, for capacity and fee planning
+#[derive(Debug, Clone, Serialize)]
+pub struct ProofStats {
+    /// Request ID, or the artifact path of a local conversion
+    pub source: String,
+    pub proof_type: ProofType,
+    pub artifact_bytes: u64,
+    /// SP1 proof variant: core, compressed, plonk or groth16
+    pub proof_variant: Option<String>,
+    /// SP1 version the artifact declares
+    pub sp1_version: Option<String>,
+    pub pubs_bytes: usize,
+    /// Size of the converted proof as written, after any compression
+    pub converted_bytes: usize,
+    pub conversion_seconds: f64,
+}
+
+impl ProofStats {
+    pub fn collect(
+        source: &str,
+        artifact_path: &Path,
+        converted_proof: &ConvertedProof,
+        converted_bytes: usize,
+        conversion_time: Duration,
+    ) -> Result<Self> {
+        // Captured during the conversion, so the artifact is not read again
+        let (proof_variant, sp1_version) = match &converted_proof.sp1_artifact {
+            Some(artifact) => (Some(artifact.variant.to_string()), Some(artifact.version.clone())),
+            None => (None, None),
+        };
+        let (_, pubs, _) = converted_proof.decode()?;
+        Ok(Self {
+            source: source.to_string(),
+            proof_type: converted_proof.proof_type,
+            artifact_bytes: std::fs::metadata(artifact_path)?.len(),
+            proof_variant,
+            sp1_version,
+            pubs_bytes: pubs.len(),
+            converted_bytes,
+            conversion_seconds: conversion_time.as_secs_f64(),
+        })
+    }
+}
+
+/// Collects the stats of every conversion of a router, in the order they finish
+#[derive(Debug, Default)]
+pub struct StatsRecorder {
+    stats: Mutex<Vec<ProofStats>>,
+}
+
+impl StatsRecorder {
+    pub fn record(&self, stats: ProofStats) {
+        self.stats.lock().expect("stats lock").push(stats);
+    }
+
+    pub fn snapshot(&self) -> Vec<ProofStats> {
+        self.stats.lock().expect("stats lock").clone()
+    }
+}
+
+/// One header row and one row per conversion
+pub fn to_csv(stats: &[ProofStats]) -> String {
+    let mut csv = String::from(
+        "source,proof_type,artifact_bytes,proof_variant,sp1_version,pubs_bytes,converted_bytes,conversion_seconds\n",
+    );
+    for entry in stats {
+        let fields = [
+            entry.source.clone(),
+            entry.proof_type.to_string(),
+            entry.artifact_bytes.to_string(),
+            entry.proof_variant.clone().unwrap_or_default(),
+            entry.sp1_version.clone().unwrap_or_default(),
+            entry.pubs_bytes.to_string(),
+            entry.converted_bytes.to_string(),
+            format!("{:.1}", entry.conversion_seconds),
+        ];
+        let row: Vec<String> = fields
+            .iter()
+            .map(|field| match field.contains([',', '"']) {
+                true => format!("\"{}\"", field.replace('"', "\"\"")),
+                false => field.clone(),
+            })
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Write `stats` as CSV to a `.csv` path, as a JSON array otherwise
+pub async fn write_stats(path: &Path, stats: &[ProofStats]) -> Result<()> {
+    let content = match path.extension().is_some_and(|extension| extension == "csv") {
+        true => to_csv(stats).into_bytes(),
+        false => serde_json::to_vec_pretty(stats)?,
+    };
+    write_atomic(path, content).await?;
+    Ok(())
+}
+
+/// Print the totals across `stats`
+pub fn print_totals(stats: &[ProofStats]) {
+    if stats.is_empty() {
+        return;
+    }
+    let count = stats.len();
+    let sum = |field: fn(&ProofStats) -> f64| stats.iter().map(field).sum::<f64>();
+    status!(
+        "📊 {} proofs: {:.0} bytes of artifacts, {:.0} bytes converted, {:.1}s converting ({:.1}s on average)",
+        count,
+        sum(|entry| entry.artifact_bytes as f64),
+        sum(|entry| entry.converted_bytes as f64),
+        sum(|entry| entry.conversion_seconds),
+        sum(|entry| entry.conversion_seconds) / count as f64
+    );
+}
@@ -1,35 +1,1291 @@
 use anyhow::Result;
 use subxt::{
-    config::PolkadotConfig,
+    backend::rpc::RpcClient,
+    client::OfflineClientT,
+    config::{
+        polkadot::PolkadotExtrinsicParamsBuilder, ExtrinsicParams, ExtrinsicParamsEncoder, PolkadotConfig,
+        PolkadotExtrinsicParams,
+    },
+    dynamic::{At, Value},
+    events::{EventDetails, Phase, StaticEvent},
+    ext::{
+        futures::{stream, Stream, StreamExt},
+        codec::{Compact, Encode},
+        scale_decode::DecodeAsType,
+        scale_value::{Composite, ValueDef},
+        subxt_rpcs::{self, rpc_params},
+    },
+    blocks::{Block, ExtrinsicEvents},
+    tx::{DynamicPayload, Payload, TxInBlock, TxStatus},
+    utils::{AccountId32, MultiSignature, H256},
     OnlineClient,
 };
 use subxt_signer::sr25519::Keypair;
+use jsonrpsee::ws_client::WsClientBuilder;
 use bip39::Mnemonic;
+use zeroize::Zeroize;
+use serde::{Deserialize, Serialize};
+use blake2::{digest::consts::U32, Blake2b, Blake2b512, Digest};
+use std::collections::VecDeque;
+use std::future::Future;
 use std::path::Path;
-use tracing::{debug, info, error};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+#[cfg(feature = "light-client")]
+use subxt::lightclient::LightClient;
+
+use crate::aggregation::{AggregationReceipt, DomainInfo, MerkleProof};
+use crate::backend::CallArgs;
+use crate::converter::{decompress, vk_value, ConvertedProof};
+use crate::failure::{FailureClass, ResultExt, RouterError};
+#[cfg(feature = "light-client")]
+use crate::network::LightClientOptions;
+use crate::network::{RateLimiter, RpcPolicy};
+use crate::progress;
+use crate::remark::{self, ChunkManifest, ChunkRef};
+use crate::signing::{Mortality, RawCall, UnsignedTx};
+use crate::statement::Statement;
+use tracing::{debug, info, instrument, warn, error};
+
+/// Mortality of submitted extrinsics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Era {
+    /// Valid until included, however long that takes
+    Immortal,
+    /// Valid for the given number of blocks from submission
+    Mortal(u64),
+}
+
+impl FromStr for Era {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.eq_ignore_ascii_case("immortal") {
+            return Ok(Era::Immortal);
+        }
+        let blocks: u64 = s
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid era '{}': expected 'immortal' or a number of blocks", s))?;
+        if blocks == 0 {
+            anyhow::bail!("Mortal era must last at least one block");
+        }
+        Ok(Era::Mortal(blocks))
+    }
+}
+
+/// A submission rate, in transactions per second; fractions allow less than one a second
+pub fn parse_rate(s: &str) -> Result<f64, RouterError> {
+    let rate: f64 = s
+        .trim()
+        .parse()
+        .map_err(|_| RouterError::InvalidInput(anyhow::anyhow!("Invalid rate '{}': expected transactions per second", s)))?;
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err(RouterError::InvalidInput(anyhow::anyhow!(
+            "Rate must be a positive number of transactions per second"
+        )));
+    }
+    Ok(rate)
+}
+
+/// Re-sign policy for transactions that are not included in time
+#[derive(Debug, Clone, Copy)]
+pub struct FeeBump {
+    /// Blocks to wait for inclusion before re-signing with a higher tip
+    pub after_blocks: u32,
+    /// Tip added on every re-signed attempt, in planck
+    pub tip_increment: u128,
+    /// Maximum number of re-signed attempts after the first one
+    pub max_attempts: u32,
+}
+
+/// Per-extrinsic signing options
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TxOptions {
+    /// Tip paid to the block author, in planck
+    pub tip: u128,
+    /// `None` keeps subxt's default (mortal for 32 blocks where possible)
+    pub era: Option<Era>,
+    /// `None` submits once and returns without waiting for inclusion
+    pub fee_bump: Option<FeeBump>,
+    /// Most submissions made while the same block is the best block
+    pub max_per_block: Option<u32>,
+    /// Most submissions in any rolling hour
+    pub max_per_hour: Option<u32>,
+    /// Most submissions a second, e.g. 0.5 for one every two seconds
+    pub max_per_second: Option<f64>,
+    /// Spread the `max_per_block` submissions evenly over the observed block time instead
+    /// of sending them as soon as a block starts
+    pub pace: bool,
+    /// Times a submission the pool turns away for a nonce already in use ("priority too low",
+    /// "already imported") is signed again with a fresh nonce
+    pub pool_retries: u32,
+}
+
+/// Recent submissions, for enforcing the per-block and per-hour limits
+#[derive(Debug, Default)]
+struct ThrottleState {
+    recent: VecDeque<Instant>,
+    block: Option<u32>,
+    in_block: u32,
+    last_sent: Option<Instant>,
+    /// Observed block time and when it was measured
+    block_time: Option<(Duration, Instant)>,
+}
+
+const HOUR: Duration = Duration::from_secs(3600);
+
+/// Blocks the block time is averaged over
+const BLOCK_TIME_SAMPLE: u32 = 100;
+
+/// How long an observed block time is used before it is measured again
+const BLOCK_TIME_REFRESH: Duration = Duration::from_secs(600);
+
+/// First wait before subscribing to runtime upgrades again, doubling up to [`MAX_RESUBSCRIBE_BACKOFF`]
+const RESUBSCRIBE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RESUBSCRIBE_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Wait before the first retry after a pool conflict, about one block; doubled after each one
+const POOL_RETRY_BACKOFF: Duration = Duration::from_secs(6);
+
+/// Pool error for a transaction that is already in the pool, byte for byte
+const POOL_ALREADY_IMPORTED: i32 = 1013;
+
+/// Pool error for a transaction whose nonce another transaction of ours holds with a tip at
+/// least as high
+const POOL_TOO_LOW_PRIORITY: i32 = 1014;
+
+// JSON-RPC error code the node answered a submission with, if it answered with one
+fn pool_error_code(error: &(dyn std::error::Error + 'static)) -> Option<i32> {
+    let mut cause = Some(error);
+    while let Some(error) = cause {
+        if let Some(error) = error.downcast_ref::<subxt_rpcs::UserError>() {
+            return Some(error.code);
+        }
+        if let Some(subxt_rpcs::Error::User(error)) = error.downcast_ref::<subxt_rpcs::Error>() {
+            return Some(error.code);
+        }
+        cause = error.source();
+    }
+    None
+}
+
+type Blake2b256 = Blake2b<U32>;
+
+/// Mortality subxt uses when no era is configured
+const DEFAULT_MORTAL_BLOCKS: u64 = 32;
+
+/// A submitted transaction and the block it was included in
+#[derive(Debug, Clone, Copy)]
+struct Included {
+    block_hash: H256,
+    extrinsic_hash: H256,
+}
+
+impl Included {
+    fn of(in_block: &TxInBlock<PolkadotConfig, OnlineClient<PolkadotConfig>>) -> Self {
+        Self { block_hash: in_block.block_hash(), extrinsic_hash: in_block.extrinsic_hash() }
+    }
+}
+
+// Statement of a `ProofVerified` event, whichever settlement pallet emitted it
+fn verified_statement(event: &EventDetails<PolkadotConfig>) -> Option<H256> {
+    let fields = event.field_bytes();
+    (event.pallet_name().starts_with("Settlement") && event.variant_name() == "ProofVerified" && fields.len() >= 32)
+        .then(|| H256::from_slice(&fields[..32]))
+}
+
+/// `Remarked` event emitted by `System::remark_with_event`
+#[derive(Debug, DecodeAsType)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+struct Remarked {
+    #[allow(dead_code)]
+    sender: AccountId32,
+    hash: H256,
+}
+
+impl StaticEvent for Remarked {
+    const PALLET: &'static str = "System";
+    const EVENT: &'static str = "Remarked";
+}
+
+/// `VkRegistered` event emitted by the settlement pallets
+#[derive(Debug, DecodeAsType)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+struct VkRegistered {
+    hash: H256,
+}
+
+impl StaticEvent for VkRegistered {
+    const PALLET: &'static str = "SettlementSp1Pallet";
+    const EVENT: &'static str = "VkRegistered";
+}
+
+/// `TransactionPayment.TransactionFeePaid`: the fee charged for an extrinsic, tip included
+#[derive(Debug, DecodeAsType)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+struct TransactionFeePaid {
+    #[allow(dead_code)]
+    who: AccountId32,
+    actual_fee: u128,
+    tip: u128,
+}
+
+impl StaticEvent for TransactionFeePaid {
+    const PALLET: &'static str = "TransactionPayment";
+    const EVENT: &'static str = "TransactionFeePaid";
+}
+
+/// `Aggregate.NewProof`: a statement was queued for aggregation in a domain
+#[derive(Debug, DecodeAsType)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+struct NewProof {
+    statement: H256,
+    domain_id: u32,
+    aggregation_id: u64,
+}
+
+impl StaticEvent for NewProof {
+    const PALLET: &'static str = "Aggregate";
+    const EVENT: &'static str = "NewProof";
+}
+
+/// `Aggregate.NewAggregationReceipt`: an aggregation was published
+#[derive(Debug, DecodeAsType)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+struct NewAggregationReceipt {
+    domain_id: u32,
+    aggregation_id: u64,
+    receipt: H256,
+}
+
+impl StaticEvent for NewAggregationReceipt {
+    const PALLET: &'static str = "Aggregate";
+    const EVENT: &'static str = "NewAggregationReceipt";
+}
+
+/// Call a remark is sent with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RemarkCall {
+    /// `System::remark`, which emits no event of its own
+    Remark,
+    /// `System::remark_with_event`, which emits `Remarked` with the remark's hash
+    #[default]
+    RemarkWithEvent,
+}
+
+impl RemarkCall {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RemarkCall::Remark => "remark",
+            RemarkCall::RemarkWithEvent => "remark_with_event",
+        }
+    }
+}
+
+impl FromStr for RemarkCall {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.replace('-', "_").as_str() {
+            "remark" => Ok(RemarkCall::Remark),
+            "remark_with_event" => Ok(RemarkCall::RemarkWithEvent),
+            _ => anyhow::bail!("Unknown remark call '{}': expected remark or remark_with_event", s),
+        }
+    }
+}
+
+/// Multisig account proofs are submitted from, the signer approving as one of its signatories
+#[derive(Debug, Clone)]
+pub struct Multisig {
+    /// Approvals needed to execute a call
+    pub threshold: u16,
+    /// All signatories, the signer included
+    pub signatories: Vec<AccountId32>,
+}
+
+impl Multisig {
+    // Signatories sorted and deduplicated, as the pallet expects them
+    fn sorted_signatories(&self) -> Vec<AccountId32> {
+        let mut signatories = self.signatories.clone();
+        signatories.sort_by(|a, b| a.0.cmp(&b.0));
+        signatories.dedup();
+        signatories
+    }
+
+    /// Account of the multisig, derived from its signatories and threshold as the pallet does
+    pub fn account_id(&self) -> AccountId32 {
+        let mut data = b"modlpy/utilisuba".to_vec();
+        self.sorted_signatories().encode_to(&mut data);
+        self.threshold.encode_to(&mut data);
+        AccountId32(Blake2b256::digest(&data).into())
+    }
+
+    // The signatories other than `signer`, checking that it is one and the threshold can be met
+    fn other_signatories(&self, signer: &AccountId32) -> Result<Vec<AccountId32>> {
+        let signatories = self.sorted_signatories();
+        if !signatories.contains(signer) {
+            return Err(FailureClass::BadInput.error("The signing account is not one of the multisig's signatories"));
+        }
+        if self.threshold < 2 || self.threshold as usize > signatories.len() {
+            return Err(FailureClass::BadInput.error(format!(
+                "Multisig threshold must be between 2 and the {} signatories",
+                signatories.len()
+            )));
+        }
+        Ok(signatories.into_iter().filter(|signatory| signatory != signer).collect())
+    }
+}
+
+/// A multisig call waiting for approvals
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingMultisig {
+    pub call_hash: H256,
+    /// Block and extrinsic index of the first approval, which later approvals refer to
+    pub height: u32,
+    pub index: u32,
+    /// Signatories that approved so far
+    pub approvals: Vec<String>,
+    pub threshold: u16,
+}
+
+/// How a proof submission refers to the proof's VK
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VkMode {
+    /// `VkOrHash::Vk`: the whole VK, in every submission
+    #[default]
+    Inline,
+    /// `VkOrHash::Hash` of a VK registered beforehand
+    Hash,
+    /// `VkOrHash::Hash`, registering the VK in the same `Utility::batch_all` when it is not yet
+    Register,
+}
+
+impl FromStr for VkMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "inline" => Ok(VkMode::Inline),
+            "hash" => Ok(VkMode::Hash),
+            "register" => Ok(VkMode::Register),
+            _ => anyhow::bail!("Unknown VK mode '{}': expected inline, hash or register", s),
+        }
+    }
+}
+
+/// Where a remark, or the manifest of a chunked one, was included
+#[derive(Debug, Clone, Copy)]
+pub struct RemarkInclusion {
+    pub tx_hash: H256,
+    pub block_hash: H256,
+    /// Chunk remarks sent before the manifest; 0 when the proof fit in one remark
+    pub chunks: usize,
+}
+
+/// An earlier verification of the same statement, found on chain
+#[derive(Debug, Clone, Copy)]
+pub struct PriorVerification {
+    pub tx_hash: H256,
+    pub block_number: u32,
+    pub block_hash: H256,
+    pub statement: H256,
+    /// Domain and aggregation the proof was queued into, if any
+    pub aggregation: Option<(u32, u64)>,
+}
+
+/// Outcome of a finalized proof submission
+#[derive(Debug, Clone)]
+pub struct ProofInclusion {
+    pub tx_hash: String,
+    pub block_hash: H256,
+    /// `None` when the block's header could not be fetched after the proof was finalized
+    pub block_number: Option<u32>,
+    pub statement: H256,
+    pub domain_id: Option<u32>,
+    /// Set when the proof was queued into a domain aggregation
+    pub aggregation_id: Option<u64>,
+    /// Events the submission emitted
+    pub events: Vec<SubmissionEvent>,
+}
+
+/// Event emitted by a submission's extrinsic, decoded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SubmissionEvent {
+    /// A settlement pallet verified the proof
+    ProofVerified { pallet: String, statement: H256 },
+    /// The statement was queued into an aggregation of a domain
+    NewProof { statement: H256, domain_id: u32, aggregation_id: u64 },
+    /// An aggregation was published, e.g. because the proof completed it
+    NewAggregationReceipt { domain_id: u32, aggregation_id: u64, receipt: H256 },
+    /// A VK was registered, with `--vk-mode register`
+    VkRegistered { pallet: String, hash: H256 },
+    /// Fee charged for the extrinsic, tip included, in planck
+    TransactionFeePaid { actual_fee: u128, tip: u128 },
+    /// The extrinsic failed with `error`: `Pallet::Error` for a pallet's error, the dispatch
+    /// error's kind otherwise
+    ExtrinsicFailed { error: String, docs: Option<String> },
+    /// A signatory approved a multisig call, without executing it
+    MultisigApproval { multisig: String, call_hash: H256 },
+    /// The last approval executed a multisig call; `error` is set when the call failed
+    MultisigExecuted { multisig: String, call_hash: H256, error: Option<String> },
+    /// A proxied call was dispatched on behalf of the real account; `error` is set when it failed
+    ProxyExecuted { error: Option<String> },
+    /// Any other event, with its fields; byte arrays are shown as hex
+    Other { pallet: String, event: String, fields: serde_json::Value },
+}
+
+/// Step reached by a watched submission
+#[derive(Debug, Clone)]
+pub enum TxEvent {
+    /// Sent to the pool; it cannot be in any block before `from_block`, the finalized head at the time
+    Broadcast { tx_hash: String, from_block: u32 },
+    /// Included in a best block, not yet finalized
+    InBlock { block_hash: H256 },
+}
+
+pub type OnTxEvent<'a> = &'a (dyn Fn(TxEvent) + Send + Sync);
+
+/// Where a previously broadcast extrinsic ended up
+#[derive(Debug, Clone)]
+pub enum ExtrinsicLookup {
+    Finalized { block_hash: H256, success: bool },
+    /// Not finalized yet, but may still be included
+    Pending,
+    /// Not included before its mortality ran out
+    Expired,
+}
+
+/// Balances of an account, in planck
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccountBalance {
+    pub free: u128,
+    /// Held by the runtime, e.g. for deposits; not spendable
+    pub reserved: u128,
+}
+
+/// Pallets the `events` command follows
+pub const WATCHED_PALLETS: [&str; 6] = [
+    "SettlementSp1Pallet",
+    "SettlementUltraplonkPallet",
+    "SettlementGroth16Pallet",
+    "SettlementFFlonkPallet",
+    "SettlementPlonky2Pallet",
+    "Aggregate",
+];
+
+/// Which events [`SubstrateClient::tail_events`] reports
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// Only events of extrinsics signed by this account
+    pub account: Option<AccountId32>,
+    /// Only events carrying this statement hash
+    pub statement: Option<H256>,
+}
+
+/// Event of a watched pallet in a finalized block
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainEvent {
+    pub block_number: u32,
+    pub block_hash: H256,
+    pub pallet: String,
+    pub event: String,
+    /// Signer of the extrinsic that emitted the event, if it came from a signed extrinsic
+    pub signer: Option<String>,
+    /// Event fields; byte arrays are shown as hex
+    pub fields: serde_json::Value,
+}
+
+impl std::fmt::Display for ChainEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{} {}.{} {}", self.block_number, self.pallet, self.event, self.fields)?;
+        if let Some(signer) = &self.signer {
+            write!(f, " (signed by {})", signer)?;
+        }
+        Ok(())
+    }
+}
+
+/// A finalized extrinsic and what it did
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtrinsicReport {
+    pub tx_hash: H256,
+    pub block_number: u32,
+    pub block_hash: H256,
+    /// Position of the extrinsic in its block
+    pub index: u32,
+    pub success: bool,
+    /// Fee charged to the signer, tip included, in planck
+    pub fee_paid: Option<u128>,
+    pub events: Vec<ChainEvent>,
+}
+
+// Account of a `MultiAddress::Id` signer address: a zero tag and the account id
+fn signer_account(address: Option<&[u8]>) -> Option<AccountId32> {
+    address
+        .and_then(|address| address.strip_prefix(&[0u8]))
+        .and_then(|id| <[u8; 32]>::try_from(id).ok())
+        .map(AccountId32)
+}
+
+// Event fields as JSON, with byte arrays such as hashes and account ids as hex strings
+fn fields_json(value: &Value<u32>) -> serde_json::Value {
+    use subxt::ext::scale_value::Primitive;
+
+    let composite = |composite: &Composite<u32>| match composite {
+        Composite::Named(fields) => {
+            serde_json::Value::Object(fields.iter().map(|(name, value)| (name.clone(), fields_json(value))).collect())
+        }
+        Composite::Unnamed(values) => match value_bytes(values) {
+            Some(bytes) if !bytes.is_empty() => serde_json::Value::String(format!("0x{}", hex::encode(bytes))),
+            _ => serde_json::Value::Array(values.iter().map(fields_json).collect()),
+        },
+    };
+
+    match &value.value {
+        ValueDef::Composite(fields) => composite(fields),
+        ValueDef::Variant(variant) => match variant.values.is_empty() {
+            true => serde_json::Value::String(variant.name.clone()),
+            false => serde_json::json!({ &variant.name: composite(&variant.values) }),
+        },
+        ValueDef::Primitive(Primitive::Bool(b)) => serde_json::Value::Bool(*b),
+        ValueDef::Primitive(Primitive::String(s)) => serde_json::Value::String(s.clone()),
+        // Balances and ids may not fit a JSON number
+        ValueDef::Primitive(Primitive::U128(n)) if *n <= u64::MAX as u128 => serde_json::Value::from(*n as u64),
+        ValueDef::Primitive(primitive) => serde_json::Value::String(primitive.to_string()),
+        ValueDef::BitSequence(_) => serde_json::Value::Null,
+    }
+}
+
+// A 32-byte field such as a hash or an account id, however many newtypes wrap the bytes
+fn field_bytes32(fields: &Value<u32>, name: &str) -> Option<[u8; 32]> {
+    let mut value = fields.at(name)?;
+    loop {
+        match &value.value {
+            ValueDef::Composite(Composite::Unnamed(values)) if values.len() == 1 => value = &values[0],
+            ValueDef::Composite(Composite::Unnamed(values)) => return value_bytes(values)?.try_into().ok(),
+            _ => return None,
+        }
+    }
+}
+
+// Every 32-byte array in `value`, in order, such as the accounts of a bounded vec
+fn collect_bytes32(value: &Value<u32>, found: &mut Vec<[u8; 32]>) {
+    let ValueDef::Composite(composite) = &value.value else { return };
+    if let Composite::Unnamed(values) = composite
+        && let Some(bytes) = value_bytes(values).and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+    {
+        found.push(bytes);
+        return;
+    }
+    for value in composite.values() {
+        collect_bytes32(value, found);
+    }
+}
+
+// The bytes of a decoded `Vec<u8>` or byte array, if the values are all bytes
+fn value_bytes(values: &[Value<u32>]) -> Option<Vec<u8>> {
+    use subxt::ext::scale_value::Primitive;
+
+    values
+        .iter()
+        .map(|value| match &value.value {
+            ValueDef::Primitive(Primitive::U128(byte)) => u8::try_from(*byte).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Finalized blocks searched for an earlier verification of a proof by default, about ten
+/// minutes of blocks
+pub const DEFAULT_DUPLICATE_CHECK_BLOCKS: u32 = 100;
+
+/// Blocks searched for an immortal extrinsic, which never expires
+const IMMORTAL_SEARCH_BLOCKS: u32 = 1024;
+
+/// Finalized blocks searched for the receipt of an aggregation that is already published
+const PUBLISHED_SEARCH_BLOCKS: u32 = 1024;
+
+/// Blocks fetched at once when searching back through finalized blocks
+const SEARCH_CONCURRENCY: usize = 16;
 
 pub struct SubstrateClient {
     client: OnlineClient<PolkadotConfig>,
-    signer: Keypair,
+    rpc: RpcClient,
+    /// `None` when connected for an externally signed account
+    signer: Option<Keypair>,
+    account_id: AccountId32,
+    tx_options: TxOptions,
+    throttle: Mutex<ThrottleState>,
+    rpc_policy: RpcPolicy,
+    rpc_limiter: RateLimiter,
+    ss58_prefix: u16,
+    remark_call: RemarkCall,
+    duplicate_check_blocks: u32,
+    vk_mode: VkMode,
+    multisig: Option<Multisig>,
+    proxy_for: Option<AccountId32>,
+}
+
+/// Address prefix of the generic Substrate format
+pub const GENERIC_SS58_PREFIX: u16 = 42;
+
+// Address prefix the runtime declares (`System.SS58Prefix`), or the generic one if it declares none
+fn chain_ss58_prefix(client: &OnlineClient<PolkadotConfig>) -> u16 {
+    let prefix = client
+        .constants()
+        .at(&subxt::dynamic::constant("System", "SS58Prefix"))
+        .ok()
+        .and_then(|prefix| prefix.to_value().ok())
+        .and_then(|prefix| prefix.as_u128())
+        .and_then(|prefix| u16::try_from(prefix).ok());
+    prefix.unwrap_or_else(|| {
+        debug!("The runtime declares no System.SS58Prefix, using the generic prefix {}", GENERIC_SS58_PREFIX);
+        GENERIC_SS58_PREFIX
+    })
+}
+
+/// SS58 encoding of `account_id` with the network's address `prefix`
+pub fn ss58_address(account_id: &AccountId32, prefix: u16) -> String {
+    let mut data = match prefix {
+        0..=63 => vec![prefix as u8],
+        // Two-byte prefixes, as laid out by the SS58 spec
+        _ => vec![
+            ((prefix & 0b1111_1100) >> 2) as u8 | 0b0100_0000,
+            ((prefix >> 8) as u8) | (((prefix & 0b11) as u8) << 6),
+        ],
+    };
+    data.extend_from_slice(&account_id.0);
+    let checksum = Blake2b512::new().chain_update(b"SS58PRE").chain_update(&data).finalize();
+    data.extend_from_slice(&checksum[..2]);
+    bs58::encode(data).into_string()
+}
+
+// Create the client, keeping the raw RPC client around for zkVerify-specific RPCs
+async fn connect(ws_url: &str, policy: &RpcPolicy) -> Result<(RpcClient, OnlineClient<PolkadotConfig>)> {
+    #[cfg(feature = "light-client")]
+    if let Some(light_client) = &policy.light_client {
+        return connect_light_client(ws_url, light_client).await.classify(FailureClass::Network);
+    }
+    info!("Connecting to Substrate node at: {}", ws_url);
+    let tls = match policy.tls.is_default() {
+        true => None,
+        false => Some(policy.tls.rustls_config()?),
+    };
+    policy
+        .run(&RateLimiter::new(None), "Connecting to the node", || async {
+            let rpc = match &tls {
+                Some(tls) => RpcClient::new(
+                    WsClientBuilder::new()
+                        .max_buffer_capacity_per_subscription(4096)
+                        .with_custom_cert_store(tls.clone())
+                        .build(ws_url)
+                        .await?,
+                ),
+                None => RpcClient::from_url(ws_url).await?,
+            };
+            let client = OnlineClient::<PolkadotConfig>::from_rpc_client(rpc.clone()).await?;
+            Ok((rpc, client))
+        })
+        .await
+        .classify(FailureClass::Network)
+}
+
+// Start a smoldot light client and talk to the chain through it. Without a chain spec file,
+// the spec is fetched from the node at `ws_url`, which is trusted for that alone.
+#[cfg(feature = "light-client")]
+async fn connect_light_client(
+    ws_url: &str,
+    options: &LightClientOptions,
+) -> Result<(RpcClient, OnlineClient<PolkadotConfig>)> {
+    let chain_spec = match &options.chain_spec {
+        Some(path) => tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read chain spec {}: {}", path.display(), e))?,
+        None => {
+            info!("Fetching the chain spec from {}", ws_url);
+            subxt::utils::fetch_chainspec_from_rpc_node(ws_url).await?.get().to_string()
+        }
+    };
+    status!("💡 Syncing a light client of the chain from its boot nodes...");
+    // The RPC handle keeps the light client running; the client handle is only needed to add chains
+    let (_light_client, light_client_rpc) = LightClient::relay_chain(chain_spec.as_str())?;
+    let rpc = RpcClient::new(light_client_rpc);
+    let client = OnlineClient::<PolkadotConfig>::from_rpc_client(rpc.clone()).await?;
+    info!("Light client synced");
+    Ok((rpc, client))
+}
+
+/// Bytes of a hex VK as written to `proof.json`, which may be hex-encoded twice
+pub fn decode_vk(vk: &str) -> Result<Vec<u8>, RouterError> {
+    let vk = vk.trim_start_matches("0x");
+    let vk = if vk.len() > 64 {
+        let inner = String::from_utf8(hex::decode(vk)?).map_err(|_| anyhow::anyhow!("Invalid VK '{}'", vk))?;
+        hex::decode(inner.trim_start_matches("0x"))?
+    } else {
+        hex::decode(vk)?
+    };
+    if vk.len() != 32 {
+        return Err(RouterError::InvalidInput(anyhow::anyhow!("Invalid VK: expected 32 bytes, got {}", vk.len())));
+    }
+    Ok(vk)
+}
+
+/// Hash zkVerify registers `vk` under: Keccak-256 of its SCALE encoding, which for an
+/// SP1 VK is the raw 32 bytes
+pub fn vk_hash(vk: &[u8]) -> H256 {
+    H256(sha3::Keccak256::digest(vk).into())
+}
+
+// `Option<Timepoint>` of a multisig call: the first approval's, for later approvals
+fn timepoint(pending: Option<&PendingMultisig>) -> subxt::dynamic::Value {
+    match pending {
+        Some(pending) => subxt::dynamic::Value::unnamed_variant(
+            "Some",
+            vec![subxt::dynamic::Value::named_composite([
+                ("height", subxt::dynamic::Value::u128(pending.height as u128)),
+                ("index", subxt::dynamic::Value::u128(pending.index as u128)),
+            ])],
+        ),
+        None => subxt::dynamic::Value::unnamed_variant("None", vec![]),
+    }
+}
+
+fn weight(ref_time: u64, proof_size: u64) -> subxt::dynamic::Value {
+    subxt::dynamic::Value::named_composite([
+        ("ref_time", subxt::dynamic::Value::u128(ref_time as u128)),
+        ("proof_size", subxt::dynamic::Value::u128(proof_size as u128)),
+    ])
+}
+
+// The chain refused, dropped or failed a transaction
+fn rejected(message: String) -> anyhow::Error {
+    FailureClass::ChainRejected.error(message)
 }
 
 impl SubstrateClient {
-    pub async fn new(ws_url: &str, mnemonic: &str) -> Result<Self> {
-        info!("Connecting to Substrate node at: {}", ws_url);
-        
-        // Create the client
-        let client = OnlineClient::<PolkadotConfig>::from_url(ws_url).await?;
+    pub async fn new(ws_url: &str, mnemonic: &str) -> Result<Self, RouterError> {
+        Self::new_with_policy(ws_url, mnemonic, RpcPolicy::default()).await
+    }
+
+    /// Connect under `rpc_policy`, which also applies to the client's read-only queries
+    pub async fn new_with_policy(ws_url: &str, mnemonic: &str, rpc_policy: RpcPolicy) -> Result<Self, RouterError> {
+        let (rpc, client) = connect(ws_url, &rpc_policy).await?;
         
-        // Create the signer from mnemonic
-        let mnemonic = Mnemonic::parse_normalized(mnemonic)?;
-        let keypair = Keypair::from_phrase(&mnemonic, None)?;
+        // Create the signer from mnemonic, wiping the parsed words once the key is derived
+        let mut mnemonic = Mnemonic::parse_normalized(mnemonic).map_err(|e| RouterError::InvalidInput(e.into()))?;
+        let keypair = Keypair::from_phrase(&mnemonic, None);
+        mnemonic.zeroize();
+        let keypair = keypair.map_err(|e| RouterError::InvalidInput(e.into()))?;
         
         info!("Connected to Substrate node successfully");
         
-        Ok(Self { client, signer: keypair })
+        let account_id = keypair.public_key().to_account_id();
+        let ss58_prefix = chain_ss58_prefix(&client);
+        Ok(Self {
+            client,
+            rpc,
+            signer: Some(keypair),
+            account_id,
+            tx_options: TxOptions::default(),
+            throttle: Mutex::default(),
+            rpc_limiter: RateLimiter::new(rpc_policy.max_requests_per_second),
+            rpc_policy,
+            ss58_prefix,
+            remark_call: RemarkCall::default(),
+            duplicate_check_blocks: DEFAULT_DUPLICATE_CHECK_BLOCKS,
+            vk_mode: VkMode::default(),
+            multisig: None,
+            proxy_for: None,
+        })
+    }
+
+    /// Connect for an account whose key is held elsewhere; transactions for it can be
+    /// prepared with [`Self::prepare_proof_submission`] but not signed here.
+    pub async fn new_without_signer(ws_url: &str, account_id: AccountId32) -> Result<Self, RouterError> {
+        Self::new_without_signer_with_policy(ws_url, account_id, RpcPolicy::default()).await
+    }
+
+    /// [`Self::new_without_signer`] under `rpc_policy`
+    pub async fn new_without_signer_with_policy(
+        ws_url: &str,
+        account_id: AccountId32,
+        rpc_policy: RpcPolicy,
+    ) -> Result<Self, RouterError> {
+        let (rpc, client) = connect(ws_url, &rpc_policy).await?;
+        let ss58_prefix = chain_ss58_prefix(&client);
+        Ok(Self {
+            client,
+            rpc,
+            signer: None,
+            account_id,
+            tx_options: TxOptions::default(),
+            throttle: Mutex::default(),
+            rpc_limiter: RateLimiter::new(rpc_policy.max_requests_per_second),
+            rpc_policy,
+            ss58_prefix,
+            remark_call: RemarkCall::default(),
+            duplicate_check_blocks: DEFAULT_DUPLICATE_CHECK_BLOCKS,
+            vk_mode: VkMode::default(),
+            multisig: None,
+            proxy_for: None,
+        })
+    }
+
+    fn keypair(&self) -> Result<&Keypair> {
+        self.signer
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No signing key available; use prepare-tx and submit-signed instead"))
+    }
+
+    /// Account transactions are signed for
+    pub fn account_id(&self) -> &AccountId32 {
+        &self.account_id
+    }
+
+    /// SS58 address of the account, in the network's format
+    pub fn address(&self) -> String {
+        ss58_address(&self.account_id, self.ss58_prefix)
+    }
+
+    /// Address prefix used when printing addresses; the one the connected runtime declares
+    pub fn ss58_prefix(&self) -> u16 {
+        self.ss58_prefix
+    }
+
+    /// Print addresses with `ss58_prefix` instead of the runtime's
+    pub fn with_ss58_prefix(mut self, ss58_prefix: u16) -> Self {
+        self.ss58_prefix = ss58_prefix;
+        self
+    }
+
+    pub fn with_tx_options(mut self, tx_options: TxOptions) -> Self {
+        self.tx_options = tx_options;
+        self
+    }
+
+    /// Call proofs are sent as remarks with
+    pub fn with_remark_call(mut self, remark_call: RemarkCall) -> Self {
+        self.remark_call = remark_call;
+        self
+    }
+
+    /// How submissions refer to the proof's VK
+    pub fn with_vk_mode(mut self, vk_mode: VkMode) -> Self {
+        self.vk_mode = vk_mode;
+        self
+    }
+
+    /// Submit proofs from this multisig, approving them as the signer
+    pub fn with_multisig(mut self, multisig: Option<Multisig>) -> Self {
+        self.multisig = multisig;
+        self
+    }
+
+    /// Submit proofs on behalf of this account, which made the signer one of its proxies
+    pub fn with_proxy_for(mut self, real: Option<AccountId32>) -> Self {
+        self.proxy_for = real;
+        self
+    }
+
+    /// Finalized blocks searched for an earlier verification of a proof before submitting it;
+    /// 0 submits without checking
+    pub fn with_duplicate_check_blocks(mut self, blocks: u32) -> Self {
+        self.duplicate_check_blocks = blocks;
+        self
+    }
+
+    // Run a read-only query under the RPC policy
+    async fn query<T, F, Fut>(&self, what: &str, query: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.rpc_policy.run(&self.rpc_limiter, what, query).await
+    }
+
+    // Extrinsic params reflecting the configured tip and era
+    fn params_builder(&self) -> PolkadotExtrinsicParamsBuilder<PolkadotConfig> {
+        let builder = PolkadotExtrinsicParamsBuilder::new().tip(self.tx_options.tip);
+        match self.tx_options.era {
+            Some(Era::Immortal) => builder.immortal(),
+            Some(Era::Mortal(blocks)) => builder.mortal(blocks),
+            None => builder,
+        }
+    }
+
+    // Wait until another submission fits within the configured rate limits, and record it.
+    // The send time is reserved under the lock and waited for without it, so concurrent
+    // submissions queue up behind each other instead of behind the lock.
+    async fn throttle(&self) -> Result<()> {
+        // Queried before locking, so no RPC call holds up other submissions
+        let block_time = match (self.tx_options.pace, self.tx_options.max_per_block) {
+            (true, Some(_)) => Some(self.block_time().await?),
+            _ => None,
+        };
+        let mut best_blocks = match self.tx_options.max_per_block {
+            Some(_) => {
+                let mut blocks = self.client.blocks().subscribe_best().await?;
+                let best = match blocks.next().await {
+                    Some(block) => block?.number(),
+                    None => anyhow::bail!("Best block subscription ended"),
+                };
+                Some((blocks, best))
+            }
+            None => None,
+        };
+
+        let (send_at, target_block) = {
+            let mut state = self.throttle.lock().await;
+            let now = Instant::now();
+            while state.recent.front().is_some_and(|sent| now.saturating_duration_since(*sent) >= HOUR) {
+                state.recent.pop_front();
+            }
+
+            let mut send_at = now;
+            if let Some(max_per_hour) = self.tx_options.max_per_hour
+                && let Some(index) = state.recent.len().checked_sub(max_per_hour.max(1) as usize)
+            {
+                // A slot frees up when the submission `max_per_hour` back leaves the hour
+                send_at = send_at.max(state.recent[index] + HOUR);
+                status!(
+                    "⏳ {} submissions in the last hour, waiting {:?}",
+                    max_per_hour,
+                    send_at.saturating_duration_since(now)
+                );
+            }
+
+            let mut interval = self.tx_options.max_per_second.map(|rate| Duration::from_secs_f64(1.0 / rate));
+            if let (Some(block_time), Some(max_per_block)) = (block_time, self.tx_options.max_per_block) {
+                interval = interval.max(Some(block_time / max_per_block.max(1)));
+            }
+            if let (Some(interval), Some(last_sent)) = (interval, state.last_sent)
+                && last_sent + interval > send_at
+            {
+                debug!(
+                    "Pacing submissions {:?} apart, waiting {:?}",
+                    interval,
+                    (last_sent + interval).saturating_duration_since(now)
+                );
+                send_at = last_sent + interval;
+            }
+
+            // The first block with room for the submission, counting those already reserved
+            let mut target_block = None;
+            if let (Some(max_per_block), Some((_, best))) = (self.tx_options.max_per_block, &best_blocks) {
+                let block = state.block.map_or(*best, |block| block.max(*best));
+                if state.block != Some(block) {
+                    state.block = Some(block);
+                    state.in_block = 0;
+                }
+                if state.in_block >= max_per_block {
+                    state.block = Some(block + 1);
+                    state.in_block = 0;
+                }
+                state.in_block += 1;
+                target_block = state.block;
+            }
+
+            state.recent.push_back(send_at);
+            state.last_sent = Some(send_at);
+            (send_at, target_block)
+        };
+
+        tokio::time::sleep_until(send_at.into()).await;
+        if let (Some((blocks, best)), Some(target_block)) = (best_blocks.as_mut(), target_block)
+            && *best < target_block
+        {
+            status!("⏳ Block #{} is full of submissions, waiting for block #{}", best, target_block);
+            while *best < target_block {
+                *best = match blocks.next().await {
+                    Some(block) => block?.number(),
+                    None => anyhow::bail!("Best block subscription ended"),
+                };
+            }
+        }
+        Ok(())
+    }
+
+    // Average time between the last finalized blocks, from their timestamps, remeasured
+    // every BLOCK_TIME_REFRESH
+    async fn block_time(&self) -> Result<Duration> {
+        let cached = self.throttle.lock().await.block_time;
+        if let Some((block_time, measured)) = cached
+            && measured.elapsed() < BLOCK_TIME_REFRESH
+        {
+            return Ok(block_time);
+        }
+        let head = self.finalized_block_number().await?;
+        // The genesis block has no timestamp
+        let from = head.saturating_sub(BLOCK_TIME_SAMPLE).max(1);
+        let elapsed = self.block_timestamp(head).await?.saturating_sub(self.block_timestamp(from).await?);
+        let block_time = Duration::from_millis(elapsed / u64::from(head.saturating_sub(from).max(1)));
+        info!("Observed block time: {:?}", block_time);
+        self.throttle.lock().await.block_time = Some((block_time, Instant::now()));
+        Ok(block_time)
+    }
+
+    // `Timestamp.Now` of block `number`, in milliseconds
+    async fn block_timestamp(&self, number: u32) -> Result<u64> {
+        let block_hash: Option<H256> = self
+            .query("Fetching a block hash", || async {
+                Ok(self.rpc.request("chain_getBlockHash", rpc_params![number]).await?)
+            })
+            .await?;
+        let block_hash = block_hash.ok_or_else(|| anyhow::anyhow!("Block #{} not found", number))?;
+        let query = subxt::dynamic::storage("Timestamp", "Now", ());
+        let now = self
+            .query("Fetching a block timestamp", || async {
+                Ok(self.client.storage().at(block_hash).fetch(&query).await?)
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Block #{} has no timestamp", number))?
+            .to_value()?;
+        now.as_u128()
+            .map(|millis| millis as u64)
+            .ok_or_else(|| anyhow::anyhow!("Unexpected Timestamp.Now layout"))
+    }
+
+    // Sign and submit a call according to the configured options, returning the extrinsic hash
+    async fn submit<Call: Payload>(&self, call: &Call) -> Result<String> {
+        if self.tx_options.fee_bump.is_some() {
+            let included = self.submit_and_wait(call, &|_| {}).await?;
+            return Ok(format!("{:?}", included.extrinsic_hash));
+        }
+
+        self.throttle().await?;
+
+        let tx_hash = self
+            .retry_pool_conflicts(|| async {
+                let tx = self.client.tx().create_signed(call, self.keypair()?, self.params_builder().build()).await?;
+                match tx.submit().await {
+                    Err(e) if pool_error_code(&e) == Some(POOL_ALREADY_IMPORTED) => {
+                        warn!("Transaction {:?} is already in the pool, not submitting it again", tx.hash());
+                        Ok(tx.hash())
+                    }
+                    result => Ok(result?),
+                }
+            })
+            .await?;
+        Ok(format!("{:?}", tx_hash))
+    }
+
+    // Run `submit` again, with a doubling backoff, while the pool turns it away because
+    // another of our transactions holds its nonce (1014). subxt fetches the next nonce, pool
+    // included, whenever it signs without one, so every run signs with a fresh one. A
+    // transaction already in the pool (1013) is ours and is never signed again.
+    async fn retry_pool_conflicts<T, F, Fut>(&self, mut submit: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut backoff = POOL_RETRY_BACKOFF;
+        let mut retries = 0;
+        loop {
+            match submit().await {
+                Err(e) if retries < self.tx_options.pool_retries && pool_error_code(e.as_ref()) == Some(POOL_TOO_LOW_PRIORITY) => {
+                    retries += 1;
+                    warn!(
+                        "Transaction pool conflict ({:#}), signing again with a fresh nonce in {:?} ({}/{})",
+                        e, backoff, retries, self.tx_options.pool_retries
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    // Sign and submit a call, waiting until it is in a block (finalized, unless fee bumping)
+    async fn submit_and_wait<Call: Payload>(&self, call: &Call, on_event: OnTxEvent<'_>) -> Result<Included> {
+        self.throttle().await?;
+        if let Some(fee_bump) = self.tx_options.fee_bump {
+            return self.submit_with_fee_bump(call, fee_bump, on_event).await;
+        }
+
+        let from_block = self.finalized_block_number().await?;
+        let (tx_hash, progress) = self
+            .retry_pool_conflicts(|| async {
+                let tx = self.client.tx().create_signed(call, self.keypair()?, self.params_builder().build()).await?;
+                match tx.submit_and_watch().await {
+                    Ok(progress) => Ok((tx.hash(), Some(progress))),
+                    Err(e) if pool_error_code(&e) == Some(POOL_ALREADY_IMPORTED) => Ok((tx.hash(), None)),
+                    Err(e) => Err(e.into()),
+                }
+            })
+            .await?;
+        on_event(TxEvent::Broadcast { tx_hash: format!("{:?}", tx_hash), from_block });
+        let Some(mut progress) = progress else {
+            warn!("Transaction {:?} is already in the pool, waiting for it instead of submitting it again", tx_hash);
+            return self.wait_for_inclusion(tx_hash, from_block).await;
+        };
+        let bar = progress::confirmation("Broadcast, waiting for a block".to_string());
+        bar.set_position(1);
+
+        while let Some(status) = progress.next().await {
+            match status? {
+                TxStatus::InBestBlock(in_block) => {
+                    bar.set_position(2);
+                    bar.set_message(format!("In block {:?}, waiting for finality", in_block.block_hash()));
+                    on_event(TxEvent::InBlock { block_hash: in_block.block_hash() });
+                }
+                TxStatus::InFinalizedBlock(in_block) => return Ok(Included::of(&in_block)),
+                TxStatus::Error { message } => return Err(rejected(format!("Transaction error: {}", message))),
+                TxStatus::Invalid { message } => return Err(rejected(format!("Transaction invalid: {}", message))),
+                TxStatus::Dropped { message } => return Err(rejected(format!("Transaction dropped: {}", message))),
+                _ => {}
+            }
+        }
+        Err(rejected("Transaction status stream ended before finalization".to_string()))
+    }
+
+    // Blocks after its submission a transaction can be included in, given the configured era
+    fn inclusion_window(&self) -> u32 {
+        // Mortal periods are rounded up to a power of two when encoded
+        match self.tx_options.era {
+            Some(Era::Immortal) => IMMORTAL_SEARCH_BLOCKS,
+            Some(Era::Mortal(blocks)) => blocks.next_power_of_two() as u32,
+            None => DEFAULT_MORTAL_BLOCKS.next_power_of_two() as u32,
+        }
+    }
+
+    // Follow finalized blocks until the transaction `tx_hash`, which another submission already
+    // put in the pool, is in one, for as long as it could be included
+    async fn wait_for_inclusion(&self, tx_hash: H256, from_block: u32) -> Result<Included> {
+        let last = from_block.saturating_add(self.inclusion_window());
+        let mut blocks = self.client.blocks().subscribe_finalized().await.classify(FailureClass::Network)?;
+        while let Some(block) = blocks.next().await {
+            let block = block.classify(FailureClass::Network)?;
+            if block.extrinsics().await?.iter().any(|extrinsic| extrinsic.hash() == tx_hash) {
+                return Ok(Included { block_hash: block.hash(), extrinsic_hash: tx_hash });
+            }
+            if block.number() >= last {
+                return Err(rejected(format!("Transaction {:?} left the pool without being included", tx_hash)));
+            }
+        }
+        Err(FailureClass::Network.error("Finalized block subscription ended"))
+    }
+
+    // Events of an included transaction
+    async fn included_events(&self, included: &Included) -> Result<ExtrinsicEvents<PolkadotConfig>> {
+        let extrinsics = self
+            .query("Fetching block extrinsics", || async {
+                Ok(self.client.blocks().at(included.block_hash).await?.extrinsics().await?)
+            })
+            .await?;
+        let extrinsic = extrinsics
+            .iter()
+            .find(|extrinsic| extrinsic.hash() == included.extrinsic_hash)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Extrinsic {:?} is not in block {:?}", included.extrinsic_hash, included.block_hash)
+            })?;
+        Ok(extrinsic.events().await?)
+    }
+
+    // Events of an included transaction, failing when its dispatch failed
+    async fn included_success(&self, included: &Included) -> Result<ExtrinsicEvents<PolkadotConfig>> {
+        let events = self.included_events(included).await?;
+        for event in events.iter() {
+            let event = event?;
+            if event.pallet_name() == "System" && event.variant_name() == "ExtrinsicFailed" {
+                let fields = Value { value: ValueDef::Composite(event.field_values()?), context: 0 };
+                let (error, docs) = self.dispatch_error(fields.at("dispatch_error"));
+                return Err(rejected(match docs {
+                    Some(docs) => format!("Transaction failed with {}: {}", error, docs),
+                    None => format!("Transaction failed with {}", error),
+                }));
+            }
+        }
+        Ok(events)
+    }
+
+    // Submit and watch the call; if it is not in a block after `after_blocks` blocks,
+    // re-sign it with the same nonce and a higher tip. Earlier attempts stay watched,
+    // since any of them may still be the one that lands. Once one is in a block, no more
+    // are signed and it is followed until it is finalized.
+    async fn submit_with_fee_bump<Call: Payload>(&self, call: &Call, fee_bump: FeeBump, on_event: OnTxEvent<'_>) -> Result<Included> {
+        let signer = self.keypair()?;
+        let from_block = self.finalized_block_number().await?;
+        let mut blocks = self.client.blocks().subscribe_best().await?;
+        let mut watched = stream::SelectAll::new();
+        let total_attempts = fee_bump.max_attempts + 1;
+        let bar = progress::confirmation("Broadcasting".to_string());
+        let sign_and_watch = |nonce: u64, attempt: u32| async move {
+            let tip = self.tx_options.tip.saturating_add(fee_bump.tip_increment.saturating_mul(attempt as u128 - 1));
+            let params = self.params_builder().tip(tip).nonce(nonce).build();
+            let tx = self.client.tx().create_signed(call, signer, params).await?;
+            let tx_hash = tx.hash();
+            info!("Attempt {}/{}: submitting {:?} with nonce {} and tip {}", attempt, total_attempts, tx_hash, nonce, tip);
+            Ok::<_, anyhow::Error>((tx_hash, tx.submit_and_watch().await?))
+        };
+        // Later attempts replace the first one, so only the first is retried with a fresh nonce
+        let (nonce, first) = self
+            .retry_pool_conflicts(|| async {
+                let nonce = self.client.tx().account_nonce(&self.account_id).await?;
+                Ok((nonce, sign_and_watch(nonce, 1).await?))
+            })
+            .await?;
+        let mut first = Some(first);
+        let mut in_block = false;
+
+        for attempt in 1..=total_attempts {
+            let (tx_hash, progress) = match first.take() {
+                Some(first) => first,
+                None => sign_and_watch(nonce, attempt).await?,
+            };
+            on_event(TxEvent::Broadcast { tx_hash: format!("{:?}", tx_hash), from_block });
+            watched.push(progress.map(move |status| (attempt, tx_hash, status)));
+            bar.set_position(1);
+            bar.set_message(format!("Attempt {}/{} broadcast, waiting for a block", attempt, total_attempts));
+
+            let mut blocks_waited = 0;
+            while in_block || blocks_waited < fee_bump.after_blocks {
+                tokio::select! {
+                    update = watched.next() => match update {
+                        Some((landed, _, Ok(TxStatus::InBestBlock(best)))) => {
+                            status!("📦 Attempt {}/{} in block {:?}, waiting for finality", landed, total_attempts, best.block_hash());
+                            bar.set_position(2);
+                            bar.set_message(format!("Attempt {}/{} in a block, waiting for finality", landed, total_attempts));
+                            on_event(TxEvent::InBlock { block_hash: best.block_hash() });
+                            in_block = true;
+                        }
+                        Some((landed, _, Ok(TxStatus::InFinalizedBlock(finalized)))) => {
+                            status!("✅ Attempt {}/{} finalized in block {:?}", landed, total_attempts, finalized.block_hash());
+                            return Ok(Included::of(&finalized));
+                        }
+                        Some((attempt, _, Ok(TxStatus::Error { message } | TxStatus::Invalid { message } | TxStatus::Dropped { message }))) => {
+                            debug!("Attempt {} left the pool: {}", attempt, message);
+                        }
+                        Some((_, _, Ok(_))) => {}
+                        Some((attempt, _, Err(e))) => debug!("Attempt {} watch failed: {}", attempt, e),
+                        None => return Err(rejected("All submission attempts were dropped from the pool".to_string())),
+                    },
+                    block = blocks.next(), if !in_block => {
+                        if let Some(block) = block {
+                            block?;
+                        }
+                        blocks_waited += 1;
+                        bar.set_message(format!(
+                            "Attempt {}/{} not in a block after {}/{} blocks",
+                            attempt, total_attempts, blocks_waited, fee_bump.after_blocks
+                        ));
+                    }
+                }
+            }
+
+            if attempt < total_attempts {
+                warn!("Not included after {} blocks, re-signing with a higher tip", fee_bump.after_blocks);
+            }
+        }
+
+        Err(rejected(format!(
+            "Transaction not included after {} attempts of {} blocks each",
+            total_attempts, fee_bump.after_blocks
+        )))
     }
     
-    pub async fn list_available_pallets(&self) -> Result<()> {
+    pub async fn list_available_pallets(&self) -> Result<(), RouterError> {
         info!("Fetching available pallets from the network...");
         
         // For now, just log that we're connected
@@ -39,128 +1295,1259 @@ impl SubstrateClient {
         Ok(())
     }
     
-    pub async fn send_system_remark(&self, remark: &[u8]) -> Result<String> {
-        info!("Preparing system.remark transaction...");
-        
-        // Create the system.remark call
-        let call = subxt::dynamic::tx("System", "remark", vec![remark.to_vec()]);
-        
-        // Submit the transaction
-        let tx_hash = self
-            .client
-            .tx()
-            .sign_and_submit_default(&call, &self.signer)
+    // `System.Account` entry of the signer in the latest finalized block
+    async fn account_info(&self) -> Result<Option<subxt::dynamic::Value<u32>>> {
+        let query = subxt::dynamic::storage(
+            "System",
+            "Account",
+            vec![subxt::dynamic::Value::from_bytes(&self.account_id)],
+        );
+
+        let account = self
+            .query("Fetching the account", || async {
+                Ok(self.client.storage().at_latest().await?.fetch(&query).await?)
+            })
             .await?;
-            
-        info!("Transaction submitted successfully with hash: {:?}", tx_hash);
-        
-        Ok(format!("{:?}", tx_hash))
+
+        match account {
+            Some(account) => Ok(Some(account.to_value()?)),
+            // Accounts that never received funds have no storage entry
+            None => Ok(None),
+        }
+    }
+
+    /// Free and reserved balance of the signer account
+    pub async fn balance(&self) -> Result<AccountBalance, RouterError> {
+        let Some(account) = self.account_info().await? else {
+            return Ok(AccountBalance::default());
+        };
+
+        let data = account.at("data");
+        let amount = |field: &str| {
+            data.and_then(|data| data.at(field))
+                .and_then(|amount| amount.as_u128())
+                .ok_or_else(|| anyhow::anyhow!("Unexpected System.Account layout"))
+        };
+        let balance = AccountBalance { free: amount("free")?, reserved: amount("reserved")? };
+        debug!("Balance: {:?}", balance);
+
+        Ok(balance)
+    }
+
+    /// Free balance of the signer account, in planck
+    pub async fn free_balance(&self) -> Result<u128, RouterError> {
+        Ok(self.balance().await?.free)
+    }
+
+    /// Whether a VK with this hash is registered in the SP1 settlement pallet
+    pub async fn is_vk_registered(&self, hash: H256) -> Result<bool, RouterError> {
+        self.is_vk_registered_in("SettlementSp1Pallet", hash).await
+    }
+
+    /// Whether a VK with this hash is registered in the settlement pallet `pallet`
+    pub async fn is_vk_registered_in(&self, pallet: &str, hash: H256) -> Result<bool, RouterError> {
+        let query = subxt::dynamic::storage(pallet, "Vks", vec![subxt::dynamic::Value::from_bytes(hash)]);
+        let entry = self
+            .query("Fetching the VK registration", || async {
+                Ok(self.client.storage().at_latest().await?.fetch(&query).await?)
+            })
+            .await?;
+        Ok(entry.is_some())
+    }
+
+    /// Register `vk` in the SP1 settlement pallet, so proofs can refer to it by hash;
+    /// returns the hash and the block it was registered in
+    #[instrument(skip(self))]
+    pub async fn register_vk(&self, vk: &[u8]) -> Result<(H256, H256), RouterError> {
+        let call = subxt::dynamic::tx("SettlementSp1Pallet", "register_vk", vec![vk_value(vk)]);
+
+        status!("📤 Registering VK and waiting for finalization...");
+        let included = self.submit_and_wait(&call, &|_| {}).await?;
+        let events = self.included_success(&included).await?;
+        let hash = events
+            .find_first::<VkRegistered>()?
+            .map(|ev| ev.hash)
+            .ok_or_else(|| rejected("No VkRegistered event emitted by the registration".to_string()))?;
+        Ok((hash, included.block_hash))
+    }
+
+    /// Nonce the next transaction from the signer will use, including pending pool transactions
+    pub async fn next_nonce(&self) -> Result<u64, RouterError> {
+        Ok(self
+            .query("Fetching the account nonce", || async {
+                Ok(self.client.tx().account_nonce(&self.account_id).await?)
+            })
+            .await?)
+    }
+
+    /// Nonce of the signer account in the latest finalized block
+    pub async fn finalized_nonce(&self) -> Result<u64, RouterError> {
+        let Some(account) = self.account_info().await? else {
+            return Ok(0);
+        };
+
+        let nonce = account
+            .at("nonce")
+            .and_then(|nonce| nonce.as_u128())
+            .ok_or_else(|| anyhow::anyhow!("Unexpected System.Account layout"))?;
+        Ok(nonce as u64)
+    }
+
+    /// Apply runtime upgrades as they are enacted, so calls keep being encoded with the
+    /// pallet and call indices of the current metadata. For long-running modes: when the
+    /// subscription fails or ends, it is made again with a growing backoff, forever.
+    pub async fn follow_runtime_upgrades(&self) -> Result<(), RouterError> {
+        let updater = self.client.updater();
+        let mut backoff = RESUBSCRIBE_BACKOFF;
+        loop {
+            match updater.runtime_updates().await {
+                Ok(mut updates) => {
+                    while let Some(update) = updates.next().await {
+                        let update = match update {
+                            Ok(update) => update,
+                            Err(e) => {
+                                warn!("Runtime upgrade subscription failed: {:#}", e);
+                                break;
+                            }
+                        };
+                        // Following again, so the next failure starts from the shortest wait
+                        backoff = RESUBSCRIBE_BACKOFF;
+                        let from = self.client.runtime_version().spec_version;
+                        let to = update.runtime_version().spec_version;
+                        // The first update repeats the runtime connected to, which is not applied again
+                        if updater.apply_update(update).is_ok() {
+                            status!("🔄 Runtime upgraded from spec {} to {}; metadata refreshed", from, to);
+                            info!(from, to, "Runtime upgrade applied");
+                        }
+                    }
+                    warn!("Runtime upgrade subscription ended, subscribing again in {:?}", backoff);
+                }
+                Err(e) => warn!("Failed to subscribe to runtime upgrades ({:#}), retrying in {:?}", e, backoff),
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_RESUBSCRIBE_BACKOFF);
+        }
+    }
+
+    pub async fn finalized_block_number(&self) -> Result<u32, RouterError> {
+        Ok(self
+            .query("Fetching the finalized head", || async {
+                Ok(self.client.blocks().at_latest().await?.number())
+            })
+            .await?)
+    }
+
+    /// Search the finalized blocks from `from_block` on for the extrinsic `tx_hash`,
+    /// as far as it could have been included given the configured era.
+    pub async fn find_extrinsic(&self, tx_hash: &str, from_block: u32) -> Result<ExtrinsicLookup, RouterError> {
+        let tx_hash: H256 = tx_hash
+            .parse()
+            .map_err(|_| RouterError::InvalidInput(anyhow::anyhow!("Invalid extrinsic hash '{}'", tx_hash)))?;
+        let window = self.inclusion_window();
+        let head = self.finalized_block_number().await?;
+        let last = head.min(from_block.saturating_add(window));
+
+        for number in from_block..=last {
+            let block_hash: Option<H256> = self
+                .query("Fetching a block hash", || async {
+                    Ok(self.rpc.request("chain_getBlockHash", rpc_params![number]).await?)
+                })
+                .await?;
+            let Some(block_hash) = block_hash else { continue };
+            let extrinsics = self
+                .query("Fetching block extrinsics", || async {
+                    Ok(self.client.blocks().at(block_hash).await?.extrinsics().await?)
+                })
+                .await?;
+            for extrinsic in extrinsics.iter() {
+                if extrinsic.hash() != tx_hash {
+                    continue;
+                }
+                let events = extrinsic.events().await?;
+                let success = !events.iter().any(|event| {
+                    event.is_ok_and(|event| event.pallet_name() == "System" && event.variant_name() == "ExtrinsicFailed")
+                });
+                return Ok(ExtrinsicLookup::Finalized { block_hash, success });
+            }
+        }
+
+        if head >= from_block.saturating_add(window) && !matches!(self.tx_options.era, Some(Era::Immortal)) {
+            Ok(ExtrinsicLookup::Expired)
+        } else {
+            Ok(ExtrinsicLookup::Pending)
+        }
+    }
+
+    // Decode an event of a submission's extrinsic
+    fn submission_event(&self, event: &EventDetails<PolkadotConfig>) -> Result<SubmissionEvent> {
+        let pallet = event.pallet_name().to_string();
+        if let Some(statement) = verified_statement(event) {
+            return Ok(SubmissionEvent::ProofVerified { pallet, statement });
+        }
+        if let Some(new_proof) = event.as_event::<NewProof>()? {
+            let NewProof { statement, domain_id, aggregation_id } = new_proof;
+            return Ok(SubmissionEvent::NewProof { statement, domain_id, aggregation_id });
+        }
+        if let Some(receipt) = event.as_event::<NewAggregationReceipt>()? {
+            let NewAggregationReceipt { domain_id, aggregation_id, receipt } = receipt;
+            return Ok(SubmissionEvent::NewAggregationReceipt { domain_id, aggregation_id, receipt });
+        }
+        if let Some(fee) = event.as_event::<TransactionFeePaid>()? {
+            return Ok(SubmissionEvent::TransactionFeePaid { actual_fee: fee.actual_fee, tip: fee.tip });
+        }
+        let fields = event.field_bytes();
+        if pallet.starts_with("Settlement") && event.variant_name() == "VkRegistered" && fields.len() >= 32 {
+            return Ok(SubmissionEvent::VkRegistered { pallet, hash: H256::from_slice(&fields[..32]) });
+        }
+        let fields = Value { value: ValueDef::Composite(event.field_values()?), context: 0 };
+        if pallet == "System" && event.variant_name() == "ExtrinsicFailed" {
+            let (error, docs) = self.dispatch_error(fields.at("dispatch_error"));
+            return Ok(SubmissionEvent::ExtrinsicFailed { error, docs });
+        }
+        if pallet == "Multisig"
+            && let (Some(multisig), Some(call_hash)) = (field_bytes32(&fields, "multisig"), field_bytes32(&fields, "call_hash"))
+        {
+            let multisig = ss58_address(&AccountId32(multisig), self.ss58_prefix);
+            let call_hash = H256(call_hash);
+            match event.variant_name() {
+                "NewMultisig" | "MultisigApproval" => return Ok(SubmissionEvent::MultisigApproval { multisig, call_hash }),
+                "MultisigExecuted" => {
+                    let error = self.result_error(&fields);
+                    return Ok(SubmissionEvent::MultisigExecuted { multisig, call_hash, error });
+                }
+                _ => {}
+            }
+        }
+        if pallet == "Proxy" && event.variant_name() == "ProxyExecuted" {
+            return Ok(SubmissionEvent::ProxyExecuted { error: self.result_error(&fields) });
+        }
+        Ok(SubmissionEvent::Other { pallet, event: event.variant_name().to_string(), fields: fields_json(&fields) })
+    }
+
+    // An event whose fields could not be decoded; a failed extrinsic is still reported as one
+    fn undecoded_event(event: &EventDetails<PolkadotConfig>) -> SubmissionEvent {
+        if event.pallet_name() == "System" && event.variant_name() == "ExtrinsicFailed" {
+            return SubmissionEvent::ExtrinsicFailed { error: "unknown".to_string(), docs: None };
+        }
+        SubmissionEvent::Other {
+            pallet: event.pallet_name().to_string(),
+            event: event.variant_name().to_string(),
+            fields: serde_json::Value::Null,
+        }
+    }
+
+    // The error of an event's `result: DispatchResult` field, if the dispatch failed
+    fn result_error(&self, fields: &Value<u32>) -> Option<String> {
+        match fields.at("result").map(|result| &result.value) {
+            Some(ValueDef::Variant(result)) if result.name == "Err" => Some(self.dispatch_error(result.values.values().next()).0),
+            _ => None,
+        }
+    }
+
+    // `Pallet::Error` and its docs for a pallet's error, the error's kind otherwise, from a
+    // decoded `DispatchError`
+    fn dispatch_error(&self, error: Option<&Value<u32>>) -> (String, Option<String>) {
+        let Some(ValueDef::Variant(error)) = error.map(|error| &error.value) else {
+            return ("unknown".to_string(), None);
+        };
+        let module = error.values.values().next();
+        let index = module.and_then(|module| module.at("index")).and_then(|index| index.as_u128());
+        let code = match module.and_then(|module| module.at("error")).map(|code| &code.value) {
+            Some(ValueDef::Composite(Composite::Unnamed(code))) => value_bytes(code).and_then(|code| code.first().copied()),
+            _ => None,
+        };
+        let metadata = self.client.metadata();
+        if error.name == "Module"
+            && let (Some(index), Some(code)) = (index, code)
+            && let Some(pallet) = metadata.pallet_by_index(index as u8)
+            && let Some(variant) = pallet.error_variant_by_index(code)
+        {
+            let docs = variant.docs.join(" ");
+            return (format!("{}::{}", pallet.name(), variant.name), (!docs.is_empty()).then_some(docs));
+        }
+        (error.name.clone(), None)
+    }
+
+    fn chain_event(
+        &self,
+        event: &EventDetails<PolkadotConfig>,
+        block_number: u32,
+        block_hash: H256,
+        signer: Option<&AccountId32>,
+    ) -> Result<ChainEvent> {
+        Ok(ChainEvent {
+            block_number,
+            block_hash,
+            pallet: event.pallet_name().to_string(),
+            event: event.variant_name().to_string(),
+            signer: signer.map(|account| ss58_address(account, self.ss58_prefix)),
+            fields: fields_json(&Value { value: ValueDef::Composite(event.field_values()?), context: 0 }),
+        })
+    }
+
+    /// Find the extrinsic `tx_hash` in `block_hash`, or else in the last `search_depth`
+    /// finalized blocks, and report its outcome; `None` if it is not there
+    pub async fn extrinsic_report(
+        &self,
+        tx_hash: H256,
+        block_hash: Option<H256>,
+        search_depth: u32,
+    ) -> Result<Option<ExtrinsicReport>, RouterError> {
+        let _spinner = progress::spinner(format!("Searching for extrinsic {:?}", tx_hash));
+        let blocks = self
+            .candidate_blocks(block_hash, search_depth)
+            .await?
+            .map(|block_hash| async move {
+                let block_hash = block_hash?;
+                let block = self
+                    .query("Fetching a block", || async { Ok(self.client.blocks().at(block_hash).await?) })
+                    .await?;
+                let extrinsics = block.extrinsics().await?;
+                anyhow::Ok((block_hash, block, extrinsics))
+            })
+            .buffered(SEARCH_CONCURRENCY);
+        let mut blocks = std::pin::pin!(blocks);
+        while let Some(fetched) = blocks.next().await {
+            let (block_hash, block, extrinsics) = fetched?;
+            let Some(extrinsic) = extrinsics.iter().find(|extrinsic| extrinsic.hash() == tx_hash) else {
+                continue;
+            };
+
+            let signer = signer_account(extrinsic.address_bytes());
+            let extrinsic_events = extrinsic.events().await?;
+            let mut events = Vec::new();
+            for event in extrinsic_events.iter() {
+                events.push(self.chain_event(&event?, block.number(), block_hash, signer.as_ref())?);
+            }
+            return Ok(Some(ExtrinsicReport {
+                tx_hash,
+                block_number: block.number(),
+                block_hash,
+                index: extrinsic.index(),
+                success: !events.iter().any(|event| event.pallet == "System" && event.event == "ExtrinsicFailed"),
+                fee_paid: extrinsic_events.find_first::<TransactionFeePaid>()?.map(|fee| fee.actual_fee),
+                events,
+            }));
+        }
+        Ok(None)
+    }
+
+    // Hashes of the finalized blocks from `head` back `depth` blocks, newest first, fetched
+    // `SEARCH_CONCURRENCY` at a time as the stream is read
+    fn blocks_back(&self, head: u32, depth: u32) -> impl Stream<Item = Result<H256>> + '_ {
+        stream::iter((head.saturating_sub(depth)..=head).rev())
+            .map(move |number| async move {
+                let hash: Option<H256> = self
+                    .query("Fetching a block hash", || async {
+                        Ok(self.rpc.request("chain_getBlockHash", rpc_params![number]).await?)
+                    })
+                    .await?;
+                Ok(hash)
+            })
+            .buffered(SEARCH_CONCURRENCY)
+            .filter_map(|hash| async move { hash.transpose() })
+    }
+
+    // `block_hash` if known, or else the last `search_depth` finalized blocks, newest first,
+    // fetched as the stream is read so that a search can stop at its first hit
+    async fn candidate_blocks(
+        &self,
+        block_hash: Option<H256>,
+        search_depth: u32,
+    ) -> Result<impl Stream<Item = Result<H256>> + '_> {
+        Ok(match block_hash {
+            Some(block_hash) => stream::iter([Ok(block_hash)]).left_stream(),
+            None => self.blocks_back(self.finalized_block_number().await?, search_depth).right_stream(),
+        })
+    }
+
+    /// The bytes of the `System` remark `tx_hash`, looked up like [`Self::extrinsic_report`]
+    /// does; `None` if the extrinsic is not there
+    pub async fn find_remark(
+        &self,
+        tx_hash: H256,
+        block_hash: Option<H256>,
+        search_depth: u32,
+    ) -> Result<Option<Vec<u8>>, RouterError> {
+        let blocks = self
+            .candidate_blocks(block_hash, search_depth)
+            .await?
+            .map(|block_hash| async move {
+                let block_hash = block_hash?;
+                self.query("Fetching block extrinsics", || async {
+                    Ok(self.client.blocks().at(block_hash).await?.extrinsics().await?)
+                })
+                .await
+            })
+            .buffered(SEARCH_CONCURRENCY);
+        let mut blocks = std::pin::pin!(blocks);
+        while let Some(extrinsics) = blocks.next().await {
+            let extrinsics = extrinsics?;
+            let Some(extrinsic) = extrinsics.iter().find(|extrinsic| extrinsic.hash() == tx_hash) else {
+                continue;
+            };
+            if extrinsic.pallet_name()? != "System" || !extrinsic.variant_name()?.starts_with("remark") {
+                return Err(FailureClass::BadInput
+                    .error(format!(
+                        "Extrinsic {:?} is a {}.{} call, not a remark",
+                        tx_hash,
+                        extrinsic.pallet_name()?,
+                        extrinsic.variant_name()?
+                    ))
+                    .into());
+            }
+            let fields = Value { value: ValueDef::Composite(extrinsic.field_values()?), context: 0 };
+            let remark = match fields.at("remark").map(|remark| &remark.value) {
+                Some(ValueDef::Composite(Composite::Unnamed(bytes))) => value_bytes(bytes),
+                _ => None,
+            };
+            return Ok(Some(
+                remark.ok_or_else(|| anyhow::anyhow!("Unexpected System.remark layout in {:?}", tx_hash))?,
+            ));
+        }
+        Ok(None)
+    }
+
+    /// The most recent `ProofVerified` event of the converted proof's statement within the
+    /// configured number of finalized blocks, so that the same proof is not paid for twice.
+    /// With a `domain_id`, only a verification that queued the proof into that domain counts.
+    pub async fn find_prior_verification(
+        &self,
+        converted_proof: &ConvertedProof,
+        domain_id: Option<u32>,
+    ) -> Result<Option<PriorVerification>, RouterError> {
+        if self.duplicate_check_blocks == 0 {
+            return Ok(None);
+        }
+        let statement = Statement::of(converted_proof).classify(FailureClass::BadInput)?.statement;
+        let _spinner = progress::spinner(format!(
+            "Checking the last {} blocks for statement {:?}",
+            self.duplicate_check_blocks, statement
+        ));
+
+        let head = self.finalized_block_number().await?;
+        let blocks = self
+            .blocks_back(head, self.duplicate_check_blocks)
+            .map(|block_hash| async move {
+                let block_hash = block_hash?;
+                let block = self
+                    .query("Fetching a block", || async { Ok(self.client.blocks().at(block_hash).await?) })
+                    .await?;
+                let events = block.events().await?;
+                anyhow::Ok((block, events))
+            })
+            .buffered(SEARCH_CONCURRENCY);
+        let mut blocks = std::pin::pin!(blocks);
+        while let Some(fetched) = blocks.next().await {
+            let (block, events) = fetched?;
+            let mut verified_in = None;
+            let mut aggregation = None;
+            for event in events.iter() {
+                let event = event?;
+                if verified_statement(&event) == Some(statement)
+                    && let Phase::ApplyExtrinsic(index) = event.phase()
+                {
+                    verified_in = Some(index);
+                }
+                if let Some(new_proof) = event.as_event::<NewProof>()?
+                    && new_proof.statement == statement
+                {
+                    aggregation = Some((new_proof.domain_id, new_proof.aggregation_id));
+                }
+            }
+            let Some(index) = verified_in else { continue };
+            if let Some(domain_id) = domain_id
+                && aggregation.is_none_or(|(domain, _)| domain != domain_id)
+            {
+                debug!(
+                    "Statement {:?} verified at block {} was not queued into domain {}",
+                    statement,
+                    block.number(),
+                    domain_id
+                );
+                continue;
+            }
+            let extrinsics = block.extrinsics().await?;
+            let Some(extrinsic) = extrinsics.iter().find(|extrinsic| extrinsic.index() == index) else {
+                continue;
+            };
+            return Ok(Some(PriorVerification {
+                tx_hash: extrinsic.hash(),
+                block_number: block.number(),
+                block_hash: block.hash(),
+                statement,
+                aggregation,
+            }));
+        }
+        Ok(None)
+    }
+
+    /// Follow finalized blocks, passing each event of the [`WATCHED_PALLETS`] that matches
+    /// `filter` to `on_event`, until the subscription ends
+    pub async fn tail_events(&self, filter: &EventFilter, mut on_event: impl FnMut(ChainEvent)) -> Result<(), RouterError> {
+        let mut blocks = self.client.blocks().subscribe_finalized().await.classify(FailureClass::Network)?;
+        while let Some(block) = blocks.next().await {
+            let block = block.classify(FailureClass::Network)?;
+            let extrinsics = block.extrinsics().await?;
+            let signers: Vec<Option<AccountId32>> =
+                extrinsics.iter().map(|extrinsic| signer_account(extrinsic.address_bytes())).collect();
+            debug!("Scanning finalized block #{}", block.number());
+
+            for event in block.events().await?.iter() {
+                let event = event?;
+                if !WATCHED_PALLETS.contains(&event.pallet_name()) {
+                    continue;
+                }
+                let signer = match event.phase() {
+                    Phase::ApplyExtrinsic(index) => signers.get(index as usize).cloned().flatten(),
+                    _ => None,
+                };
+                if let Some(account) = &filter.account
+                    && signer.as_ref() != Some(account)
+                {
+                    continue;
+                }
+                if let Some(statement) = filter.statement {
+                    let carried = match verified_statement(&event) {
+                        Some(verified) => Some(verified),
+                        None => event.as_event::<NewProof>()?.map(|new_proof| new_proof.statement),
+                    };
+                    if carried != Some(statement) {
+                        continue;
+                    }
+                }
+
+                on_event(self.chain_event(&event, block.number(), block.hash(), signer.as_ref())?);
+            }
+        }
+        Err(FailureClass::Network.error("Finalized block subscription ended").into())
+    }
+
+    /// Wait until the finalized nonce reaches `target`, so that a later run signing from the
+    /// same account cannot pick a nonce that is still in flight.
+    pub async fn wait_for_nonce_settled(&self, target: u64, timeout: Duration) -> Result<(), RouterError> {
+        status!("⏳ Waiting for account nonce to reach {}...", target);
+        let mut blocks = self.client.blocks().subscribe_finalized().await?;
+
+        let settle = async {
+            loop {
+                let nonce = self.finalized_nonce().await?;
+                if nonce >= target {
+                    status!("✅ Account nonce settled at {}", nonce);
+                    return Ok(());
+                }
+                debug!("Finalized nonce {} < {}", nonce, target);
+
+                match blocks.next().await {
+                    Some(block) => {
+                        block?;
+                    }
+                    None => anyhow::bail!("Finalized block subscription ended before the nonce settled"),
+                }
+            }
+        };
+
+        Ok(tokio::time::timeout(timeout, settle)
+            .await
+            .map_err(|_| anyhow::anyhow!("Timed out waiting for the account nonce to reach {}", target))??)
+    }
+
+    /// Send `remark` and wait until it is included. With `remark_with_event`, the `Remarked`
+    /// event must also carry the remark's hash, so the data is known to be on chain.
+    pub async fn send_system_remark(&self, remark: &[u8]) -> Result<RemarkInclusion, RouterError> {
+        info!("Preparing system.{} transaction...", self.remark_call.as_str());
+        let call = subxt::dynamic::tx("System", self.remark_call.as_str(), vec![remark.to_vec()]);
+
+        let included = self.submit_and_wait(&call, &|_| {}).await?;
+        let events = self.included_success(&included).await?;
+        if self.remark_call == RemarkCall::RemarkWithEvent {
+            let remarked = events
+                .find_first::<Remarked>()?
+                .ok_or_else(|| rejected("No Remarked event emitted by the remark".to_string()))?;
+            let expected = H256(Blake2b256::digest(remark).into());
+            if remarked.hash != expected {
+                return Err(rejected(format!("Remarked event carries hash {:?}, expected {:?}", remarked.hash, expected)).into());
+            }
+        }
+
+        let inclusion = RemarkInclusion { tx_hash: included.extrinsic_hash, block_hash: included.block_hash, chunks: 0 };
+        info!("Remark {:?} included in block {:?}", inclusion.tx_hash, inclusion.block_hash);
+        Ok(inclusion)
     }
     
-    pub async fn send_proof_as_remark(&self, proof_path: &Path) -> Result<String> {
+    /// Send a proof file as a remark, or, when it is larger than a remark can be, as numbered
+    /// chunk remarks followed by a manifest remark listing them. Returns where the remark
+    /// holding the proof, or the manifest, was included.
+    pub async fn send_proof_as_remark(&self, proof_path: &Path) -> Result<RemarkInclusion, RouterError> {
         info!("Reading proof file from: {}", proof_path.display());
         
         // Read the proof file
         let proof_data = tokio::fs::read(proof_path).await?;
         debug!("Proof file size: {} bytes", proof_data.len());
-        
-        // Send as system.remark
-        self.send_system_remark(&proof_data).await
+
+        let max_remark = self.max_remark_size()?;
+        if proof_data.len() <= max_remark {
+            return self.send_system_remark(&proof_data).await;
+        }
+
+        let chunks = remark::split(&proof_data, max_remark)?;
+        status!(
+            "✂️  Proof is {} bytes, more than the {} a remark can hold: sending it in {} chunks",
+            proof_data.len(),
+            max_remark,
+            chunks.len()
+        );
+        let mut chunk_refs = Vec::with_capacity(chunks.len());
+        for (index, chunk) in chunks.iter().enumerate() {
+            // Each chunk is waited for, so the manifest can name the block it is in
+            let inclusion = self.send_system_remark(chunk).await?;
+            status!("📦 Chunk {}/{} in block {:?}", index + 1, chunks.len(), inclusion.block_hash);
+            chunk_refs.push(ChunkRef { tx_hash: inclusion.tx_hash, block_hash: inclusion.block_hash });
+        }
+
+        let manifest = ChunkManifest {
+            rusty_router_chunks: remark::MANIFEST_VERSION,
+            file_name: proof_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+            size: proof_data.len(),
+            sha256: remark::content_sha256(&proof_data),
+            chunks: chunk_refs,
+        };
+        let inclusion = self.send_system_remark(&serde_json::to_vec(&manifest)?).await?;
+        status!("🧾 Chunk manifest sent in {:?}; rebuild the proof with `reassemble {:?}`", inclusion.tx_hash, inclusion.tx_hash);
+        Ok(RemarkInclusion { chunks: chunks.len(), ..inclusion })
+    }
+
+    // Largest remark a normal extrinsic can carry: the block length available to the normal
+    // dispatch class, less room for the signature, extensions and call encoding
+    fn max_remark_size(&self) -> Result<usize> {
+        const EXTRINSIC_OVERHEAD: usize = 1024;
+        let block_length = self
+            .client
+            .constants()
+            .at(&subxt::dynamic::constant("System", "BlockLength"))?
+            .to_value()?;
+        let normal = block_length
+            .at("max")
+            .and_then(|max| max.at("normal"))
+            .and_then(|normal| normal.as_u128())
+            .ok_or_else(|| anyhow::anyhow!("Unexpected System.BlockLength layout"))?;
+        Ok((normal as usize).saturating_sub(EXTRINSIC_OVERHEAD))
     }
     
-    pub async fn submit_proof_to_zkverify(&self, proof_path: &Path) -> Result<String> {
-        println!("📄 Reading proof file...");
-        
-        // Read the proof file
-        let proof_data = tokio::fs::read(proof_path).await?;
-        
-        // Parse the JSON to extract proof and public inputs
-        let proof_json: serde_json::Value = serde_json::from_slice(&proof_data)?;
-        
-        let proof_hex = proof_json["proof"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Missing 'proof' field in JSON"))?;
-        
-        // Try both 'pubs' and 'pub_inputs' field names for compatibility
-        let pub_inputs_hex = proof_json.get("pubs")
-            .or_else(|| proof_json.get("pub_inputs"))
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Missing 'pubs' or 'pub_inputs' field in JSON"))?;
-        
-        // Remove 0x prefix if present
-        let proof_hex = proof_hex.strip_prefix("0x").unwrap_or(proof_hex);
-        let pub_inputs_hex = pub_inputs_hex.strip_prefix("0x").unwrap_or(pub_inputs_hex);
-        
-        // Convert hex to bytes
-        let proof_bytes = hex::decode(proof_hex)?;
-        let pub_inputs_bytes = hex::decode(pub_inputs_hex)?;
+    // The proof's submission, wrapped in `Proxy::proxy` when submitting on behalf of another
+    // account and in `Multisig::as_multi` when submitting from a multisig
+    async fn submit_proof_call(&self, proof_path: &Path, domain_id: Option<u32>) -> Result<DynamicPayload> {
+        let call = self.proxied(self.proof_call(proof_path, domain_id).await?);
+        match &self.multisig {
+            Some(multisig) => self.as_multi(multisig, call).await,
+            None => Ok(call),
+        }
+    }
+
+    /// Hash of the call a multisig submission of the proof approves, for the other signatories
+    /// to approve with `approve_as_multi`
+    pub async fn multisig_call_hash(&self, proof_path: &Path, domain_id: Option<u32>) -> Result<H256, RouterError> {
+        let call = self.proxied(self.proof_call(proof_path, domain_id).await?);
+        Ok(H256(Blake2b256::digest(self.client.tx().call_data(&call)?).into()))
+    }
+
+    // `call` dispatched as the `with_proxy_for` account, with any proxy type it granted the signer
+    fn proxied(&self, call: DynamicPayload) -> DynamicPayload {
+        let Some(real) = &self.proxy_for else {
+            return call;
+        };
+        subxt::dynamic::tx("Proxy", "proxy", vec![
+            subxt::dynamic::Value::unnamed_variant("Id", vec![subxt::dynamic::Value::from_bytes(real.0)]),
+            subxt::dynamic::Value::unnamed_variant("None", vec![]),
+            call.into_value(),
+        ])
+    }
+
+    // Approve `call` as the signer, executing it when the approval is the last one needed
+    async fn as_multi(&self, multisig: &Multisig, call: DynamicPayload) -> Result<DynamicPayload> {
+        let others = multisig.other_signatories(&self.account_id)?;
+        let call_data = self.client.tx().call_data(&call)?;
+        let call_hash = H256(Blake2b256::digest(&call_data).into());
+        let pending = self.pending_multisig(multisig, call_hash).await?;
+        let approvals = pending.as_ref().map_or(0, |pending| pending.approvals.len());
+        if pending.as_ref().is_some_and(|pending| pending.approvals.contains(&self.address())) {
+            return Err(FailureClass::BadInput.error(format!(
+                "{} already approved multisig call {:?}; it needs another signatory's approval",
+                self.address(),
+                call_hash
+            )));
+        }
+        status!(
+            "🔐 Multisig {}: approving call {:?} ({}/{})",
+            ss58_address(&multisig.account_id(), self.ss58_prefix),
+            call_hash,
+            approvals + 1,
+            multisig.threshold
+        );
+        let (ref_time, proof_size) = self.call_weight(&call_data).await?;
+        Ok(subxt::dynamic::tx("Multisig", "as_multi", vec![
+            subxt::dynamic::Value::u128(multisig.threshold as u128),
+            subxt::dynamic::Value::unnamed_composite(others.iter().map(|signatory| subxt::dynamic::Value::from_bytes(signatory.0))),
+            timepoint(pending.as_ref()),
+            call.into_value(),
+            weight(ref_time, proof_size),
+        ]))
+    }
+
+    /// Approve the multisig call `call_hash` by its hash as the signer. It is executed by the
+    /// signatory who approves last with the whole call, normally the router submitting the proof.
+    pub async fn approve_multisig(&self, multisig: &Multisig, call_hash: H256) -> Result<H256, RouterError> {
+        let others = multisig.other_signatories(&self.account_id)?;
+        let pending = self.pending_multisig(multisig, call_hash).await?;
+        let call = subxt::dynamic::tx("Multisig", "approve_as_multi", vec![
+            subxt::dynamic::Value::u128(multisig.threshold as u128),
+            subxt::dynamic::Value::unnamed_composite(others.iter().map(|signatory| subxt::dynamic::Value::from_bytes(signatory.0))),
+            timepoint(pending.as_ref()),
+            subxt::dynamic::Value::from_bytes(call_hash),
+            // Approving by hash never executes the call
+            weight(0, 0),
+        ]);
+        let included = self.submit_and_wait(&call, &|_| {}).await?;
+        self.included_success(&included).await?;
+        Ok(included.block_hash)
+    }
+
+    /// Approvals so far of the multisig call `call_hash`, if it is waiting for more
+    pub async fn pending_multisig(
+        &self,
+        multisig: &Multisig,
+        call_hash: H256,
+    ) -> Result<Option<PendingMultisig>, RouterError> {
+        let query = subxt::dynamic::storage(
+            "Multisig",
+            "Multisigs",
+            vec![subxt::dynamic::Value::from_bytes(multisig.account_id()), subxt::dynamic::Value::from_bytes(call_hash)],
+        );
+        let Some(pending) = self
+            .query("Fetching the multisig call", || async {
+                Ok(self.client.storage().at_latest().await?.fetch(&query).await?)
+            })
+            .await?
+        else {
+            return Ok(None);
+        };
+        let pending = pending.to_value()?;
+        let layout = || anyhow::anyhow!("Unexpected Multisig.Multisigs layout");
+        let number = |name: &str| {
+            pending.at("when").and_then(|when| when.at(name)).and_then(|value| value.as_u128()).ok_or_else(layout)
+        };
+        let mut approvals = Vec::new();
+        collect_bytes32(pending.at("approvals").ok_or_else(layout)?, &mut approvals);
+        let approvals = approvals
+            .into_iter()
+            .map(|account| ss58_address(&AccountId32(account), self.ss58_prefix))
+            .collect();
+        Ok(Some(PendingMultisig {
+            call_hash,
+            height: number("height")? as u32,
+            index: number("index")? as u32,
+            approvals,
+            threshold: multisig.threshold,
+        }))
+    }
+
+    // Weight of dispatching `call_data`, as the runtime's fee estimation reports it
+    async fn call_weight(&self, call_data: &[u8]) -> Result<(u64, u64)> {
+        let mut params = call_data.to_vec();
+        (call_data.len() as u32).encode_to(&mut params);
+        // `RuntimeDispatchInfo` starts with the weight, both of its parts compact encoded
+        let (ref_time, proof_size): (Compact<u64>, Compact<u64>) = self
+            .query("Estimating the call weight", || async {
+                Ok(self
+                    .client
+                    .runtime_api()
+                    .at_latest()
+                    .await?
+                    .call_raw("TransactionPaymentCallApi_query_call_info", Some(&params))
+                    .await?)
+            })
+            .await?;
+        Ok((ref_time.0, proof_size.0))
+    }
+
+    // Build the `submit_proof` call of the proof's settlement pallet from a converted proof file
+    async fn proof_call(&self, proof_path: &Path, domain_id: Option<u32>) -> Result<DynamicPayload> {
+        status!("📄 Reading proof file...");
         
-        println!("✅ Proof decomposed: {} bytes proof, {} bytes public inputs", proof_bytes.len(), pub_inputs_bytes.len());
+        // Read the proof file, decompressing proofs written with --compress
+        let proof_file = tokio::fs::read(proof_path).await?;
+        let proof_data = decompress(&proof_file)?;
         
-        // Create the zkVerify proof submission call using the correct pallet name and call
-        // Based on successful transaction: Settlementsp1pallet.Submit_proof with 4 parameters:
+        let converted_proof = if proof_data.trim_ascii_start().starts_with(b"{") {
+            let (converted_proof, deprecations) = ConvertedProof::from_json(&proof_data)
+                .map_err(|e| FailureClass::BadInput.error(format!("{}: {:#}", proof_path.display(), e)))?;
+            for deprecation in deprecations {
+                warn!("{}: {}; convert the proof again to update it", proof_path.display(), deprecation);
+            }
+            converted_proof
+        } else {
+            ConvertedProof::from_bytes(&proof_data).classify(FailureClass::BadInput)?
+        };
+        let backend = converted_proof.proof_type.backend();
+        backend.verify_locally(&converted_proof)?;
+
+        let (proof_bytes, pub_inputs_bytes, _) = converted_proof.decode()?;
+        status!("✅ Proof decomposed: {} bytes proof, {} bytes public inputs", proof_bytes.len(), pub_inputs_bytes.len());
+
+        // The pallet's `submit_proof` takes 4 parameters:
         // 1. vk_or_hash (VkOrHash)
-        // 2. proof (Vec<U8>)
-        // 3. pubs (Vec<U8>) 
+        // 2. proof
+        // 3. pubs
         // 4. domain_id (Option<u32>)
-        
-        // Get VK from proof.json
-        let vk_hex = proof_json.get("vk")
-            .and_then(|v| v.as_str())
-            .unwrap_or("50f8a2481aff84670a96db9126c7f4533f9f7e912129edfe3d35e4e81aa32472");
-        
-        // Handle double-encoded VK - decode it properly
-        let vk_hex_clean = vk_hex.trim_start_matches("0x");
-        let vk_bytes = if vk_hex_clean.len() > 64 {
-            // If VK is longer than 64 chars, it might be double-encoded
-            let decoded_vk = hex::decode(vk_hex_clean).unwrap();
-            let decoded_vk_str = String::from_utf8(decoded_vk).unwrap();
-            hex::decode(decoded_vk_str.trim_start_matches("0x")).unwrap()
-        } else {
-            hex::decode(vk_hex_clean).unwrap()
+        let CallArgs { vk, proof, pubs } = backend.call_args(&converted_proof)?;
+        let domain_id = match domain_id {
+            Some(domain_id) => subxt::dynamic::Value::unnamed_variant("Some", vec![subxt::dynamic::Value::u128(domain_id as u128)]),
+            None => subxt::dynamic::Value::named_variant::<&str, &str, Vec<(&str, subxt::dynamic::Value)>>("None", vec![]),
         };
+        if self.vk_mode == VkMode::Inline {
+            let vk_or_hash = subxt::dynamic::Value::named_variant("Vk", vec![("Vk", vk)]);
+            return Ok(subxt::dynamic::tx(backend.pallet(), "submit_proof", vec![vk_or_hash, proof, pubs, domain_id]));
+        }
+
+        // By hash, the VK must be registered by the time the submission executes
+        let hash = vk_hash(&backend.encode_args(&converted_proof)?.vk);
+        let vk_or_hash = subxt::dynamic::Value::unnamed_variant("Hash", vec![subxt::dynamic::Value::from_bytes(hash)]);
+        if self.is_vk_registered_in(backend.pallet(), hash).await? {
+            status!("✅ VK {:?} is registered; submitting by hash", hash);
+            return Ok(subxt::dynamic::tx(backend.pallet(), "submit_proof", vec![vk_or_hash, proof, pubs, domain_id]));
+        }
+        if self.vk_mode == VkMode::Hash {
+            return Err(FailureClass::BadInput.error(format!(
+                "VK {:?} is not registered in {}; register it with `vk register` or pass --vk-mode register",
+                hash,
+                backend.pallet()
+            )));
+        }
+        status!("📝 VK {:?} is not registered; registering it in the same transaction", hash);
+        let call = |name: &str, args: Vec<subxt::dynamic::Value>| {
+            subxt::dynamic::Value::unnamed_variant(backend.pallet(), vec![subxt::dynamic::Value::unnamed_variant(name, args)])
+        };
+        Ok(subxt::dynamic::tx("Utility", "batch_all", vec![subxt::dynamic::Value::unnamed_composite(vec![
+            call("register_vk", vec![vk]),
+            call("submit_proof", vec![vk_or_hash, proof, pubs, domain_id]),
+        ])]))
+    }
+    
+    #[instrument(skip(self))]
+    pub async fn submit_proof_to_zkverify(&self, proof_path: &Path, domain_id: Option<u32>) -> Result<String, RouterError> {
+        let call = self.submit_proof_call(proof_path, domain_id).await?;
         
-        let vk_or_hash = subxt::dynamic::Value::named_variant("Vk", vec![
-            ("Vk", subxt::dynamic::Value::unnamed_composite(vec![
-                subxt::dynamic::Value::unnamed_composite(vk_bytes.into_iter().map(|b| subxt::dynamic::Value::u128(b as u128)).collect::<Vec<_>>())
-            ]))
-        ]);
-        
-        println!("🔗 Connecting to zkVerify network...");
-        
-        let call = subxt::dynamic::tx("SettlementSp1Pallet", "submit_proof", vec![
-            vk_or_hash,
-            subxt::dynamic::Value::unnamed_composite(proof_bytes.into_iter().map(|b| subxt::dynamic::Value::u128(b as u128)).collect::<Vec<_>>()),
-            subxt::dynamic::Value::unnamed_composite(pub_inputs_bytes.into_iter().map(|b| subxt::dynamic::Value::u128(b as u128)).collect::<Vec<_>>()),
-            subxt::dynamic::Value::named_variant::<&str, &str, Vec<(&str, subxt::dynamic::Value)>>("None", vec![]), // domain_id as None
-        ]);
-        
-        println!("📤 Submitting transaction to zkVerify...");
-        let result = self
-            .client
-            .tx()
-            .sign_and_submit_default(&call, &self.signer)
-            .await;
+        status!("🔗 Connecting to zkVerify network...");
+        status!("📤 Submitting transaction to zkVerify...");
+        let result = self.submit(&call).await;
             
         match result {
             Ok(tx_hash) => {
-                println!("✅ Transaction submitted successfully!");
-                Ok(format!("{:?}", tx_hash))
+                status!("✅ Transaction submitted successfully!");
+                Ok(tx_hash)
             }
             Err(e) => {
-                println!("❌ Transaction submission failed!");
-                println!("Error: {:?}", e);
+                status!("❌ Transaction submission failed!");
+                status!("Error: {:?}", e);
                 
                 // Check if it's a runtime error
                 if e.to_string().contains("1010") {
-                    println!("Error 1010 detected - this often indicates:");
-                    println!("1. Insufficient funds for transaction fees");
-                    println!("2. Invalid proof format or parameters");
-                    println!("3. Chain-specific validation failure");
+                    status!("Error 1010 detected - this often indicates:");
+                    status!("1. Insufficient funds for transaction fees");
+                    status!("2. Invalid proof format or parameters");
+                    status!("3. Chain-specific validation failure");
                 }
                 
                 Err(e.into())
             }
         }
     }
+
+    /// Build an unsigned proof submission for the account, to be signed elsewhere
+    pub async fn prepare_proof_submission(&self, proof_path: &Path, domain_id: Option<u32>) -> Result<UnsignedTx, RouterError> {
+        let call = self.submit_proof_call(proof_path, domain_id).await?;
+        let call_data = self.client.tx().call_data(&call)?;
+        let nonce = self.next_nonce().await?;
+
+        // Offline signing needs the mortality checkpoint pinned now rather than at submission
+        let mortality = match self.tx_options.era {
+            Some(Era::Immortal) => None,
+            era => {
+                let period = match era {
+                    Some(Era::Mortal(blocks)) => blocks,
+                    _ => DEFAULT_MORTAL_BLOCKS,
+                };
+                let block = self.client.blocks().at_latest().await?;
+                Some(Mortality { period, block_number: block.number() as u64, block_hash: block.hash() })
+            }
+        };
+
+        let unsigned = UnsignedTx {
+            signer: self.address(),
+            genesis_hash: self.client.genesis_hash(),
+            spec_version: self.client.runtime_version().spec_version,
+            nonce,
+            tip: self.tx_options.tip,
+            mortality,
+            call_data: format!("0x{}", hex::encode(&call_data)),
+            extensions: String::new(),
+            signer_payload: String::new(),
+        };
+
+        // Unhashed extension bytes, for signers that decode and display the transaction
+        let params = <PolkadotExtrinsicParams<PolkadotConfig> as ExtrinsicParams<PolkadotConfig>>::new(
+            &self.client.client_state(),
+            Self::unsigned_params(&unsigned),
+        )
+        .map_err(anyhow::Error::from)?;
+        let mut extensions = Vec::new();
+        params.encode_signer_payload_value_to(&mut extensions);
+        params.encode_implicit_to(&mut extensions);
+
+        let signer_payload = self.partial_tx(&unsigned)?.signer_payload();
+        info!("Prepared {} byte signing payload for nonce {}", signer_payload.len(), nonce);
+
+        Ok(UnsignedTx {
+            extensions: format!("0x{}", hex::encode(extensions)),
+            signer_payload: format!("0x{}", hex::encode(signer_payload)),
+            ..unsigned
+        })
+    }
+
+    // Extrinsic params pinned by a prepared transaction
+    fn unsigned_params(unsigned: &UnsignedTx) -> <PolkadotExtrinsicParams<PolkadotConfig> as ExtrinsicParams<PolkadotConfig>>::Params {
+        let builder = PolkadotExtrinsicParamsBuilder::new().tip(unsigned.tip).nonce(unsigned.nonce);
+        match &unsigned.mortality {
+            Some(m) => builder.mortal_from_unchecked(m.period, m.block_number, m.block_hash),
+            None => builder.immortal(),
+        }
+        .build()
+    }
+
+    // Rebuild the partial transaction described by a prepared transaction
+    fn partial_tx(
+        &self,
+        unsigned: &UnsignedTx,
+    ) -> Result<subxt::tx::PartialTransaction<PolkadotConfig, OnlineClient<PolkadotConfig>>> {
+        let call = RawCall(unsigned.call_data_bytes()?);
+        Ok(self.client.tx().create_partial_offline(&call, Self::unsigned_params(unsigned))?)
+    }
+
+    /// Attach a signature produced elsewhere to a prepared transaction and broadcast it
+    pub async fn submit_signed(&self, unsigned: &UnsignedTx, signature: &MultiSignature) -> Result<String, RouterError> {
+        if unsigned.genesis_hash != self.client.genesis_hash() {
+            return Err(RouterError::InvalidInput(anyhow::anyhow!(
+                "Prepared transaction is for a different chain (genesis {:?})",
+                unsigned.genesis_hash
+            )));
+        }
+        if unsigned.spec_version != self.client.runtime_version().spec_version {
+            return Err(RouterError::InvalidInput(anyhow::anyhow!(
+                "Runtime upgraded since the transaction was prepared (spec {} -> {}); prepare it again",
+                unsigned.spec_version,
+                self.client.runtime_version().spec_version
+            )));
+        }
+
+        let mut partial = self.partial_tx(unsigned)?;
+        if format!("0x{}", hex::encode(partial.signer_payload())) != unsigned.signer_payload {
+            return Err(RouterError::InvalidInput(anyhow::anyhow!(
+                "Signing payload does not match the prepared transaction"
+            )));
+        }
+
+        let account_id: AccountId32 = unsigned
+            .signer
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid signer address {}: {:?}", unsigned.signer, e))?;
+        let tx = partial.sign_with_account_and_signature(&account_id, signature);
+        self.throttle().await?;
+        let tx_hash = tx.submit().await?;
+        Ok(format!("{:?}", tx_hash))
+    }
+
+    /// Submit a proof and wait until it is finalized, reporting the statement it produced
+    /// and, when submitted to a domain, the aggregation it was queued into.
+    #[instrument(skip(self))]
+    pub async fn submit_proof_and_wait(&self, proof_path: &Path, domain_id: Option<u32>) -> Result<ProofInclusion, RouterError> {
+        self.submit_proof_and_track(proof_path, domain_id, &|_| {}).await
+    }
+
+    /// [`Self::submit_proof_and_wait`], reporting each step of the submission to `on_event`
+    #[instrument(skip(self, on_event))]
+    pub async fn submit_proof_and_track(
+        &self,
+        proof_path: &Path,
+        domain_id: Option<u32>,
+        on_event: OnTxEvent<'_>,
+    ) -> Result<ProofInclusion, RouterError> {
+        let call = self.submit_proof_call(proof_path, domain_id).await?;
+        
+        status!("📤 Submitting transaction to zkVerify and waiting for finalization...");
+        let included = self.submit_and_wait(&call, on_event).await?;
+        let events = self.included_events(&included).await.classify(FailureClass::Network)?;
+        // The proof is in a block by now, so an event that does not decode is reported rather
+        // than failing the submission
+        let mut decoded = Vec::new();
+        for event in events.iter() {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("Skipping an event of {:?} that failed to decode: {:#}", included.extrinsic_hash, e);
+                    continue;
+                }
+            };
+            decoded.push(self.submission_event(&event).unwrap_or_else(|e| {
+                warn!("Failed to decode {}.{}: {:#}", event.pallet_name(), event.variant_name(), e);
+                Self::undecoded_event(&event)
+            }));
+        }
+        let events = decoded;
+        if let Some(SubmissionEvent::ExtrinsicFailed { error, docs }) =
+            events.iter().find(|event| matches!(event, SubmissionEvent::ExtrinsicFailed { .. }))
+        {
+            return Err(rejected(match docs {
+                Some(docs) => format!("Submission failed with {}: {}", error, docs),
+                None => format!("Submission failed with {}", error),
+            })
+            .into());
+        }
+        for event in &events {
+            match event {
+                SubmissionEvent::MultisigExecuted { error: Some(error), .. } => {
+                    return Err(rejected(format!("Multisig call failed with {}", error)).into());
+                }
+                SubmissionEvent::ProxyExecuted { error: Some(error) } => {
+                    return Err(rejected(format!("Proxied call failed with {}", error)).into());
+                }
+                SubmissionEvent::MultisigApproval { multisig, call_hash } => {
+                    return Err(FailureClass::AwaitingApproval
+                        .error(format!(
+                            "Approved call {:?} of multisig {}; the proof is submitted once enough other signatories approve it",
+                            call_hash, multisig
+                        ))
+                        .into());
+                }
+                _ => {}
+            }
+        }
+        
+        let statement = events
+            .iter()
+            .find_map(|event| match event {
+                SubmissionEvent::ProofVerified { statement, .. } => Some(*statement),
+                _ => None,
+            })
+            .ok_or_else(|| rejected("No ProofVerified event emitted by the submission".to_string()))?;
+        let aggregation_id = events.iter().find_map(|event| match event {
+            SubmissionEvent::NewProof { aggregation_id, .. } => Some(*aggregation_id),
+            _ => None,
+        });
+        
+        status!("✅ Proof verified with statement {:?}", statement);
+        // The proof is finalized by now, so a header that cannot be fetched only leaves the number out
+        let block_number = match self.client.blocks().at(included.block_hash).await {
+            Ok(block) => Some(block.number()),
+            Err(e) => {
+                warn!("Failed to fetch the number of block {:?}: {:#}", included.block_hash, e);
+                None
+            }
+        };
+        
+        Ok(ProofInclusion {
+            tx_hash: format!("{:?}", included.extrinsic_hash),
+            block_hash: included.block_hash,
+            block_number,
+            statement,
+            domain_id,
+            aggregation_id,
+            events,
+        })
+    }
+    
+    /// Wait for the aggregation containing `statement` to be published and fetch its Merkle path.
+    /// Without an `aggregation_id`, every aggregation the domain publishes is checked for the statement.
+    /// An `aggregation_id` that the domain already published is looked up in the last 1024
+    /// finalized blocks instead of waiting for it.
+    #[instrument(skip(self))]
+    pub async fn wait_for_aggregation(
+        &self,
+        statement: H256,
+        domain_id: u32,
+        aggregation_id: Option<u64>,
+    ) -> Result<AggregationReceipt, RouterError> {
+        match aggregation_id {
+            Some(aggregation_id) => status!("⏳ Waiting for aggregation {} in domain {}...", aggregation_id, domain_id),
+            None => status!("⏳ Waiting for an aggregation of domain {} with statement {:?}...", domain_id, statement),
+        }
+        // Subscribed before the domain is checked, so a receipt published in between is not missed
+        let mut blocks = self.client.blocks().subscribe_finalized().await?;
+
+        if let Some(aggregation_id) = aggregation_id {
+            let domain = self.domain_info(domain_id).await?;
+            if aggregation_id < domain.next_aggregation_id && !domain.ready_aggregations.contains(&aggregation_id) {
+                return self.find_published_aggregation(statement, domain_id, aggregation_id).await;
+            }
+        }
+
+        while let Some(block) = blocks.next().await {
+            let block = block?;
+            if let Some(receipt) = self.aggregation_in_block(&block, statement, domain_id, aggregation_id).await? {
+                return Ok(receipt);
+            }
+        }
+        
+        Err(anyhow::anyhow!("Finalized block subscription ended before the aggregation was published").into())
+    }
+
+    // The receipt of `aggregation_id`, which the domain already published, from the block that
+    // carried its `NewAggregationReceipt`
+    async fn find_published_aggregation(
+        &self,
+        statement: H256,
+        domain_id: u32,
+        aggregation_id: u64,
+    ) -> Result<AggregationReceipt, RouterError> {
+        debug!("Aggregation {} of domain {} is already published, looking for its receipt", aggregation_id, domain_id);
+        let head = self.finalized_block_number().await?;
+        let blocks = self
+            .blocks_back(head, PUBLISHED_SEARCH_BLOCKS)
+            .map(|block_hash| async move {
+                let block_hash = block_hash?;
+                self.query("Fetching a block", || async { Ok(self.client.blocks().at(block_hash).await?) }).await
+            })
+            .buffered(SEARCH_CONCURRENCY);
+        let mut blocks = std::pin::pin!(blocks);
+        while let Some(block) = blocks.next().await {
+            if let Some(receipt) = self.aggregation_in_block(&block?, statement, domain_id, Some(aggregation_id)).await? {
+                return Ok(receipt);
+            }
+        }
+        Err(FailureClass::BadInput
+            .error(format!(
+                "Aggregation {} of domain {} was published more than {} blocks ago",
+                aggregation_id, domain_id, PUBLISHED_SEARCH_BLOCKS
+            ))
+            .into())
+    }
+
+    // The receipt of the aggregation of `domain_id` with `statement` published in `block`, if any
+    async fn aggregation_in_block(
+        &self,
+        block: &Block<PolkadotConfig, OnlineClient<PolkadotConfig>>,
+        statement: H256,
+        domain_id: u32,
+        aggregation_id: Option<u64>,
+    ) -> Result<Option<AggregationReceipt>> {
+        let events = block.events().await?;
+        for receipt in events.find::<NewAggregationReceipt>() {
+            let receipt = receipt?;
+            if receipt.domain_id != domain_id || aggregation_id.is_some_and(|id| receipt.aggregation_id != id) {
+                continue;
+            }
+            
+            let path = self
+                .query("Fetching the statement path", || async {
+                    Ok(self
+                        .rpc
+                        .request::<MerkleProof>(
+                            "aggregate_statementPath",
+                            rpc_params![block.hash(), domain_id, receipt.aggregation_id, statement],
+                        )
+                        .await?)
+                })
+                .await;
+            let merkle_path = match (path, aggregation_id) {
+                (Ok(merkle_path), _) => merkle_path,
+                (Err(e), Some(_)) => return Err(e),
+                // Some other aggregation of the domain, without the statement
+                (Err(e), None) => {
+                    debug!("Statement not in aggregation {}: {:#}", receipt.aggregation_id, e);
+                    continue;
+                }
+            };
+            status!(
+                "📬 Aggregation {} receipt {:?} published in block #{}",
+                receipt.aggregation_id,
+                receipt.receipt,
+                block.number()
+            );
+            
+            return Ok(Some(AggregationReceipt {
+                domain_id,
+                aggregation_id: receipt.aggregation_id,
+                statement,
+                receipt: receipt.receipt,
+                receipt_block_hash: block.hash(),
+                merkle_path,
+            }));
+        }
+        Ok(None)
+    }
+
+    /// State of aggregation domain `domain_id`
+    pub async fn domain_info(&self, domain_id: u32) -> Result<DomainInfo, RouterError> {
+        let query = subxt::dynamic::storage("Aggregate", "Domains", vec![subxt::dynamic::Value::u128(domain_id as u128)]);
+        let domain = self
+            .query("Fetching the domain", || async {
+                Ok(self.client.storage().at_latest().await?.fetch(&query).await?)
+            })
+            .await?
+            .ok_or_else(|| FailureClass::BadInput.error(format!("Domain {} does not exist", domain_id)))?
+            .to_value()?;
+
+        let layout = || anyhow::anyhow!("Unexpected Aggregate.Domains layout");
+        let number = |value: Option<&Value<u32>>| value.and_then(|value| value.as_u128()).ok_or_else(layout);
+        let state = match domain.at("state").map(|state| &state.value) {
+            Some(ValueDef::Variant(variant)) => variant.name.clone(),
+            _ => return Err(layout().into()),
+        };
+        let next = domain.at("next");
+        // A bounded map is encoded as a sequence of (key, value) pairs
+        let ready_aggregations = match domain.at("should_publish").map(|queue| &queue.value) {
+            Some(ValueDef::Composite(queue)) => queue
+                .values()
+                .map(|entry| number(entry.at(0)).map(|id| id as u64))
+                .collect::<Result<Vec<_>>>()?,
+            _ => return Err(layout().into()),
+        };
+        Ok(DomainInfo {
+            domain_id,
+            state,
+            next_aggregation_id: number(next.and_then(|next| next.at("id")))? as u64,
+            queue_size: number(next.and_then(|next| next.at("size")))? as u32,
+            max_aggregation_size: number(domain.at("max_aggregation_size"))? as u32,
+            ready_aggregations,
+        })
+    }
+
+    /// Publish a complete aggregation of `domain_id`, by default the oldest one waiting; returns
+    /// its ID, the aggregation root and the block it was published in
+    #[instrument(skip(self))]
+    pub async fn aggregate(&self, domain_id: u32, aggregation_id: Option<u64>) -> Result<(u64, H256, H256), RouterError> {
+        let aggregation_id = match aggregation_id {
+            Some(aggregation_id) => aggregation_id,
+            None => {
+                let domain = self.domain_info(domain_id).await?;
+                domain.ready_aggregations.first().copied().ok_or_else(|| {
+                    FailureClass::BadInput.error(format!(
+                        "Domain {} has no complete aggregation to publish; aggregation {} holds {} of {} statements",
+                        domain_id, domain.next_aggregation_id, domain.queue_size, domain.max_aggregation_size
+                    ))
+                })?
+            }
+        };
+        let call = subxt::dynamic::tx("Aggregate", "aggregate", vec![
+            subxt::dynamic::Value::u128(domain_id as u128),
+            subxt::dynamic::Value::u128(aggregation_id as u128),
+        ]);
+
+        status!("📤 Publishing aggregation {} of domain {}...", aggregation_id, domain_id);
+        let included = self.submit_and_wait(&call, &|_| {}).await?;
+        let events = self.included_success(&included).await?;
+        let receipt = events
+            .find_first::<NewAggregationReceipt>()?
+            .ok_or_else(|| rejected("No NewAggregationReceipt event emitted by the aggregation".to_string()))?;
+        Ok((receipt.aggregation_id, receipt.receipt, included.block_hash))
+    }
 }
@@ -0,0 +1,34 @@
+use anyhow::Result;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{trace::SdkTracerProvider, Resource};
+use tracing_subscriber::{filter::LevelFilter, Layer, Registry};
+
+/// Flushes buffered spans to the collector when dropped
+pub struct TelemetryGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to flush OpenTelemetry spans: {}", e);
+        }
+    }
+}
+
+/// Layer exporting pipeline spans over OTLP/HTTP to `endpoint` (e.g. `http://localhost:4318/v1/traces`)
+pub fn layer(endpoint: &str) -> Result<(Box<dyn Layer<Registry> + Send + Sync>, TelemetryGuard)> {
+    let exporter = SpanExporter::builder().with_http().with_endpoint(endpoint).build()?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(env!("CARGO_PKG_NAME")).build())
+        .build();
+    let tracer = provider.tracer(env!("CARGO_PKG_NAME"));
+
+    let otel_layer = tracing_opentelemetry::layer()
+        .with_tracer(tracer)
+        .with_filter(LevelFilter::INFO)
+        .boxed();
+    Ok((otel_layer, TelemetryGuard { provider }))
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use std::path::Path;
+use subxt::ext::codec::Encode;
+use subxt::ext::futures::future::BoxFuture;
+
+use crate::backend::{bytes_value, check_size, encode_scalars, scalars_value, CallArgs, ProofBackend, ProofInputs, ScaleArgs};
+use crate::converter::{ConvertedProof, ProofType, read_raw_or_hex, to_hex_with_prefix};
+use crate::failure::FailureClass;
+
+/// Size of an UltraPlonk verification key as written by `bb write_vk`
+pub const VK_SIZE: usize = 1719;
+/// Size of an UltraPlonk proof, without its public inputs
+pub const PROOF_SIZE: usize = 2144;
+/// Size of one public input (a field element)
+pub const PUB_SIZE: usize = 32;
+
+/// Number of public inputs the circuit of `vk` takes, a big-endian u32 after
+/// the circuit type and size
+pub fn num_public_inputs(vk: &[u8]) -> Result<usize> {
+    if vk.len() != VK_SIZE {
+        return Err(FailureClass::BadInput.error(format!(
+            "UltraPlonk VK is {} bytes, expected {}",
+            vk.len(),
+            VK_SIZE
+        )));
+    }
+    Ok(u32::from_be_bytes(vk[8..12].try_into()?) as usize)
+}
+
+/// Split the `proof` and `vk` files of `bb prove` / `bb write_vk` into the proof,
+/// public inputs and VK zkVerify's UltraPlonk pallet takes. bb puts the public
+/// inputs in front of the proof; the VK says how many there are.
+pub async fn convert(proof_path: &Path, vk_path: &Path) -> Result<ConvertedProof> {
+    let vk = read_raw_or_hex(vk_path).await?;
+    let num_pubs = num_public_inputs(&vk)?;
+    let proof_with_pubs = read_raw_or_hex(proof_path).await?;
+
+    let pubs_len = num_pubs * PUB_SIZE;
+    if proof_with_pubs.len() != pubs_len + PROOF_SIZE {
+        return Err(FailureClass::BadInput.error(format!(
+            "{} is {} bytes, but the VK expects {} public inputs and a {}-byte proof ({} bytes): was it proven for this circuit?",
+            proof_path.display(),
+            proof_with_pubs.len(),
+            num_pubs,
+            PROOF_SIZE,
+            pubs_len + PROOF_SIZE
+        )));
+    }
+    let (pubs, proof) = proof_with_pubs.split_at(pubs_len);
+    status!("✅ Proof split: {} public inputs, {} bytes proof", num_pubs, proof.len());
+
+    Ok(ConvertedProof::new(ProofType::UltraPlonk, proof, pubs, &vk))
+}
+
+/// Noir UltraPlonk proofs for zkVerify's UltraPlonk pallet
+pub struct UltraPlonkBackend;
+
+impl ProofBackend for UltraPlonkBackend {
+    fn proof_type(&self) -> ProofType {
+        ProofType::UltraPlonk
+    }
+
+    fn pallet(&self) -> &'static str {
+        "SettlementUltraplonkPallet"
+    }
+
+    fn convert<'a>(&'a self, inputs: &'a ProofInputs) -> BoxFuture<'a, Result<ConvertedProof>> {
+        Box::pin(async move { convert(&inputs.proof, inputs.vk_file(ProofType::UltraPlonk)?).await })
+    }
+
+    fn derive_vk<'a>(&'a self, inputs: &'a ProofInputs) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let vk = read_raw_or_hex(inputs.vk_file(ProofType::UltraPlonk)?).await?;
+            num_public_inputs(&vk)?;
+            Ok(to_hex_with_prefix(&vk))
+        })
+    }
+
+    fn verify_locally(&self, converted_proof: &ConvertedProof) -> Result<()> {
+        let (proof, pubs, vk) = converted_proof.decode()?;
+        check_size(ProofType::UltraPlonk, "proof", &proof, PROOF_SIZE)?;
+        check_size(ProofType::UltraPlonk, "pubs", &pubs, num_public_inputs(&vk)? * PUB_SIZE)
+    }
+
+    // `[u8; VK_SIZE]` and `Vec<[u8; 32]>`
+    fn call_args(&self, converted_proof: &ConvertedProof) -> Result<CallArgs> {
+        let (proof, pubs, vk) = converted_proof.decode()?;
+        Ok(CallArgs {
+            vk: bytes_value(&vk),
+            proof: bytes_value(&proof),
+            pubs: scalars_value(&pubs, PUB_SIZE, ProofType::UltraPlonk)?,
+        })
+    }
+
+    fn encode_args(&self, converted_proof: &ConvertedProof) -> Result<ScaleArgs> {
+        let (proof, pubs, vk) = converted_proof.decode()?;
+        Ok(ScaleArgs { vk, proof: proof.encode(), pubs: encode_scalars(&pubs, PUB_SIZE, ProofType::UltraPlonk)? })
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use qrcode::{render::unicode::Dense1x2, QrCode};
+use raptorq::Encoder;
+use std::path::Path;
+use std::time::Duration;
+use subxt::{ext::codec::Compact, ext::codec::Encode, utils::AccountId32};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::signing::{SignatureScheme, UnsignedTx};
+
+/// Payloads up to this size fit in one static QR code
+const STATIC_QR_LIMIT: usize = 2048;
+/// Fountain packet size Vault's own encoder uses for animated QR codes
+const FOUNTAIN_PACKET_SIZE: u16 = 1072;
+/// Delay between frames of an animated QR code
+const FRAME_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Universal Offline Signatures (UOS) "sign transaction" payload for a prepared transaction:
+/// `0x53 || scheme || 0x02 || public key || compact(call length) || call || extensions || genesis hash`
+pub fn uos_payload(unsigned: &UnsignedTx, scheme: SignatureScheme) -> Result<Vec<u8>> {
+    if unsigned.extensions.is_empty() {
+        anyhow::bail!("Prepared transaction has no extension bytes; prepare it again");
+    }
+    let account_id: AccountId32 = unsigned
+        .signer
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid signer address {}: {:?}", unsigned.signer, e))?;
+    let call_data = unsigned.call_data_bytes()?;
+    let extensions = hex::decode(unsigned.extensions.trim_start_matches("0x"))?;
+
+    let scheme_byte = match scheme {
+        SignatureScheme::Ed25519 => 0x00,
+        SignatureScheme::Sr25519 => 0x01,
+        SignatureScheme::Ecdsa => 0x02,
+    };
+    let mut payload = vec![0x53, scheme_byte, 0x02];
+    payload.extend_from_slice(account_id.as_ref());
+    Compact(call_data.len() as u32).encode_to(&mut payload);
+    payload.extend_from_slice(&call_data);
+    payload.extend_from_slice(&extensions);
+    payload.extend_from_slice(unsigned.genesis_hash.as_bytes());
+    Ok(payload)
+}
+
+/// Split a payload into QR frames: a single static frame when it fits, otherwise RaptorQ
+/// fountain frames (`0x80000000 | payload length` as big-endian u32, then the packet).
+pub fn qr_frames(payload: &[u8]) -> Vec<Vec<u8>> {
+    if payload.len() <= STATIC_QR_LIMIT {
+        return vec![payload.to_vec()];
+    }
+
+    let header = (payload.len() as u32 | 0x8000_0000).to_be_bytes();
+    let encoder = Encoder::with_defaults(payload, FOUNTAIN_PACKET_SIZE);
+    // A few repair packets per block, so a missed frame does not cost a whole cycle
+    encoder
+        .get_encoded_packets(4)
+        .into_iter()
+        .map(|packet| {
+            let mut frame = header.to_vec();
+            frame.extend_from_slice(&packet.serialize());
+            frame
+        })
+        .collect()
+}
+
+fn render_frame(frame: &[u8]) -> Result<String> {
+    let code = QrCode::new(frame)?;
+    Ok(code.render::<Dense1x2>().quiet_zone(true).build())
+}
+
+/// Show the frames in the terminal, cycling animated codes until Enter is pressed
+pub async fn show_qr(frames: &[Vec<u8>]) -> Result<()> {
+    let rendered = frames.iter().map(|frame| render_frame(frame)).collect::<Result<Vec<_>>>()?;
+    if let [single] = rendered.as_slice() {
+        println!("{}", single);
+        return Ok(());
+    }
+
+    let mut stdin = BufReader::new(tokio::io::stdin()).lines();
+    for (index, frame) in rendered.iter().enumerate().cycle() {
+        // Clear the screen and redraw from the top-left corner
+        print!("\x1b[2J\x1b[H");
+        println!("{}", frame);
+        println!("📷 Frame {}/{} - press Enter once Vault has read the code", index + 1, rendered.len());
+
+        tokio::select! {
+            _ = tokio::time::sleep(FRAME_INTERVAL) => {}
+            _ = stdin.next_line() => return Ok(()),
+        }
+    }
+    Ok(())
+}
+
+/// Read the signature QR code Vault displays from a photo or screenshot of it
+pub fn read_signature_qr(image_path: &Path) -> Result<String> {
+    let image = image::open(image_path)?.to_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare(image);
+    let grids = prepared.detect_grids();
+    let grid = grids
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("No QR code found in {}", image_path.display()))?;
+    let (_, content) = grid.decode()?;
+    Ok(content.trim().to_string())
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tracing::warn;
+
+/// Machine-readable warning codes.
+///
+/// Codes are part of the output contract: wrappers match on them, so an
+/// existing code must never be renumbered or reused for something else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WarningCode {
+    /// The VK scraped from the explorer differs from the one in the artifact
+    #[serde(rename = "W001")]
+    VkMismatch,
+    /// The converted proof is unusually large
+    #[serde(rename = "W002")]
+    LargeProof,
+    /// The signer's free balance is below the configured threshold
+    #[serde(rename = "W003")]
+    NearBalanceThreshold,
+    /// A file the signing mnemonic is read from can be read by other users
+    #[serde(rename = "W004")]
+    ExposedKeyFile,
+}
+
+impl WarningCode {
+    pub fn code(&self) -> &'static str {
+        match self {
+            WarningCode::VkMismatch => "W001",
+            WarningCode::LargeProof => "W002",
+            WarningCode::NearBalanceThreshold => "W003",
+            WarningCode::ExposedKeyFile => "W004",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarningCode::VkMismatch => "vk-mismatch",
+            WarningCode::LargeProof => "large-proof",
+            WarningCode::NearBalanceThreshold => "near-balance-threshold",
+            WarningCode::ExposedKeyFile => "exposed-key-file",
+        }
+    }
+}
+
+impl fmt::Display for WarningCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.code(), self.name())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Warning {
+    pub code: WarningCode,
+    pub name: String,
+    pub message: String,
+}
+
+impl Warning {
+    /// Create a warning and report it in the human-readable output. Like a status line, it
+    /// is logged under the `status` target, which the console log leaves out so that it shows once.
+    pub fn emit(code: WarningCode, message: impl Into<String>) -> Self {
+        let message = message.into();
+        let line = format!("⚠️  [{}] {}", code, message);
+        warn!(target: "status", code = code.code(), "{}", line);
+        if !crate::output::is_quiet() {
+            println!("{}", line);
+        }
+        Self {
+            code,
+            name: code.name().to_string(),
+            message,
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use subxt::ext::futures::{stream, StreamExt};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use crate::client::RequestFilter;
+use crate::queue::{Job, JobQueue, JobResult, QueueOptions};
+use crate::router::Router;
+use crate::scheduler::Scheduler;
+use crate::webhook::{JobNotification, WebhookClient};
+
+pub struct WatchOptions {
+    pub filter: RequestFilter,
+    pub poll_interval: Duration,
+    /// Converted proofs are written here as `<request_id>.json`
+    pub output_dir: PathBuf,
+    /// Also route requests already listed when the watch starts
+    pub include_existing: bool,
+    /// Notified whenever a job finishes or fails
+    pub webhook: Option<WebhookClient>,
+    /// Periodic tasks run between polls
+    pub scheduler: Scheduler,
+    /// Explorer pages of new requests fetched at the same time
+    pub fetch_concurrency: usize,
+    /// Workers and retry policies for the routing jobs
+    pub queue: QueueOptions,
+}
+
+/// Poll the explorer for new requests matching the filter and route each fulfilled one,
+/// until interrupted with Ctrl-C
+pub async fn watch(router: Arc<Router>, mut options: WatchOptions) -> Result<()> {
+    tokio::fs::create_dir_all(&options.output_dir).await?;
+    // Settle whatever a previous run left in flight before anything is routed again
+    router.reconcile_history().await?;
+    let mut queue = JobQueue::start(router.clone(), options.queue);
+    // Keep submitting correctly across zkVerify runtime upgrades without a restart
+    let upgrades = router.substrate_client().is_some().then(|| {
+        let router = router.clone();
+        tokio::spawn(async move {
+            if let Some(substrate_client) = router.substrate_client()
+                && let Err(e) = substrate_client.follow_runtime_upgrades().await
+            {
+                warn!("No longer following runtime upgrades: {:#}", e);
+            }
+        })
+    });
+
+    let mut seen: HashSet<String> = HashSet::new();
+    if !options.include_existing {
+        seen.extend(router.client().list_requests(&options.filter).await?.into_iter().map(|request| request.request_id));
+        status!("👀 Ignoring {} requests already listed", seen.len());
+    }
+
+    status!("👀 Watching {} every {:?} (Ctrl-C to stop)", options.filter, options.poll_interval);
+    let mut jobs = 0;
+    loop {
+        match router.client().list_requests(&options.filter).await {
+            Ok(requests) => {
+                // The explorer lists newest first; route in the order requests were made
+                let mut new_requests = Vec::new();
+                for request_id in requests.into_iter().rev().map(|request| request.request_id) {
+                    if seen.contains(&request_id) {
+                        continue;
+                    }
+                    if router.already_routed(&request_id) {
+                        debug!("Request {} already routed, skipping", request_id);
+                        seen.insert(request_id);
+                        continue;
+                    }
+                    new_requests.push(request_id);
+                }
+
+                let router_ref = &router;
+                let mut fetched = stream::iter(new_requests)
+                    .map(|request_id| async move {
+                        let metadata = router_ref.fetch_metadata(&request_id).await;
+                        (request_id, metadata)
+                    })
+                    .buffered(options.fetch_concurrency.max(1));
+                while let Some((request_id, metadata)) = fetched.next().await {
+                    // Unfulfilled requests have no artifact yet, so retry them on the next poll
+                    let metadata = match metadata {
+                        Ok(metadata) => metadata,
+                        Err(e) => {
+                            debug!("Request {} not ready: {}", request_id, e);
+                            continue;
+                        }
+                    };
+                    seen.insert(request_id.clone());
+                    jobs += 1;
+
+                    status!("🧾 [job {}] {}: fulfilled, queued", jobs, request_id);
+                    let output = options.output_dir.join(router.output_file_name(&request_id));
+                    queue.push(Job { number: jobs, request_id, metadata, output, domain_id: None });
+                }
+            }
+            Err(e) => warn!("Failed to list requests: {:#}", e),
+        }
+
+        // Run scheduled tasks as they come due until the next poll
+        let next_poll = Instant::now() + options.poll_interval;
+        loop {
+            let wake = options.scheduler.next_due().map_or(next_poll, |due| due.min(next_poll));
+            tokio::select! {
+                _ = tokio::time::sleep_until(wake) => {}
+                result = queue.next_result() => {
+                    report(&router, options.webhook.as_ref(), result).await;
+                    continue;
+                }
+                _ = tokio::signal::ctrl_c() => {
+                    if queue.pending() > 0 {
+                        status!("👋 Stopping watch after {} jobs, abandoning {} in progress", jobs, queue.pending());
+                    } else {
+                        status!("👋 Stopping watch after {} jobs", jobs);
+                    }
+                    if let Some(upgrades) = &upgrades {
+                        upgrades.abort();
+                    }
+                    router.client().close_browser().await;
+                    return Ok(());
+                }
+            }
+            options.scheduler.run_due(&router).await;
+            if Instant::now() >= next_poll {
+                break;
+            }
+        }
+    }
+}
+
+// Print a finished job's outcome, record failures in the history and notify the webhook
+async fn report(router: &Router, webhook: Option<&WebhookClient>, result: JobResult) {
+    let JobResult { number, request_id, outcome } = result;
+    let notification = match outcome {
+        Ok(None) => {
+            status!("✅ [job {}] {}: converted", number, request_id);
+            JobNotification {
+                request_id,
+                status: "succeeded",
+                tx_hash: None,
+                block_hash: None,
+                aggregation_id: None,
+                error: None,
+            }
+        }
+        Ok(Some(submission)) => {
+            status!("🎉 [job {}] {}: submitted in {}", number, request_id, submission.tx_hash);
+            JobNotification {
+                request_id,
+                status: "succeeded",
+                tx_hash: Some(submission.tx_hash),
+                block_hash: submission.block_hash,
+                aggregation_id: submission.aggregation_id,
+                error: None,
+            }
+        }
+        Err(failure) => {
+            let error = format!("{:#}", failure.error);
+            status!(
+                "❌ [job {}] {}: {:?} failed after {} attempts: {}",
+                number, request_id, failure.stage, failure.attempts, error
+            );
+            if let Some(history) = router.history()
+                && let Err(e) = history.record_failure(&request_id, &error)
+            {
+                warn!("Failed to update history: {:#}", e);
+            }
+            JobNotification {
+                request_id,
+                status: "failed",
+                tx_hash: None,
+                block_hash: None,
+                aggregation_id: None,
+                error: Some(error),
+            }
+        }
+    };
+
+    if let Some(webhook) = webhook
+        && let Err(e) = webhook.notify(&notification).await
+    {
+        warn!("[job {}] {}", number, e);
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::Result;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::Serialize;
+use sha2::Sha256;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Header carrying the hex HMAC-SHA256 of the request body, when a secret is configured
+pub const SIGNATURE_HEADER: &str = "X-Rusty-Router-Signature";
+
+/// Body POSTed when a routing job finishes
+#[derive(Debug, Clone, Serialize)]
+pub struct JobNotification {
+    pub request_id: String,
+    /// `succeeded` or `failed`
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<String>,
+    /// Aggregation the proof was queued into, when submitted to a domain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregation_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub struct WebhookClient {
+    client: Client,
+    url: String,
+    secret: Option<String>,
+    max_retries: u32,
+}
+
+impl WebhookClient {
+    pub fn new(url: &str, secret: Option<String>, max_retries: u32) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.to_string(),
+            secret,
+            max_retries,
+        }
+    }
+
+    fn signature(&self, body: &[u8]) -> Option<String> {
+        let secret = self.secret.as_ref()?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+        mac.update(body);
+        Some(format!("sha256={}", hex::encode(mac.finalize().into_bytes())))
+    }
+
+    /// POST the notification, retrying with exponential backoff on errors and non-2xx replies
+    pub async fn notify(&self, notification: &JobNotification) -> Result<()> {
+        let body = serde_json::to_vec(notification)?;
+        let signature = self.signature(&body);
+
+        let mut delay = Duration::from_secs(1);
+        let mut attempt = 0;
+        loop {
+            let mut request = self
+                .client
+                .post(&self.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.clone());
+            if let Some(signature) = &signature {
+                request = request.header(SIGNATURE_HEADER, signature);
+            }
+
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    debug!("Webhook delivered for {}", notification.request_id);
+                    return Ok(());
+                }
+                Ok(response) => format!("webhook answered {}", response.status()),
+                Err(e) => e.to_string(),
+            };
+
+            attempt += 1;
+            if attempt > self.max_retries {
+                anyhow::bail!("Webhook delivery failed after {} attempts: {}", attempt, error);
+            }
+            warn!("Webhook attempt {} failed ({}), retrying in {:?}", attempt, error, delay);
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! End-to-end run of the route pipeline against a local zkVerify dev node.
+//!
+//! Run with `cargo test --features dev-node --test dev_node`. The node is started in Docker
+//! (`ZKV_DEV_NODE_IMAGE`, default `horizenlabs/zkverify:latest`) unless `ZKV_DEV_NODE_WS`
+//! points at one that is already running.
+#![cfg(feature = "dev-node")]
+
+use anyhow::Result;
+use rusty_router::client::ProofClient;
+use rusty_router::converter::ProofConverter;
+use rusty_router::router::{Router, Submitter};
+use rusty_router::substrate::SubstrateClient;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+use subxt::{dynamic::Value, utils::H256, OnlineClient, PolkadotConfig};
+use subxt_signer::sr25519::dev;
+
+const DEFAULT_IMAGE: &str = "horizenlabs/zkverify:latest";
+// Root account of the dev phrase; it is not endowed at genesis, so the harness funds it
+const ROUTER_MNEMONIC: &str = subxt_signer::DEV_PHRASE;
+const FUNDING: u128 = 1_000_000_000_000_000_000_000;
+
+/// A dev node, stopped on drop if the harness started it
+struct DevNode {
+    container: Option<String>,
+    ws_url: String,
+}
+
+impl DevNode {
+    fn start() -> Result<Self> {
+        if let Ok(ws_url) = std::env::var("ZKV_DEV_NODE_WS") {
+            return Ok(Self { container: None, ws_url });
+        }
+
+        let image = std::env::var("ZKV_DEV_NODE_IMAGE").unwrap_or_else(|_| DEFAULT_IMAGE.to_string());
+        let container = docker(&[
+            "run", "-d", "--rm", "-p", "127.0.0.1::9944", &image, "--dev", "--rpc-external", "--rpc-cors", "all",
+        ])?;
+        // Stops the container if looking up its port fails
+        let mut node = Self { container: Some(container.clone()), ws_url: String::new() };
+        // `docker port` answers with e.g. `127.0.0.1:49153`
+        let address = docker(&["port", &container, "9944/tcp"])?;
+        node.ws_url = format!("ws://{}", address.lines().next().unwrap_or_default());
+        Ok(node)
+    }
+
+    /// Wait until the node accepts connections and produces blocks
+    async fn client(&self) -> Result<OnlineClient<PolkadotConfig>> {
+        let mut last_error = None;
+        for _ in 0..60 {
+            match OnlineClient::<PolkadotConfig>::from_url(&self.ws_url).await {
+                Ok(client) => return Ok(client),
+                Err(e) => last_error = Some(e),
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        anyhow::bail!("Dev node at {} did not come up: {:?}", self.ws_url, last_error)
+    }
+}
+
+impl Drop for DevNode {
+    fn drop(&mut self) {
+        if let Some(container) = &self.container {
+            let _ = docker(&["stop", container]);
+        }
+    }
+}
+
+fn docker(args: &[&str]) -> Result<String> {
+    let output = Command::new("docker").args(args).output()?;
+    if !output.status.success() {
+        anyhow::bail!("docker {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Transfer FUNDING from Alice and wait until it is finalized
+async fn fund(client: &OnlineClient<PolkadotConfig>, account: &subxt::utils::AccountId32) -> Result<()> {
+    let transfer = subxt::dynamic::tx(
+        "Balances",
+        "transfer_keep_alive",
+        vec![Value::unnamed_variant("Id", [Value::from_bytes(account)]), Value::u128(FUNDING)],
+    );
+    client
+        .tx()
+        .sign_and_submit_then_watch_default(&transfer, &dev::alice())
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+    Ok(())
+}
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/dev_node").join(name)
+}
+
+#[tokio::test]
+async fn routes_fixture_proof_to_dev_node() -> Result<()> {
+    let artifact = fixture("artifact.bin");
+    // The artifact is not checked in, so the test is skipped until one is saved
+    if !artifact.exists() {
+        eprintln!(
+            "Skipping: missing {}; save an SP1 compressed proof artifact there (see tests/fixtures/dev_node/README.md)",
+            artifact.display()
+        );
+        return Ok(());
+    }
+
+    let node = DevNode::start()?;
+    let client = node.client().await?;
+
+    let substrate_client = SubstrateClient::new(&node.ws_url, ROUTER_MNEMONIC).await?;
+    fund(&client, substrate_client.account_id()).await?;
+    assert!(substrate_client.free_balance().await? >= FUNDING);
+
+    let output = tempfile::Builder::new().suffix(".json").tempfile()?;
+    let converter = ProofConverter::new();
+    let converted_proof = converter.convert_proof(&artifact, "").await?;
+    converter.save_proof(&converted_proof, output.path()).await?;
+
+    let router = Router::new(ProofClient::new()).with_submitter(Submitter::Direct(Box::new(substrate_client)));
+    let submission = router.submit(output.path()).await?;
+
+    let block_hash: H256 = submission.block_hash.as_deref().expect("finalized submission").parse()?;
+    let events = client.blocks().at(block_hash).await?.events().await?;
+    let verified = events
+        .iter()
+        .filter_map(|event| event.ok())
+        .any(|event| event.pallet_name() == "SettlementSp1Pallet" && event.variant_name() == "ProofVerified");
+    assert!(verified, "no ProofVerified event in block {:?}", block_hash);
+    Ok(())
+}
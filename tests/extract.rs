@@ -0,0 +1,127 @@
+//! Metadata extraction from saved explorer pages in `tests/fixtures/explorer`.
+
+use rusty_router::extract::{
+    extract_from_dom, extract_from_json, extract_from_next_data, extract_from_script_tags, extract_request_list,
+    ExtractionStrategy, RequestDetails,
+};
+
+const RENDERED: &str = include_str!("fixtures/explorer/rendered.html");
+const UNRENDERED: &str = include_str!("fixtures/explorer/unrendered.html");
+const NEXT_DATA: &str = include_str!("fixtures/explorer/next_data.html");
+
+const REQUEST_ID: &str = "0x2388a8a8af25fdaec2ae488ca57f315903915de7750a07c88874f8e52b1424b9";
+const PROGRAM_VK: &str = "0x681047444efcb811048971ef3caae49c663d751a5c9d71094e8ac5501f5a5887";
+const ARTIFACT_PREFIX: &str = "https://spn-artifacts-mainnet.s3.us-east-2.amazonaws.com/proofs/artifact_e424f2cf1cf63ae0303ee85e1059b5ad38cd79b44c4054b81521a2944143d53f?";
+
+#[test]
+fn dom_finds_the_artifact_url_and_program_vk() {
+    let extracted = extract_from_dom(RENDERED);
+    let artifact_url = extracted.artifact_url.expect("artifact URL");
+    assert!(artifact_url.starts_with(ARTIFACT_PREFIX), "{}", artifact_url);
+    assert_eq!(extracted.vk.as_deref(), Some(PROGRAM_VK));
+}
+
+#[test]
+fn dom_decodes_entities_in_the_artifact_url() {
+    let artifact_url = extract_from_dom(RENDERED).artifact_url.expect("artifact URL");
+    assert!(artifact_url.contains("?x-id=GetObject&X-Amz-Algorithm="), "{}", artifact_url);
+    assert!(!artifact_url.contains("&amp;"), "{}", artifact_url);
+}
+
+#[test]
+fn dom_does_not_take_the_request_id_for_the_vk() {
+    // The request ID is the first 32-byte hex value on the page
+    assert_ne!(extract_from_dom(RENDERED).vk.as_deref(), Some(REQUEST_ID));
+}
+
+#[test]
+fn unrendered_page_has_nothing() {
+    assert!(extract_from_dom(UNRENDERED).is_empty());
+    assert!(extract_from_next_data(UNRENDERED).is_empty());
+    assert!(extract_from_script_tags(UNRENDERED).is_empty());
+}
+
+#[test]
+fn next_data_finds_the_artifact_url_and_program_vk() {
+    let extracted = extract_from_next_data(NEXT_DATA);
+    let artifact_url = extracted.artifact_url.expect("artifact URL");
+    assert!(artifact_url.starts_with(ARTIFACT_PREFIX), "{}", artifact_url);
+    assert_eq!(extracted.vk.as_deref(), Some(PROGRAM_VK));
+}
+
+#[test]
+fn script_tags_read_json_scripts() {
+    let extracted = extract_from_script_tags(NEXT_DATA);
+    assert!(extracted.artifact_url.is_some_and(|url| url.starts_with(ARTIFACT_PREFIX)));
+    assert_eq!(extracted.vk.as_deref(), Some(PROGRAM_VK));
+}
+
+#[test]
+fn json_ignores_hex_values_under_other_keys() {
+    let json = serde_json::json!({ "requestId": REQUEST_ID, "program": { "vk": PROGRAM_VK } });
+    assert_eq!(extract_from_json(&json).vk.as_deref(), Some(PROGRAM_VK));
+}
+
+#[test]
+fn strategies_parse_and_print() {
+    for name in ["json-api", "next-data", "script-tags", "dom"] {
+        assert_eq!(name.parse::<ExtractionStrategy>().unwrap().to_string(), name);
+    }
+    assert!("regex".parse::<ExtractionStrategy>().is_err());
+}
+
+#[test]
+fn request_list_reads_each_row_once() {
+    let requests = extract_request_list(include_str!("fixtures/explorer/program_list.html"));
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].request_id, REQUEST_ID);
+    assert_eq!(requests[0].status.as_deref(), Some("Fulfilled"));
+    assert_eq!(requests[0].created.as_deref(), Some("Aug 29, 2025, 10:18:43 AM"));
+    assert_eq!(requests[1].request_id, "0x9f0c2b1e4c1d4bb0a1d5b8c7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b1c0d9e8f7");
+    assert_eq!(requests[1].status.as_deref(), Some("Assigned"));
+    assert_eq!(requests[1].created.as_deref(), Some("5 minutes ago"));
+}
+
+#[test]
+fn dom_keeps_the_other_details_rows() {
+    let fields = extract_from_dom(RENDERED).fields;
+    assert_eq!(fields.get("SP1 Version").map(String::as_str), Some("sp1-v5.0.0"));
+    assert_eq!(fields.get("Request ID").map(String::as_str), Some(REQUEST_ID));
+}
+
+#[test]
+fn next_data_keeps_the_fields_of_the_request() {
+    let fields = extract_from_next_data(NEXT_DATA).fields;
+    assert_eq!(fields.get("requestId").map(String::as_str), Some(REQUEST_ID));
+}
+
+#[test]
+fn request_details_come_from_the_details_rows_and_summary_cards() {
+    let details = RequestDetails::from_fields(&extract_from_dom(RENDERED).fields);
+    assert_eq!(details.cycles, Some(44_854));
+    assert_eq!(details.gas_used, Some(1_124_095));
+    assert_eq!(details.requester.as_deref(), Some("0x0118029a04b007b08feac3e828c4d5f43123eb19"));
+    assert_eq!(details.proof_mode.as_deref(), Some("Groth16"));
+    assert_eq!(details.fulfilled_at, None);
+}
+
+#[test]
+fn request_details_come_from_json_keys() {
+    let json = serde_json::json!({
+        "request": {
+            "proofUri": format!("{}x-id=GetObject", ARTIFACT_PREFIX),
+            "cyclesUsed": 44854,
+            "gas_used": "1124095",
+            "requester": "0x0118029a04b007b08feac3e828c4d5f43123eb19",
+            "fulfilledAt": "1756462799",
+            "proofMode": "Groth16",
+            "fulfillmentStatus": "FULFILLMENT_STATUS_UNFULFILLABLE",
+        }
+    });
+    let details = RequestDetails::from_fields(&extract_from_json(&json).fields);
+    assert_eq!(details.cycles, Some(44_854));
+    assert_eq!(details.gas_used, Some(1_124_095));
+    assert_eq!(details.fulfilled_at.as_deref(), Some("1756462799"));
+    assert_eq!(details.proof_mode.as_deref(), Some("Groth16"));
+    assert!(details.is_unfulfillable());
+}
@@ -0,0 +1,36 @@
+//! Statements computed off chain against the ones zkVerify reported in `ProofVerified` events,
+//! from the proofs in `tests/fixtures/statement` (see the README there).
+
+use anyhow::Result;
+use rusty_router::converter::ProofConverter;
+use rusty_router::statement::Statement;
+use std::path::Path;
+
+// `NO_VERSION_HASH` of zkVerify's verifiers, the SHA-256 of no bytes
+const NO_VERSION_HASH: &str = "0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+#[tokio::test]
+async fn statements_match_proof_verified_events() -> Result<()> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/statement");
+    let mut checked = 0;
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        let expected = std::fs::read_to_string(path.with_extension("statement"))?;
+        let converted_proof = ProofConverter::new().load_proof(&path).await?;
+        let statement = Statement::of(&converted_proof)?;
+
+        assert_eq!(format!("{:?}", statement.version_hash), NO_VERSION_HASH, "{}", path.display());
+        assert_eq!(format!("{:?}", statement.statement), expected.trim().to_lowercase(), "{}", path.display());
+        checked += 1;
+    }
+    if checked == 0 {
+        eprintln!(
+            "Skipping: no proofs in {}; add one with the statement its ProofVerified event carried",
+            dir.display()
+        );
+    }
+    Ok(())
+}